@@ -0,0 +1,323 @@
+use itertools::Itertools;
+
+pub const ANALYSIS_PATH: &str = "analysis.json";
+
+const TRANSIENT: usize = 2_000;
+const STEPS: usize = 20_000;
+const SAMPLES: usize = 1_000;
+const THEILER_WINDOW: usize = 5;
+
+pub struct FixedPoint {
+    pub position: macroquad::math::Vec3,
+    pub eigenvalues: [(f32, f32); 3],
+}
+
+impl FixedPoint {
+    /// Number of eigenvalues with positive real part.
+    pub fn unstable_directions(&self) -> usize {
+        self.eigenvalues.iter().filter(|(re, _)| *re > 0.0).count()
+    }
+}
+
+pub struct Lobes {
+    pub switches: usize,
+    pub visits: [usize; 2],
+    pub mean_loops: f32,
+    pub max_loops: usize,
+}
+
+pub struct Report {
+    pub system: &'static str,
+    pub params: Vec<(&'static str, f32)>,
+    pub lyapunov: [f32; 3],
+    pub kaplan_yorke: f32,
+    pub correlation_dimension: f32,
+    pub fixed_points: Vec<FixedPoint>,
+    pub lobes: Option<Lobes>,
+}
+
+impl Report {
+    pub fn new(
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+        trail: &[macroquad::math::Vec3],
+    ) -> Self {
+        let lyapunov = crate::lyapunov::spectrum(
+            |p| system.derivative(p, params),
+            |p| system.jacobian(p, params),
+            integrator,
+            system.start(),
+            dt,
+            TRANSIENT,
+            STEPS,
+        );
+        let lorenz = matches!(
+            system,
+            crate::system::System::Lorenz | crate::system::System::FractionalLorenz
+        );
+        Self {
+            system: system.name(),
+            params: system
+                .params()
+                .iter()
+                .zip(params)
+                .map(|(param, value)| (param.name, *value))
+                .collect(),
+            lyapunov,
+            kaplan_yorke: crate::lyapunov::kaplan_yorke(&lyapunov),
+            correlation_dimension: correlation_dimension(trail),
+            fixed_points: fixed_points(system, params, bounds(trail)),
+            lobes: lorenz.then(|| lobes(trail)),
+        }
+    }
+
+    pub fn json(&self) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|(name, value)| format!("\"{name}\": {}", number(*value)))
+            .join(", ");
+        let fixed_points = self
+            .fixed_points
+            .iter()
+            .map(|point| {
+                let eigenvalues = point
+                    .eigenvalues
+                    .iter()
+                    .map(|(re, im)| format!("[{}, {}]", number(*re), number(*im)))
+                    .join(", ");
+                format!(
+                    "\n    {{\"position\": {}, \"eigenvalues\": [{eigenvalues}], \"stable\": {}}}",
+                    vector(point.position),
+                    point.eigenvalues.iter().all(|(re, _)| *re < 0.0)
+                )
+            })
+            .join(",");
+        let lobes = self.lobes.as_ref().map_or("null".to_owned(), |lobes| {
+            format!(
+                "{{\"switches\": {}, \"visits\": [{}, {}], \"mean_loops\": {}, \"max_loops\": {}}}",
+                lobes.switches,
+                lobes.visits[0],
+                lobes.visits[1],
+                number(lobes.mean_loops),
+                lobes.max_loops
+            )
+        });
+        format!(
+            "{{\n  \"system\": \"{}\",\n  \"params\": {{{params}}},\n  \"lyapunov\": [{}],\n  \
+             \"dimension\": {{\"kaplan_yorke\": {}, \"correlation\": {}}},\n  \
+             \"fixed_points\": [{fixed_points}\n  ],\n  \"lobes\": {lobes}\n}}\n",
+            self.system,
+            self.lyapunov.map(number).join(", "),
+            number(self.kaplan_yorke),
+            number(self.correlation_dimension),
+        )
+    }
+}
+
+fn number(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_owned()
+    }
+}
+
+fn vector(v: macroquad::math::Vec3) -> String {
+    format!("[{}, {}, {}]", number(v.x), number(v.y), number(v.z))
+}
+
+fn correlation_dimension(trail: &[macroquad::math::Vec3]) -> f32 {
+    let fit = correlation_sums(trail);
+    if fit.len() < 3 {
+        return f32::NAN;
+    }
+    line_fit(&fit).0
+}
+
+/// Grassberger-Procaccia correlation sums as (ln r, ln C(r)) over radii spanning 1.3 decades
+/// below a hundredth of the trail's diameter, skipping pairs closer in time than the Theiler
+/// window.
+pub fn correlation_sums(trail: &[macroquad::math::Vec3]) -> Vec<(f32, f32)> {
+    let stride = trail.len().div_ceil(SAMPLES).max(1);
+    let samples = trail.iter().step_by(stride).copied().collect::<Vec<_>>();
+    if samples.len() < 4 * THEILER_WINDOW {
+        return Vec::new();
+    }
+    let (min, max) = samples
+        .iter()
+        .fold((samples[0], samples[0]), |(min, max), p| {
+            (min.min(*p), max.max(*p))
+        });
+    let diameter = min.distance(max);
+    let radii = (0..8)
+        .map(|k| diameter * 10.0_f32.powf(-2.0 + k as f32 * 1.3 / 7.0))
+        .collect::<Vec<_>>();
+    let mut counts = vec![0usize; radii.len()];
+    let mut pairs = 0usize;
+    for i in 0..samples.len() {
+        for j in i + THEILER_WINDOW..samples.len() {
+            let d = samples[i].distance(samples[j]);
+            pairs += 1;
+            for (count, r) in counts.iter_mut().zip(&radii) {
+                if d < *r {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    radii
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(r, count)| (r.ln(), (count as f32 / pairs as f32).ln()))
+        .collect()
+}
+
+/// Least-squares slope and intercept.
+pub fn line_fit(fit: &[(f32, f32)]) -> (f32, f32) {
+    let n = fit.len() as f32;
+    let (sx, sy) = fit
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    let (mx, my) = (sx / n, sy / n);
+    let (sxy, sxx) = fit.iter().fold((0.0, 0.0), |(sxy, sxx), (x, y)| {
+        (sxy + (x - mx) * (y - my), sxx + (x - mx) * (x - mx))
+    });
+    let slope = sxy / sxx;
+    (slope, my - slope * mx)
+}
+
+/// Bounding box of the trail and the origin.
+pub fn bounds(trail: &[macroquad::math::Vec3]) -> (macroquad::math::Vec3, macroquad::math::Vec3) {
+    trail.iter().fold(
+        (macroquad::math::Vec3::ZERO, macroquad::math::Vec3::ZERO),
+        |(min, max), p| (min.min(*p), max.max(*p)),
+    )
+}
+
+/// Equilibria found by Newton's method from a grid of seeds across `(min, max)`.
+pub fn fixed_points(
+    system: crate::system::System,
+    params: &[f32],
+    (min, max): (macroquad::math::Vec3, macroquad::math::Vec3),
+) -> Vec<FixedPoint> {
+    let mut seeds = vec![macroquad::math::Vec3::ZERO, system.start()];
+    for (i, j, k) in itertools::iproduct!(0..3, 0..3, 0..3) {
+        let t = macroquad::math::vec3(i as f32, j as f32, k as f32) / 2.0;
+        seeds.push(min + (max - min) * t);
+    }
+
+    let mut points: Vec<FixedPoint> = Vec::new();
+    for seed in seeds {
+        let Some(position) = newton(system, params, seed) else {
+            continue;
+        };
+        if points
+            .iter()
+            .any(|point| point.position.distance(position) < 1e-2 * (1.0 + position.length()))
+        {
+            continue;
+        }
+        points.push(FixedPoint {
+            position,
+            eigenvalues: eigenvalues(system.jacobian(position, params)),
+        });
+    }
+    points.sort_by(|a, b| a.position.x.total_cmp(&b.position.x));
+    points
+}
+
+fn newton(
+    system: crate::system::System,
+    params: &[f32],
+    mut p: macroquad::math::Vec3,
+) -> Option<macroquad::math::Vec3> {
+    for _ in 0..50 {
+        let f = system.derivative(p, params);
+        if f.length() < 1e-4 * (1.0 + p.length()) {
+            return Some(p);
+        }
+        let j = system.jacobian(p, params);
+        if j.determinant().abs() < 1e-9 {
+            return None;
+        }
+        p -= j.inverse() * f;
+        if !p.is_finite() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Roots of the characteristic polynomial as (real, imaginary) pairs.
+pub fn eigenvalues(m: macroquad::math::Mat3) -> [(f32, f32); 3] {
+    let m = m.as_dmat3();
+    let trace = m.x_axis.x + m.y_axis.y + m.z_axis.z;
+    let minors = m.x_axis.x * m.y_axis.y - m.y_axis.x * m.x_axis.y + m.x_axis.x * m.z_axis.z
+        - m.z_axis.x * m.x_axis.z
+        + m.y_axis.y * m.z_axis.z
+        - m.z_axis.y * m.y_axis.z;
+    let (a, b, c) = (-trace, minors, -m.determinant());
+    let shift = -a / 3.0;
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a * a * a / 27.0 - a * b / 3.0 + c;
+    let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+    let roots = if discriminant > 0.0 {
+        let u = (-q / 2.0 + discriminant.sqrt()).cbrt();
+        let v = (-q / 2.0 - discriminant.sqrt()).cbrt();
+        let im = (u - v) * 3.0_f64.sqrt() / 2.0;
+        [
+            (u + v + shift, 0.0),
+            (-(u + v) / 2.0 + shift, im),
+            (-(u + v) / 2.0 + shift, -im),
+        ]
+    } else {
+        let r = (-p / 3.0).sqrt();
+        let phi = if r > 0.0 {
+            (-q / (2.0 * r * r * r)).clamp(-1.0, 1.0).acos()
+        } else {
+            0.0
+        };
+        [0.0, 1.0, 2.0].map(|k| {
+            let angle = (phi - 2.0 * std::f64::consts::PI * k) / 3.0;
+            (2.0 * r * angle.cos() + shift, 0.0)
+        })
+    };
+    let mut roots = roots.map(|(re, im)| (re as f32, im as f32));
+    roots.sort_by(|a, b| b.0.total_cmp(&a.0));
+    roots
+}
+
+fn lobes(trail: &[macroquad::math::Vec3]) -> Lobes {
+    let mut runs = Vec::new();
+    let mut current: Option<(bool, usize)> = None;
+    for (a, b, c) in trail.iter().tuple_windows() {
+        let side = b.x > 0.0;
+        let peak = b.z > a.z && b.z >= c.z;
+        current = match current {
+            Some((s, loops)) if s == side => Some((s, loops + peak as usize)),
+            Some(run) => {
+                runs.push(run);
+                Some((side, peak as usize))
+            }
+            None => Some((side, peak as usize)),
+        };
+    }
+    let switches = runs.len();
+    // The first run started before the trail did, so its loop count is incomplete.
+    let complete = runs.iter().skip(1).collect::<Vec<_>>();
+    let visits = [
+        complete.iter().filter(|(side, _)| !side).count(),
+        complete.iter().filter(|(side, _)| *side).count(),
+    ];
+    let total = complete.iter().map(|(_, loops)| loops).sum::<usize>();
+    Lobes {
+        switches,
+        visits,
+        mean_loops: total as f32 / complete.len() as f32,
+        max_loops: complete.iter().map(|(_, loops)| *loops).max().unwrap_or(0),
+    }
+}
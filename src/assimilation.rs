@@ -0,0 +1,100 @@
+const MAX_OBSERVATIONS: usize = 40;
+
+/// A forecast model that follows the first trajectory only through noisy observations, pulled
+/// toward each one by `strength`.
+pub struct Assimilation {
+    pub enabled: bool,
+    pub noise: f32,
+    pub strength: f32,
+    pub interval: f32,
+    pub model: crate::trajectory::Trajectory,
+    pub observations: std::collections::VecDeque<macroquad::math::Vec3>,
+    pub error: f32,
+    since: f32,
+}
+
+impl Assimilation {
+    pub fn new() -> Self {
+        let mut model = crate::trajectory::Trajectory::new(
+            "model".to_owned(),
+            macroquad::math::Vec3::ZERO,
+            macroquad::color::ORANGE,
+            10.0,
+        );
+        model.solid_color = true;
+        model.thickness = 2.0;
+        Self {
+            enabled: false,
+            noise: 1.0,
+            strength: 0.3,
+            interval: 0.2,
+            model,
+            observations: std::collections::VecDeque::new(),
+            error: 0.0,
+            since: 0.0,
+        }
+    }
+
+    /// Restarts the forecast from a perturbed copy of `truth`.
+    pub fn restart(&mut self, truth: macroquad::math::Vec3, rng: &crate::rng::Rng) {
+        self.model.start = truth + gaussian_vector(rng) * self.noise.max(1.0) * 2.0;
+        self.model.reset();
+        self.observations.clear();
+        self.error = self.model.start.distance(truth);
+        self.since = 0.0;
+    }
+
+    pub fn step(
+        &mut self,
+        truth: macroquad::math::Vec3,
+        h: f32,
+        advance: impl Fn(macroquad::math::Vec3, f32) -> macroquad::math::Vec3,
+        rng: &crate::rng::Rng,
+    ) {
+        let next = advance(self.model.head(), h);
+        self.model.push(next);
+        self.since += h;
+        if self.since >= self.interval {
+            self.since -= self.interval;
+            let observation = truth + gaussian_vector(rng) * self.noise;
+            self.observations.push_back(observation);
+            if self.observations.len() > MAX_OBSERVATIONS {
+                self.observations.pop_front();
+            }
+            let nudged = self.model.head().lerp(observation, self.strength);
+            self.model.push(nudged);
+        }
+        if !self.model.head().is_finite() {
+            self.restart(truth, rng);
+        }
+        self.error = self.model.head().distance(truth);
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        style: &crate::trajectory::Style,
+        truth: macroquad::math::Vec3,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        self.model.draw(camera, style, 0.0, &transform);
+        for (i, observation) in self.observations.iter().enumerate() {
+            let alpha = (i + 1) as f32 / self.observations.len() as f32;
+            macroquad::models::draw_sphere(
+                transform(*observation),
+                0.4,
+                None,
+                macroquad::color::SKYBLUE.with_alpha(alpha),
+            );
+        }
+        macroquad::models::draw_line_3d(
+            transform(self.model.head()),
+            transform(truth),
+            macroquad::color::RED,
+        );
+    }
+}
+
+fn gaussian_vector(rng: &crate::rng::Rng) -> macroquad::math::Vec3 {
+    macroquad::math::vec3(rng.gaussian(), rng.gaussian(), rng.gaussian())
+}
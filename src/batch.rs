@@ -0,0 +1,124 @@
+const DT: f32 = 0.005;
+
+pub struct Settings {
+    pub output: String,
+    pub grid: crate::explorer::Grid,
+}
+
+impl Settings {
+    /// Settings read from the `batch-*` keys; an empty `output`, as from a bare `--batch`,
+    /// becomes `batch`.
+    pub fn new(config: &crate::config::Config, output: String) -> Result<Self, String> {
+        let output = if output.is_empty() {
+            "batch".to_owned()
+        } else {
+            output
+        };
+        let name = config
+            .get::<String>("batch-system")
+            .unwrap_or_else(|| "lorenz".to_owned());
+        let system = crate::system::System::all()
+            .into_iter()
+            .find(|s| s.name() == name)
+            .ok_or(format!("unknown system '{name}'"))?;
+        let descriptors = system.params();
+        if descriptors.len() < 2 {
+            return Err(format!(
+                "'{name}' has fewer than two parameters to put on the axes"
+            ));
+        }
+        let axis = |key: &str, default: usize| -> Result<usize, String> {
+            match config.get::<String>(key) {
+                Some(name) => descriptors
+                    .iter()
+                    .position(|p| p.name == name)
+                    .ok_or(format!("unknown parameter '{name}'")),
+                None => Ok(default),
+            }
+        };
+        let axes = (axis("batch-x", 0)?, axis("batch-y", descriptors.len() - 1)?);
+        if axes.0 == axes.1 {
+            return Err(format!(
+                "both axes sweep '{}'; set batch-x and batch-y to different parameters",
+                descriptors[axes.0].name
+            ));
+        }
+        let range = |key: &str, i: usize| -> Result<(f32, f32), String> {
+            let Some(text) = config.get::<String>(key) else {
+                return Ok((descriptors[i].min, descriptors[i].max));
+            };
+            text.split_once(':')
+                .and_then(|(lo, hi)| Some((lo.trim().parse().ok()?, hi.trim().parse().ok()?)))
+                .ok_or(format!("{key}: expected 'min:max', got '{text}'"))
+        };
+        let metric = match config.get::<String>("batch-metric") {
+            Some(key) => crate::explorer::Metric::KEYS
+                .iter()
+                .position(|k| *k == key)
+                .map(|i| crate::explorer::Metric::ALL[i])
+                .ok_or(format!("unknown metric '{key}'"))?,
+            None => crate::explorer::Metric::Lyapunov,
+        };
+        Ok(Self {
+            output,
+            grid: crate::explorer::Grid {
+                system,
+                params: system.defaults(),
+                axes,
+                ranges: [
+                    range("batch-x-range", axes.0)?,
+                    range("batch-y-range", axes.1)?,
+                ],
+                resolution: config.get("batch-resolution").unwrap_or(64).max(2),
+                metric,
+                integrator: crate::integrator::Integrator::Rk4,
+                dt: DT,
+            },
+        })
+    }
+}
+
+/// Computes the grid without opening a window and writes `<output>.csv` and `<output>.png`.
+pub fn run(settings: &Settings) -> Result<(), String> {
+    let grid = &settings.grid;
+    let n = grid.resolution;
+    let values = std::sync::Arc::new(std::sync::Mutex::new(vec![f32::NAN; n * n]));
+    let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let started = std::time::Instant::now();
+    for worker in grid.spawn(values.clone(), cancel) {
+        worker.join().map_err(|_| "worker panicked".to_owned())?;
+    }
+    let values = values.lock().unwrap();
+
+    let names = grid.system.params();
+    let (x, y) = (names[grid.axes.0].name, names[grid.axes.1].name);
+    let metric = crate::explorer::Metric::KEYS[crate::explorer::Metric::ALL
+        .iter()
+        .position(|m| *m == grid.metric)
+        .unwrap()];
+    let mut csv = format!("{x},{y},{metric}\n");
+    for (i, value) in values.iter().enumerate() {
+        let (column, row) = (i % n, i / n);
+        let a = crate::panel::lerp(grid.ranges[0], column as f32 / (n - 1) as f32);
+        let b = crate::panel::lerp(grid.ranges[1], row as f32 / (n - 1) as f32);
+        csv.push_str(&format!("{a},{b},{value}\n"));
+    }
+    let path = format!("{}.csv", settings.output);
+    std::fs::write(&path, csv).map_err(|err| format!("{path}: {err}"))?;
+
+    let mut image =
+        macroquad::texture::Image::gen_image_color(n as u16, n as u16, macroquad::color::BLACK);
+    crate::explorer::paint(&values, n, crate::palette::Colormap::Rainbow, &mut image);
+    // encode_png expects rows bottom to top, as read back from the GPU.
+    image.bytes = image.bytes.chunks(n * 4).rev().flatten().copied().collect();
+    let path = format!("{}.png", settings.output);
+    let png = crate::image_file::encode_png(&image).map_err(|err| format!("{path}: {err}"))?;
+    std::fs::write(&path, png).map_err(|err| format!("{path}: {err}"))?;
+    eprintln!(
+        "batch: {n}x{n} {metric} over {x} and {y} in {:.1}s, wrote {}.csv and {}.png",
+        started.elapsed().as_secs_f32(),
+        settings.output,
+        settings.output
+    );
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use itertools::Itertools;
+
+const COLUMNS: usize = 240;
+const TRANSIENT: usize = 2_000;
+const STEPS: usize = 6_000;
+const THUMBNAIL_POINTS: usize = 600;
+
+#[derive(Clone, Default)]
+struct Column {
+    maxima: Vec<f32>,
+    thumbnail: Vec<macroquad::math::Vec2>,
+}
+
+pub struct Bifurcation {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub param: usize,
+    /// Sweep range of `param`.
+    pub from: f32,
+    pub to: f32,
+    range: (f32, f32),
+    columns: std::sync::Arc<std::sync::Mutex<Vec<Option<Column>>>>,
+    cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl Bifurcation {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "bifurcation (z maxima)",
+                macroquad::math::Rect::new(0.0, 0.0, 360.0, 200.0),
+            ),
+            param: 2,
+            from: 0.0,
+            to: 0.0,
+            range: (0.0, 1.0),
+            columns: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            cancel: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        }
+    }
+
+    pub fn compute(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) {
+        self.cancel
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        self.columns = std::sync::Arc::new(std::sync::Mutex::new(vec![None; COLUMNS]));
+        self.range = (self.from.min(self.to), self.from.max(self.to));
+
+        let next_column = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        for _ in 0..workers {
+            let (columns, cancel, next_column) = (
+                self.columns.clone(),
+                self.cancel.clone(),
+                next_column.clone(),
+            );
+            let (param, range, mut params) = (self.param, self.range, params.to_vec());
+            std::thread::spawn(move || {
+                loop {
+                    let i = next_column.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    if i >= COLUMNS || cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    params[param] = crate::panel::lerp(range, i as f32 / (COLUMNS - 1) as f32);
+                    let column = compute_column(
+                        |p| system.derivative(p, &params),
+                        integrator,
+                        system.start(),
+                        dt,
+                    );
+                    columns.lock().unwrap()[i] = Some(column);
+                }
+            });
+        }
+    }
+
+    /// Drops the diagram of the previous system and keeps `param` among those of `system`.
+    pub fn switch_system(&mut self, system: crate::system::System) {
+        self.cancel
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.columns = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let count = system.params().len();
+        self.param = self.param.min(count.saturating_sub(1));
+        if count == 0 {
+            self.visible = false;
+        }
+    }
+
+    /// Sweeps `param` across its whole range unless a valid sub-range of it is already set.
+    pub fn select(&mut self, param: usize, descriptor: &crate::system::Param) {
+        let within = |v: f32| (descriptor.min..=descriptor.max).contains(&v);
+        if param != self.param || self.from >= self.to || !within(self.from) || !within(self.to) {
+            self.param = param;
+            self.from = descriptor.min.max(0.0);
+            self.to = descriptor.max;
+        }
+    }
+
+    pub fn draw(&mut self, params: &[f32]) -> Option<f32> {
+        self.panel.rect.x = 280.0;
+        self.panel.rect.y = macroquad::window::screen_height() - self.panel.rect.h - 30.0;
+        let panel = &self.panel;
+        panel.draw_frame("", "z");
+
+        let columns = self.columns.lock().unwrap();
+        let (lo, hi) = columns
+            .iter()
+            .flatten()
+            .flat_map(|c| c.maxima.iter())
+            .fold((f32::MAX, f32::MIN), |(lo, hi), z| (lo.min(*z), hi.max(*z)));
+        for (i, column) in columns.iter().enumerate() {
+            let Some(column) = column else {
+                continue;
+            };
+            let u = i as f32 / (COLUMNS - 1) as f32;
+            for z in column.maxima.iter() {
+                let p = panel.to_screen(macroquad::math::vec2(u, (z - lo) / (hi - lo).max(1e-6)));
+                macroquad::shapes::draw_rectangle(
+                    p.x,
+                    p.y,
+                    1.0,
+                    1.0,
+                    macroquad::color::Color::new(1.0, 1.0, 1.0, 0.6),
+                );
+            }
+        }
+
+        if let Some(value) = params.get(self.param) {
+            let cursor = panel.to_screen(macroquad::math::vec2(
+                crate::panel::unlerp(self.range, *value),
+                0.0,
+            ));
+            macroquad::shapes::draw_line(
+                cursor.x,
+                panel.rect.y,
+                cursor.x,
+                panel.rect.y + panel.rect.h,
+                1.0,
+                macroquad::color::ORANGE,
+            );
+        }
+
+        let mouse = macroquad::input::mouse_position().into();
+        if !panel.contains(mouse) {
+            return None;
+        }
+        let u = panel.to_unit(mouse).x;
+        let value = crate::panel::lerp(self.range, u);
+        macroquad::shapes::draw_line(
+            mouse.x,
+            panel.rect.y,
+            mouse.x,
+            panel.rect.y + panel.rect.h,
+            1.0,
+            macroquad::color::GRAY,
+        );
+        macroquad::text::draw_text(
+            &format!("{value:.3}"),
+            panel.rect.x,
+            panel.rect.y + panel.rect.h + 14.0,
+            16.0,
+            macroquad::color::WHITE,
+        );
+        let i = ((u * (COLUMNS - 1) as f32).round() as usize).min(COLUMNS - 1);
+        if let Some(Some(column)) = columns.get(i) {
+            draw_thumbnail(&column.thumbnail, mouse);
+        }
+        macroquad::input::is_mouse_button_pressed(macroquad::input::MouseButton::Left)
+            .then_some(value)
+    }
+}
+
+fn compute_column(
+    f: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    integrator: crate::integrator::Integrator,
+    start: macroquad::math::Vec3,
+    dt: f32,
+) -> Column {
+    let mut p = start;
+    for _ in 0..TRANSIENT {
+        p = integrator.step(&f, p, dt);
+    }
+    let mut column = Column::default();
+    let (mut previous, mut current) = (p, integrator.step(&f, p, dt));
+    for i in 0..STEPS {
+        let next = integrator.step(&f, current, dt);
+        if !next.is_finite() {
+            break;
+        }
+        if current.z > previous.z && current.z >= next.z {
+            column.maxima.push(current.z);
+        }
+        if i % (STEPS / THUMBNAIL_POINTS) == 0 {
+            column
+                .thumbnail
+                .push(macroquad::math::vec2(current.x, current.z));
+        }
+        (previous, current) = (current, next);
+    }
+    column
+}
+
+fn draw_thumbnail(points: &[macroquad::math::Vec2], mouse: macroquad::math::Vec2) {
+    let Some(first) = points.first() else {
+        return;
+    };
+    let (min, max) = points
+        .iter()
+        .fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p)));
+    let size = 100.0;
+    let origin = macroquad::math::vec2(mouse.x + 10.0, mouse.y - size - 10.0);
+    macroquad::shapes::draw_rectangle(origin.x, origin.y, size, size, macroquad::color::BLACK);
+    macroquad::shapes::draw_rectangle_lines(
+        origin.x,
+        origin.y,
+        size,
+        size,
+        1.0,
+        macroquad::color::GRAY,
+    );
+    let scale = (max - min).max_element().max(1e-6);
+    let to_screen = |p: &macroquad::math::Vec2| {
+        let u = (*p - min) / scale;
+        macroquad::math::vec2(origin.x + u.x * size, origin.y + (1.0 - u.y) * size)
+    };
+    for (a, b) in points.iter().tuple_windows() {
+        let (a, b) = (to_screen(a), to_screen(b));
+        macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::SKYBLUE);
+    }
+}
@@ -0,0 +1,251 @@
+use macroquad::miniquad;
+
+/// Vertex shader for full screen passes through macroquad materials.
+pub const VERTEX: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying lowp vec2 uv;
+uniform mat4 Model;
+uniform mat4 Projection;
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1.0);
+    uv = texcoord;
+}
+"#;
+
+const BRIGHT: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform float threshold;
+void main() {
+    vec4 color = texture2D(Texture, uv);
+    float luma = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+    gl_FragColor = vec4(color.rgb * smoothstep(threshold, threshold + 0.1, luma), 1.0);
+}
+"#;
+
+const BLUR: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform vec2 direction;
+uniform float intensity;
+void main() {
+    vec3 sum = texture2D(Texture, uv).rgb * 0.227;
+    sum += texture2D(Texture, uv + direction * 1.385).rgb * 0.316;
+    sum += texture2D(Texture, uv - direction * 1.385).rgb * 0.316;
+    sum += texture2D(Texture, uv + direction * 3.231).rgb * 0.070;
+    sum += texture2D(Texture, uv - direction * 3.231).rgb * 0.070;
+    gl_FragColor = vec4(sum * intensity, 1.0);
+}
+"#;
+
+/// Bright pass, separable blur and additive composite over the scene.
+struct Passes {
+    bright: macroquad::material::Material,
+    blur: macroquad::material::Material,
+    composite: macroquad::material::Material,
+    scene: macroquad::texture::RenderTarget,
+    ping: macroquad::texture::RenderTarget,
+    pong: macroquad::texture::RenderTarget,
+    size: (u32, u32),
+}
+
+/// Post-processing glow: the scene is rendered offscreen, everything brighter than `threshold`
+/// is blurred at half resolution and added back on top with `intensity`.
+pub struct Bloom {
+    pub enabled: bool,
+    pub threshold: f32,
+    pub intensity: f32,
+    pub radius: f32,
+    passes: Option<Passes>,
+}
+
+impl Bloom {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.5,
+            intensity: 1.5,
+            radius: 3.0,
+            passes: None,
+        }
+    }
+
+    /// The camera to draw the scene with: `camera` redirected into the offscreen target, sized
+    /// to the window.
+    pub fn scene_camera(
+        &mut self,
+        camera: macroquad::camera::Camera3D,
+    ) -> macroquad::camera::Camera3D {
+        let size = (
+            macroquad::window::screen_width() as u32,
+            macroquad::window::screen_height() as u32,
+        );
+        if self.passes.as_ref().is_none_or(|p| p.size != size) {
+            self.passes = passes(size);
+        }
+        let Some(passes) = &self.passes else {
+            return camera;
+        };
+        macroquad::camera::Camera3D {
+            render_target: Some(passes.scene.clone()),
+            ..camera
+        }
+    }
+
+    /// Draws the offscreen scene and its glow to the screen; expects the default camera.
+    pub fn composite(&self) {
+        let Some(passes) = &self.passes else {
+            return;
+        };
+        let (width, height) = (passes.size.0 as f32, passes.size.1 as f32);
+        let screen = macroquad::math::vec2(width, height);
+        macroquad::texture::draw_texture_ex(
+            &passes.scene.texture,
+            0.0,
+            0.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(screen),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+
+        let half = passes.ping.texture.size();
+        let texel = macroquad::math::vec2(1.0 / half.x, 1.0 / half.y) * (self.radius / 3.0);
+        passes.bright.set_uniform("threshold", self.threshold);
+        pass(&passes.bright, &passes.scene.texture, &passes.ping);
+        passes.blur.set_uniform("intensity", 1.0_f32);
+        passes
+            .blur
+            .set_uniform("direction", macroquad::math::vec2(texel.x, 0.0));
+        pass(&passes.blur, &passes.ping.texture, &passes.pong);
+        passes
+            .blur
+            .set_uniform("direction", macroquad::math::vec2(0.0, texel.y));
+        pass(&passes.blur, &passes.pong.texture, &passes.ping);
+
+        passes.composite.set_uniform("intensity", self.intensity);
+        passes
+            .composite
+            .set_uniform("direction", macroquad::math::Vec2::ZERO);
+        macroquad::material::gl_use_material(&passes.composite);
+        macroquad::texture::draw_texture_ex(
+            &passes.ping.texture,
+            0.0,
+            0.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(screen),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        macroquad::material::gl_use_default_material();
+    }
+}
+
+/// Draws `source` over all of `target` through `material`, keeping the orientation.
+pub fn pass(
+    material: &macroquad::material::Material,
+    source: &macroquad::texture::Texture2D,
+    target: &macroquad::texture::RenderTarget,
+) {
+    let size = target.texture.size();
+    macroquad::camera::set_camera(&macroquad::camera::Camera2D {
+        target: size / 2.0,
+        zoom: macroquad::math::vec2(2.0 / size.x, 2.0 / size.y),
+        render_target: Some(target.clone()),
+        ..Default::default()
+    });
+    macroquad::material::gl_use_material(material);
+    macroquad::texture::draw_texture_ex(
+        source,
+        0.0,
+        0.0,
+        macroquad::color::WHITE,
+        macroquad::texture::DrawTextureParams {
+            dest_size: Some(size),
+            ..Default::default()
+        },
+    );
+    macroquad::material::gl_use_default_material();
+    macroquad::camera::set_default_camera();
+}
+
+/// A material for full screen passes, optionally blending additively onto its target.
+pub fn material(
+    fragment: &str,
+    uniforms: &[(&str, miniquad::UniformType)],
+    additive: bool,
+) -> Option<macroquad::material::Material> {
+    let color_blend = additive.then(|| {
+        miniquad::BlendState::new(
+            miniquad::Equation::Add,
+            miniquad::BlendFactor::One,
+            miniquad::BlendFactor::One,
+        )
+    });
+    macroquad::material::load_material(
+        miniquad::ShaderSource::Glsl {
+            vertex: VERTEX,
+            fragment,
+        },
+        macroquad::material::MaterialParams {
+            uniforms: uniforms
+                .iter()
+                .map(|(name, kind)| miniquad::UniformDesc::new(name, *kind))
+                .collect(),
+            pipeline_params: miniquad::PipelineParams {
+                color_blend,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .map_err(|err| eprintln!("material: {err}"))
+    .ok()
+}
+
+/// A linearly filtered render target of at least one pixel.
+pub fn target(width: u32, height: u32, depth: bool) -> macroquad::texture::RenderTarget {
+    let target = macroquad::texture::render_target_ex(
+        width.max(1),
+        height.max(1),
+        macroquad::texture::RenderTargetParams {
+            sample_count: 1,
+            depth,
+        },
+    );
+    target
+        .texture
+        .set_filter(macroquad::texture::FilterMode::Linear);
+    target
+}
+
+fn passes((width, height): (u32, u32)) -> Option<Passes> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let blur_uniforms = [
+        ("direction", miniquad::UniformType::Float2),
+        ("intensity", miniquad::UniformType::Float1),
+    ];
+    Some(Passes {
+        bright: material(
+            BRIGHT,
+            &[("threshold", miniquad::UniformType::Float1)],
+            false,
+        )?,
+        blur: material(BLUR, &blur_uniforms, false)?,
+        composite: material(BLUR, &blur_uniforms, true)?,
+        scene: target(width, height, true),
+        ping: target(width / 2, height / 2, false),
+        pong: target(width / 2, height / 2, false),
+        size: (width, height),
+    })
+}
@@ -0,0 +1,106 @@
+pub const SLOTS: usize = 9;
+pub const KEYS: [macroquad::input::KeyCode; SLOTS] = [
+    macroquad::input::KeyCode::Key1,
+    macroquad::input::KeyCode::Key2,
+    macroquad::input::KeyCode::Key3,
+    macroquad::input::KeyCode::Key4,
+    macroquad::input::KeyCode::Key5,
+    macroquad::input::KeyCode::Key6,
+    macroquad::input::KeyCode::Key7,
+    macroquad::input::KeyCode::Key8,
+    macroquad::input::KeyCode::Key9,
+];
+const TRANSITION: f32 = 1.0;
+
+/// Saved viewpoints in numbered slots, kept in the config file as `bookmark-<slot>` entries
+/// of distance, yaw, pitch and target. Recalling one glides the camera there.
+pub struct Bookmarks {
+    pub slots: [Option<crate::camera_path::Keyframe>; SLOTS],
+    transition: Option<(crate::camera_path::CameraPath, f32)>,
+}
+
+impl Bookmarks {
+    pub fn new(config: &crate::config::Config) -> Self {
+        let slots = std::array::from_fn(|i| {
+            let key = key(i);
+            let text = config.get::<String>(&key)?;
+            let values = text
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<f32>, _>>();
+            let Ok(&[distance, yaw, pitch, x, y, z]) = values.as_deref() else {
+                eprintln!("config: invalid bookmark '{text}' for '{key}'");
+                return None;
+            };
+            Some(crate::camera_path::Keyframe {
+                time: 0.0,
+                distance,
+                yaw,
+                pitch,
+                target: macroquad::math::vec3(x, y, z),
+            })
+        });
+        Self {
+            slots,
+            transition: None,
+        }
+    }
+
+    pub fn save(&mut self, slot: usize, camera: &crate::camera::OrbitCamera) {
+        let k = keyframe(camera, 0.0);
+        self.slots[slot] = Some(k);
+        crate::config::store(
+            &key(slot),
+            &format!(
+                "{} {} {} {} {} {}",
+                k.distance, k.yaw, k.pitch, k.target.x, k.target.y, k.target.z
+            ),
+        );
+    }
+
+    /// Starts gliding from `camera` to the bookmark in `slot`, if there is one.
+    pub fn recall(&mut self, slot: usize, camera: &crate::camera::OrbitCamera) {
+        let Some(bookmark) = self.slots[slot] else {
+            return;
+        };
+        let mut start = keyframe(camera, 0.0);
+        // Turn the short way round even after the turntable has wound the yaw up.
+        start.yaw = bookmark.yaw
+            + (start.yaw - bookmark.yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        let end = crate::camera_path::Keyframe {
+            time: TRANSITION,
+            ..bookmark
+        };
+        let path = crate::camera_path::CameraPath {
+            keyframes: vec![start, end],
+        };
+        self.transition = Some((path, 0.0));
+    }
+
+    /// Moves `camera` along a running transition by `dt` seconds.
+    pub fn update(&mut self, camera: &mut crate::camera::OrbitCamera, dt: f32) {
+        let Some((path, time)) = &mut self.transition else {
+            return;
+        };
+        *time += dt;
+        path.sample(*time, camera);
+        if *time >= path.duration() {
+            self.transition = None;
+        }
+    }
+}
+
+fn keyframe(camera: &crate::camera::OrbitCamera, time: f32) -> crate::camera_path::Keyframe {
+    crate::camera_path::Keyframe {
+        time,
+        distance: camera.distance,
+        yaw: camera.yaw,
+        pitch: camera.pitch,
+        target: camera.target,
+    }
+}
+
+fn key(slot: usize) -> String {
+    format!("bookmark-{}", slot + 1)
+}
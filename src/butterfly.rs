@@ -0,0 +1,61 @@
+/// Two trails started `epsilon` apart: their separation and the time it took to become
+/// visible, meaning larger than `threshold`.
+pub struct Butterfly {
+    pub enabled: bool,
+    pub log_epsilon: f32,
+    pub threshold: f32,
+    pub separation: f64,
+    pub time: f32,
+    pub diverged_at: Option<f32>,
+}
+
+impl Butterfly {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            log_epsilon: -5.0,
+            threshold: 1.0,
+            separation: 0.0,
+            time: 0.0,
+            diverged_at: None,
+        }
+    }
+
+    pub fn epsilon(&self) -> f32 {
+        10.0_f32.powf(self.log_epsilon)
+    }
+
+    pub fn restart(&mut self, threshold: f32) {
+        self.threshold = threshold;
+        self.separation = self.epsilon() as f64;
+        self.time = 0.0;
+        self.diverged_at = None;
+    }
+
+    pub fn observe(&mut self, separation: f64, elapsed: f32) {
+        self.time += elapsed;
+        self.separation = separation;
+        if self.diverged_at.is_none() && separation > self.threshold as f64 {
+            self.diverged_at = Some(self.time);
+        }
+    }
+
+    pub fn draw(&self) {
+        let diverged = match self.diverged_at {
+            Some(t) => format!("visibly apart after t = {t:.2}"),
+            None => format!("still within {:.2}", self.threshold),
+        };
+        let text = format!(
+            "separation {:.3e} at t = {:.2}, {diverged}",
+            self.separation, self.time
+        );
+        let width = macroquad::text::measure_text(&text, None, 24, 1.0).width;
+        macroquad::text::draw_text(
+            &text,
+            (macroquad::window::screen_width() - width) / 2.0,
+            56.0,
+            24.0,
+            macroquad::color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,268 @@
+const PITCH_LIMIT: f32 = std::f32::consts::FRAC_PI_2 - 0.1;
+/// Seconds for coasting rotation and panning to slow down to about a third.
+const COAST: f32 = 0.3;
+/// Seconds for an eased zoom to cover about two thirds of the way.
+const ZOOM_EASE: f32 = 0.1;
+
+/// How mouse and keyboard input moves the camera. Both drive the same target, distance and
+/// angles, so camera paths, views and sync work the same in either.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Controller {
+    /// Drag to orbit around the target, right drag to pan, wheel to zoom.
+    Orbit,
+    /// Drag to look around, WASD to move, Q and E to sink and rise, Shift to hurry and the
+    /// wheel to change the speed.
+    Fly,
+}
+
+impl Controller {
+    pub const ALL: [Controller; 2] = [Controller::Orbit, Controller::Fly];
+    pub const NAMES: [&'static str; 2] = ["orbit", "fly (WASD)"];
+}
+
+#[derive(Clone)]
+pub struct OrbitCamera {
+    pub controller: Controller,
+    /// Fly speed in scene units per second.
+    pub fly_speed: f32,
+    /// Keep orbiting on its own at `turntable_speed` degrees per second, except while dragged.
+    pub turntable: bool,
+    pub turntable_speed: f32,
+    /// Move the target after the newest trail point, lagging `follow_lag` seconds behind.
+    pub follow: bool,
+    pub follow_lag: f32,
+    /// Let orbiting and panning coast to a stop after the mouse is released and ease zooming,
+    /// instead of following the mouse directly.
+    pub inertia: bool,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub sensitivity: f32,
+    pub pan_sensitivity: f32,
+    pub target: macroquad::math::Vec3,
+    last_left_mouse: Option<macroquad::math::Vec2>,
+    last_right_mouse: Option<macroquad::math::Vec2>,
+    /// Yaw and pitch per second of the last drag.
+    spin: macroquad::math::Vec2,
+    /// Target movement per second of the last pan.
+    pan: macroquad::math::Vec3,
+    /// Distance an eased zoom is heading for.
+    zoom: Option<f32>,
+}
+
+impl OrbitCamera {
+    pub fn new(distance: f32) -> Self {
+        Self {
+            controller: Controller::Orbit,
+            fly_speed: 20.0,
+            turntable: false,
+            turntable_speed: 10.0,
+            follow: false,
+            follow_lag: 0.5,
+            inertia: true,
+            distance,
+            yaw: 0.0,
+            pitch: 0.0,
+            sensitivity: 0.005,
+            pan_sensitivity: 0.001,
+            target: macroquad::math::vec3(0.0, 0.0, 0.0),
+            last_left_mouse: None,
+            last_right_mouse: None,
+            spin: macroquad::math::Vec2::ZERO,
+            pan: macroquad::math::Vec3::ZERO,
+            zoom: None,
+        }
+    }
+
+    /// Takes over how `other` is controlled, while keeping this camera's view.
+    pub fn keep_controls(&mut self, other: &OrbitCamera) {
+        self.controller = other.controller;
+        self.fly_speed = other.fly_speed;
+        self.turntable = other.turntable;
+        self.turntable_speed = other.turntable_speed;
+        self.follow = other.follow;
+        self.follow_lag = other.follow_lag;
+        self.inertia = other.inertia;
+    }
+
+    /// Applies this frame's mouse input, and the fly keys unless `keyboard` is false because
+    /// the keys are going to a text field.
+    pub fn update(&mut self, keyboard: bool) {
+        match self.controller {
+            Controller::Orbit => self.update_orbit(),
+            Controller::Fly => self.update_fly(keyboard),
+        }
+    }
+
+    /// Moves the orbiting camera by `dt` seconds of turntable rotation, coasting and easing
+    /// zoom, when they are on.
+    pub fn advance(&mut self, dt: f32) {
+        use macroquad::input::{MouseButton, is_mouse_button_down};
+        if self.controller != Controller::Orbit {
+            return;
+        }
+        let rotating = is_mouse_button_down(MouseButton::Left);
+        let panning = is_mouse_button_down(MouseButton::Right);
+        if self.turntable && !rotating && !panning {
+            self.yaw += self.turntable_speed.to_radians() * dt;
+        }
+        if !self.inertia {
+            return;
+        }
+
+        let decay = (-dt / COAST).exp();
+        if !rotating {
+            self.yaw += self.spin.x * dt;
+            self.pitch = (self.pitch + self.spin.y * dt).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+            self.spin *= decay;
+        }
+        if !panning {
+            self.target += self.pan * dt;
+            self.pan *= decay;
+        }
+        if let Some(zoom) = self.zoom {
+            self.distance += (zoom - self.distance) * (1.0 - (-dt / ZOOM_EASE).exp());
+            if (zoom - self.distance).abs() < 1e-3 {
+                self.distance = zoom;
+                self.zoom = None;
+            }
+        }
+    }
+
+    /// Drops any coasting and eased zoom, for when the camera is placed from elsewhere.
+    pub fn stop(&mut self) {
+        self.spin = macroquad::math::Vec2::ZERO;
+        self.pan = macroquad::math::Vec3::ZERO;
+        self.zoom = None;
+    }
+
+    /// Eases the target towards `point` over `dt` seconds, when following is on.
+    pub fn track(&mut self, point: macroquad::math::Vec3, dt: f32) {
+        if !self.follow || self.controller != Controller::Orbit {
+            return;
+        }
+        let blend = 1.0 - (-dt / self.follow_lag.max(1e-3)).exp();
+        self.target = self.target.lerp(point, blend);
+    }
+
+    /// Turns yaw and pitch by dragging with the left button.
+    fn drag_angles(&mut self) {
+        if macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Left) {
+            let mouse = macroquad::input::mouse_position().into();
+            if let Some(last) = self.last_left_mouse {
+                let delta: macroquad::math::Vec2 = mouse - last;
+                self.yaw -= delta.x * self.sensitivity;
+                self.pitch += delta.y * self.sensitivity;
+                self.pitch = self.pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT);
+            }
+            self.last_left_mouse = Some(mouse);
+        } else {
+            self.last_left_mouse = None;
+        }
+    }
+
+    fn update_orbit(&mut self) {
+        let dt = macroquad::time::get_frame_time().max(1e-4);
+        let (yaw, pitch, target) = (self.yaw, self.pitch, self.target);
+        self.drag_angles();
+
+        if macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Right) {
+            let mouse = macroquad::input::mouse_position().into();
+            if let Some(last) = self.last_right_mouse {
+                let forward = (self.target - self.get_position()).normalize();
+                let right = forward
+                    .cross(macroquad::math::vec3(0.0, 1.0, 0.0))
+                    .normalize();
+                let up = right.cross(forward).normalize();
+                let delta: macroquad::math::Vec2 = mouse - last;
+                self.target -= right * delta.x * self.pan_sensitivity * self.distance;
+                self.target += up * delta.y * self.pan_sensitivity * self.distance;
+            }
+            self.last_right_mouse = Some(mouse);
+        } else {
+            self.last_right_mouse = None;
+        }
+
+        // Holding the button still stops the coasting, so only a flick carries on.
+        if self.last_left_mouse.is_some() {
+            let spin = macroquad::math::vec2(self.yaw - yaw, self.pitch - pitch) / dt;
+            self.spin = self.spin.lerp(spin, 0.5);
+        }
+        if self.last_right_mouse.is_some() {
+            self.pan = self.pan.lerp((self.target - target) / dt, 0.5);
+        }
+
+        let wheel = macroquad::input::mouse_wheel().1;
+        if !self.inertia {
+            self.distance -= wheel * 5.0;
+            self.distance = self.distance.clamp(1.0, 200.0);
+        } else if wheel != 0.0 {
+            let zoom = self.zoom.unwrap_or(self.distance) - wheel * 5.0;
+            self.zoom = Some(zoom.clamp(1.0, 200.0));
+        }
+    }
+
+    fn update_fly(&mut self, keyboard: bool) {
+        use macroquad::input::{KeyCode, is_key_down};
+        let position = self.get_position();
+        self.drag_angles();
+        // Looking around turns the view about the eye rather than the target.
+        self.target += position - self.get_position();
+
+        let is_key_down = |key| keyboard && is_key_down(key);
+        let axis = |positive: KeyCode, negative: KeyCode| {
+            is_key_down(positive) as i32 as f32 - is_key_down(negative) as i32 as f32
+        };
+        let forward = (self.target - position).normalize();
+        let up = macroquad::math::vec3(0.0, 1.0, 0.0);
+        let right = forward.cross(up).normalize();
+        let direction = forward * axis(KeyCode::W, KeyCode::S)
+            + right * axis(KeyCode::D, KeyCode::A)
+            + up * axis(KeyCode::E, KeyCode::Q);
+        let hurry = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+            4.0
+        } else {
+            1.0
+        };
+        self.target += direction.normalize_or_zero()
+            * self.fly_speed
+            * hurry
+            * macroquad::time::get_frame_time();
+
+        self.fly_speed *= 1.1_f32.powf(macroquad::input::mouse_wheel().1.signum());
+        self.fly_speed = self.fly_speed.clamp(0.5, 500.0);
+    }
+
+    pub fn get_position(&self) -> macroquad::math::Vec3 {
+        let x = self.distance * self.pitch.cos() * self.yaw.sin();
+        let y = self.distance * self.pitch.sin();
+        let z = self.distance * self.pitch.cos() * self.yaw.cos();
+        self.target + macroquad::math::vec3(x, y, z)
+    }
+
+    pub fn get_camera(&self) -> macroquad::camera::Camera3D {
+        macroquad::camera::Camera3D {
+            position: self.get_position(),
+            up: macroquad::math::vec3(0.0, 1.0, 0.0),
+            target: self.target,
+            ..Default::default()
+        }
+    }
+}
+
+/// Origin and unit direction of the ray through screen position `mouse`, from the inverse of
+/// the camera's view-projection matrix.
+pub fn mouse_ray(
+    camera: &macroquad::camera::Camera3D,
+    mouse: macroquad::math::Vec2,
+) -> (macroquad::math::Vec3, macroquad::math::Vec3) {
+    use macroquad::camera::Camera;
+    let ndc = macroquad::math::vec2(
+        2.0 * mouse.x / macroquad::window::screen_width() - 1.0,
+        1.0 - 2.0 * mouse.y / macroquad::window::screen_height(),
+    );
+    let inverse = camera.matrix().inverse();
+    let near = inverse.project_point3(ndc.extend(-1.0));
+    let far = inverse.project_point3(ndc.extend(1.0));
+    (near, (far - near).normalize_or_zero())
+}
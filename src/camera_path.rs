@@ -0,0 +1,94 @@
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    pub time: f32,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub target: macroquad::math::Vec3,
+}
+
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        Self {
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, camera: &crate::camera::OrbitCamera, spacing: f32) {
+        let time = self.keyframes.last().map_or(0.0, |k| k.time + spacing);
+        self.keyframes.push(Keyframe {
+            time,
+            distance: camera.distance,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            target: camera.target,
+        });
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    pub fn sample(&self, time: f32, camera: &mut crate::camera::OrbitCamera) {
+        let Some(first) = self.keyframes.first() else {
+            return;
+        };
+        let i = self.keyframes.partition_point(|k| k.time <= time);
+        let (a, b) = match (i.checked_sub(1), self.keyframes.get(i)) {
+            (Some(previous), Some(next)) => (self.keyframes[previous], *next),
+            (Some(previous), None) => (self.keyframes[previous], self.keyframes[previous]),
+            (None, _) => (*first, *first),
+        };
+        let t = ((time - a.time) / (b.time - a.time).max(f32::EPSILON)).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        camera.stop();
+        camera.distance = a.distance + (b.distance - a.distance) * t;
+        camera.yaw = a.yaw + (b.yaw - a.yaw) * t;
+        camera.pitch = a.pitch + (b.pitch - a.pitch) * t;
+        camera.target = a.target.lerp(b.target, t);
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut text = "# time distance yaw pitch target.x target.y target.z\n".to_owned();
+        for k in self.keyframes.iter() {
+            text += &format!(
+                "{} {} {} {} {} {} {}\n",
+                k.time, k.distance, k.yaw, k.pitch, k.target.x, k.target.y, k.target.z
+            );
+        }
+        std::fs::write(path, text)
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut keyframes = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<f32>, _>>();
+            let Ok(&[time, distance, yaw, pitch, x, y, z]) = values.as_deref() else {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid keyframe '{line}'"),
+                ));
+            };
+            keyframes.push(Keyframe {
+                time,
+                distance,
+                yaw,
+                pitch,
+                target: macroquad::math::vec3(x, y, z),
+            });
+        }
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(Self { keyframes })
+    }
+}
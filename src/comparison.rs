@@ -0,0 +1,62 @@
+/// A copy of the first trajectory advanced by a second integrator with the same steps, so the
+/// gap between the two trails shows the numerical error directly.
+pub struct Comparison {
+    pub enabled: bool,
+    pub integrator: crate::integrator::Integrator,
+    pub model: crate::trajectory::Trajectory,
+    pub error: f32,
+}
+
+impl Comparison {
+    pub fn new() -> Self {
+        let mut model = crate::trajectory::Trajectory::new(
+            "comparison".to_owned(),
+            macroquad::math::Vec3::ZERO,
+            macroquad::color::MAGENTA,
+            10.0,
+        );
+        model.solid_color = true;
+        model.thickness = 2.0;
+        Self {
+            enabled: false,
+            integrator: crate::integrator::Integrator::Euler,
+            model,
+            error: 0.0,
+        }
+    }
+
+    pub fn restart(&mut self, start: macroquad::math::Vec3) {
+        self.model.start = start;
+        self.model.reset();
+        self.error = 0.0;
+    }
+
+    pub fn step(
+        &mut self,
+        truth: macroquad::math::Vec3,
+        advance: impl Fn(macroquad::math::DVec3) -> macroquad::math::DVec3,
+    ) {
+        let next = advance(self.model.head64());
+        if !next.is_finite() {
+            self.restart(truth);
+            return;
+        }
+        self.model.push64(next);
+        self.error = self.model.head().distance(truth);
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        style: &crate::trajectory::Style,
+        truth: macroquad::math::Vec3,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        self.model.draw(camera, style, 0.0, &transform);
+        macroquad::models::draw_line_3d(
+            transform(self.model.head()),
+            transform(truth),
+            macroquad::color::RED,
+        );
+    }
+}
@@ -0,0 +1,152 @@
+const PATH: &str = "lorenz.cfg";
+
+pub struct Config {
+    values: std::collections::BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let text = std::fs::read_to_string(PATH).unwrap_or_default();
+        Self::parse(&text, std::env::args().skip(1))
+    }
+
+    /// Reads `key = value` lines from `text`, then `--key value` or `--key=value` arguments
+    /// on top of them.
+    pub fn parse(text: &str, args: impl Iterator<Item = String>) -> Self {
+        let mut values = std::collections::BTreeMap::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => {
+                    values.insert(key.trim().to_owned(), value.trim().to_owned());
+                }
+                None => eprintln!("config: ignoring line '{line}'"),
+            }
+        }
+
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                eprintln!("config: ignoring argument '{arg}'");
+                continue;
+            };
+            match key.split_once('=') {
+                Some((key, value)) => {
+                    values.insert(key.to_owned(), value.to_owned());
+                }
+                None => {
+                    // A flag followed by another one, like a bare `--batch`, has no value.
+                    let value = args.next_if(|next| !next.starts_with("--"));
+                    values.insert(key.to_owned(), value.unwrap_or_default());
+                }
+            }
+        }
+
+        Self { values }
+    }
+
+    pub fn get<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+        let value = self.values.get(key)?;
+        let parsed = value.parse().ok();
+        if parsed.is_none() {
+            eprintln!("config: invalid value '{value}' for '{key}'");
+        }
+        parsed
+    }
+}
+
+/// Sets `key` to `value` in the config file, keeping its other lines, so the choice is
+/// remembered across runs.
+pub fn store(key: &str, value: &str) {
+    store_all(&[(key, value)]);
+}
+
+/// Like `store` for several entries, reading and writing the file once.
+pub fn store_all(entries: &[(&str, &str)]) {
+    let mut text = std::fs::read_to_string(PATH).unwrap_or_default();
+    for (key, value) in entries {
+        text = replace(&text, key, value);
+    }
+    if let Err(err) = std::fs::write(PATH, text) {
+        eprintln!("config: {err}");
+    }
+}
+
+/// `text` with the first `key` line set to `value` and any later ones dropped, or with the
+/// entry appended.
+fn replace(text: &str, key: &str, value: &str) -> String {
+    let entry = format!("{key} = {value}");
+    let mut found = false;
+    let mut lines = text
+        .lines()
+        .filter_map(|line| {
+            let matches = !line.trim_start().starts_with('#')
+                && line.split_once('=').is_some_and(|(k, _)| k.trim() == key);
+            if !matches {
+                return Some(line.to_owned());
+            }
+            (!std::mem::replace(&mut found, true)).then(|| entry.clone())
+        })
+        .collect::<Vec<_>>();
+    if !found {
+        lines.push(entry);
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn file_lines() {
+        let config = Config::parse(
+            "# comment\n\n  seed = 42  \ntheme=Blueprint\nnot a setting\nscript = a=b.txt\n",
+            args(&[]),
+        );
+        assert_eq!(config.get::<u64>("seed"), Some(42));
+        assert_eq!(config.get::<String>("theme").as_deref(), Some("Blueprint"));
+        assert_eq!(config.get::<String>("script").as_deref(), Some("a=b.txt"));
+        assert_eq!(config.get::<String>("not a setting"), None);
+    }
+
+    #[test]
+    fn arguments_override_the_file() {
+        let config = Config::parse(
+            "msaa = 4\nseed = 1\n",
+            args(&["--batch", "--msaa", "8", "--seed=7", "stray", "--script"]),
+        );
+        assert_eq!(config.get::<u32>("msaa"), Some(8));
+        assert_eq!(config.get::<u64>("seed"), Some(7));
+        assert_eq!(config.get::<String>("batch").as_deref(), Some(""));
+        assert_eq!(config.get::<String>("script").as_deref(), Some(""));
+        assert_eq!(config.get::<String>("stray"), None);
+    }
+
+    #[test]
+    fn invalid_values() {
+        let config = Config::parse("msaa = lots\n", args(&[]));
+        assert_eq!(config.get::<u32>("msaa"), None);
+        assert_eq!(config.get::<String>("msaa").as_deref(), Some("lots"));
+    }
+
+    #[test]
+    fn replacing_entries() {
+        let text = "# theme = Dark\ntheme = Dark\nseed = 1\ntheme = Paper white\n";
+        assert_eq!(
+            replace(text, "theme", "Blueprint"),
+            "# theme = Dark\ntheme = Blueprint\nseed = 1\n"
+        );
+        assert_eq!(replace("seed = 1", "msaa", "8"), "seed = 1\nmsaa = 8\n");
+        assert_eq!(replace("", "msaa", "8"), "msaa = 8\n");
+    }
+}
@@ -0,0 +1,167 @@
+const LOG_LINES: usize = 200;
+
+pub enum Command {
+    Set(String, f32),
+    Reset,
+    Pause,
+    Resume,
+    System(String),
+    View(f32, f32),
+    Distance(f32),
+    Speed(f32),
+    Seed(u64),
+    Run(String),
+    ExportAnalysis,
+    Help,
+}
+
+pub const HELP: &str = "set <param> <value>; reset(); pause; resume; system <name>; \
+camera.top() / camera.front() / camera.side(); camera.distance <d>; speed <v>; seed <n>; \
+run <file>; export_analysis";
+
+pub fn parse(text: &str) -> Result<Vec<Command>, String> {
+    let mut commands = Vec::new();
+    for statement in text.split([';', '\n']).map(str::trim) {
+        if statement.is_empty() || statement.starts_with('#') {
+            continue;
+        }
+        let tokens = statement
+            .replace(['(', ')', ','], " ")
+            .split_whitespace()
+            .map(|t| t.trim_matches('"').to_owned())
+            .collect::<Vec<_>>();
+        let Some(name) = tokens.first() else {
+            continue;
+        };
+        let number = |i: usize| -> Result<f32, String> {
+            let token = tokens
+                .get(i)
+                .ok_or(format!("'{statement}': missing value"))?;
+            token
+                .parse()
+                .map_err(|_| format!("'{statement}': invalid number '{token}'"))
+        };
+        let word = |i: usize| -> Result<String, String> {
+            tokens
+                .get(i)
+                .cloned()
+                .ok_or(format!("'{statement}': missing argument"))
+        };
+        let command = match name.as_str() {
+            "set" => Command::Set(word(1)?, number(2)?),
+            "reset" => Command::Reset,
+            "pause" => Command::Pause,
+            "resume" => Command::Resume,
+            "system" => Command::System(tokens[1..].join(" ")),
+            "camera.top" => Command::View(0.0, std::f32::consts::FRAC_PI_2 - 0.1),
+            "camera.front" => Command::View(0.0, 0.0),
+            "camera.side" => Command::View(std::f32::consts::FRAC_PI_2, 0.0),
+            "camera.distance" => Command::Distance(number(1)?),
+            "speed" => Command::Speed(number(1)?),
+            "seed" => Command::Seed(
+                word(1)?
+                    .parse()
+                    .map_err(|_| format!("'{statement}': invalid seed"))?,
+            ),
+            "run" => Command::Run(word(1)?),
+            "export_analysis" => Command::ExportAnalysis,
+            "help" => Command::Help,
+            other => return Err(format!("unknown command '{other}'")),
+        };
+        commands.push(command);
+    }
+    Ok(commands)
+}
+
+pub struct Console {
+    pub open: bool,
+    input: String,
+    log: std::collections::VecDeque<String>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            log: std::collections::VecDeque::from(["type 'help' for commands".to_owned()]),
+        }
+    }
+
+    pub fn print(&mut self, line: String) {
+        self.log.push_back(line);
+        if self.log.len() > LOG_LINES {
+            self.log.pop_front();
+        }
+    }
+
+    pub fn draw(&mut self) -> Option<String> {
+        if macroquad::input::is_key_pressed(macroquad::input::KeyCode::GraveAccent) {
+            self.open = !self.open;
+        }
+        if !self.open {
+            return None;
+        }
+        let mut entered = None;
+        macroquad::ui::root_ui().window(
+            300,
+            macroquad::math::vec2(0.0, 0.0),
+            macroquad::math::vec2(macroquad::window::screen_width(), 220.0),
+            |ui| {
+                for line in self.log.iter().skip(self.log.len().saturating_sub(10)) {
+                    ui.label(None, line);
+                }
+                ui.input_text(301, ">", &mut self.input);
+                self.input.retain(|c| c != '`');
+                if macroquad::input::is_key_pressed(macroquad::input::KeyCode::Enter)
+                    && !self.input.trim().is_empty()
+                {
+                    entered = Some(std::mem::take(&mut self.input));
+                }
+            },
+        );
+        entered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements() {
+        let commands =
+            parse("set rho 99.96; reset()\ncamera.top(); system \"double pendulum\"").unwrap();
+        assert!(matches!(&commands[0], Command::Set(name, v) if name == "rho" && *v == 99.96));
+        assert!(matches!(commands[1], Command::Reset));
+        assert!(matches!(commands[2], Command::View(0.0, _)));
+        assert!(matches!(&commands[3], Command::System(name) if name == "double pendulum"));
+        assert_eq!(commands.len(), 4);
+    }
+
+    #[test]
+    fn arguments() {
+        let commands = parse("camera.distance(80); speed 2.5; seed 42; run demo.txt").unwrap();
+        assert!(matches!(commands[0], Command::Distance(80.0)));
+        assert!(matches!(commands[1], Command::Speed(2.5)));
+        assert!(matches!(commands[2], Command::Seed(42)));
+        assert!(matches!(&commands[3], Command::Run(path) if path == "demo.txt"));
+    }
+
+    #[test]
+    fn comments_and_blank_statements() {
+        let commands = parse("# setup\n\n;; pause ;\n  resume").unwrap();
+        assert!(matches!(commands[..], [Command::Pause, Command::Resume]));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text| parse(text).err().unwrap();
+        assert_eq!(error("jump"), "unknown command 'jump'");
+        assert_eq!(error("set rho"), "'set rho': missing value");
+        assert_eq!(error("set"), "'set': missing argument");
+        assert_eq!(error("speed fast"), "'speed fast': invalid number 'fast'");
+        assert_eq!(error("seed -1"), "'seed -1': invalid seed");
+        assert_eq!(error("reset; oops"), "unknown command 'oops'");
+    }
+}
@@ -0,0 +1,66 @@
+/// Grassberger-Procaccia estimate of the correlation dimension of the first trail, computed on
+/// demand: the slope of ln C(r) against ln r.
+pub struct CorrelationDimension {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub sums: Vec<(f32, f32)>,
+    pub dimension: f32,
+    intercept: f32,
+}
+
+impl CorrelationDimension {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "correlation dimension",
+                macroquad::math::Rect::new(780.0, 400.0, 240.0, 160.0),
+            ),
+            sums: Vec::new(),
+            dimension: f32::NAN,
+            intercept: 0.0,
+        }
+    }
+
+    pub fn compute(&mut self, points: &std::collections::VecDeque<macroquad::math::DVec3>) {
+        let trail = points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>();
+        self.sums = crate::analysis::correlation_sums(&trail);
+        (self.dimension, self.intercept) = if self.sums.len() < 3 {
+            (f32::NAN, 0.0)
+        } else {
+            crate::analysis::line_fit(&self.sums)
+        };
+    }
+
+    pub fn draw(&self) {
+        let panel = &self.panel;
+        panel.draw_frame("ln r", "ln C");
+        let (Some(first), Some(last)) = (self.sums.first(), self.sums.last()) else {
+            return;
+        };
+        let xs = (first.0, last.0.max(first.0 + 1e-6));
+        let ys = (first.1, last.1.max(first.1 + 1e-6));
+        let screen = |x: f32, y: f32| {
+            panel.to_screen(macroquad::math::vec2(
+                crate::panel::unlerp(xs, x),
+                crate::panel::unlerp(ys, y),
+            ))
+        };
+        if self.dimension.is_finite() {
+            let a = screen(xs.0, self.intercept + self.dimension * xs.0);
+            let b = screen(xs.1, self.intercept + self.dimension * xs.1);
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::GRAY);
+        }
+        for (x, y) in &self.sums {
+            let p = screen(*x, *y);
+            macroquad::shapes::draw_circle(p.x, p.y, 3.0, macroquad::color::YELLOW);
+        }
+        macroquad::text::draw_text(
+            &format!("D2 = {:.3}", self.dimension),
+            panel.rect.x + 6.0,
+            panel.rect.y + 16.0,
+            16.0,
+            macroquad::color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,96 @@
+const HISTORY: usize = 600;
+
+/// Diffusive coupling between the first two trajectories, x1' = f(x1) + k (x2 - x1) and the same
+/// for x2 unless the coupling is one-way (drive and response).
+pub struct Coupling {
+    pub enabled: bool,
+    pub strength: f32,
+    pub one_way: bool,
+    pub panel: crate::panel::Panel,
+    time: f32,
+    history: std::collections::VecDeque<(f32, f32)>,
+}
+
+impl Coupling {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            strength: 1.0,
+            one_way: false,
+            panel: crate::panel::Panel::new(
+                "synchronization error",
+                macroquad::math::Rect::new(520.0, 430.0, 240.0, 160.0),
+            ),
+            time: 0.0,
+            history: std::collections::VecDeque::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.time = 0.0;
+        self.history.clear();
+    }
+
+    /// One RK4 step of the coupled pair.
+    pub fn step(
+        &self,
+        f: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+        a: macroquad::math::Vec3,
+        b: macroquad::math::Vec3,
+        h: f32,
+    ) -> (macroquad::math::Vec3, macroquad::math::Vec3) {
+        let k = self.strength;
+        let back = if self.one_way { 0.0 } else { k };
+        let g = |a: macroquad::math::Vec3, b: macroquad::math::Vec3| {
+            (f(a) + (b - a) * back, f(b) + (a - b) * k)
+        };
+        let (a1, b1) = g(a, b);
+        let (a2, b2) = g(a + a1 * (h / 2.0), b + b1 * (h / 2.0));
+        let (a3, b3) = g(a + a2 * (h / 2.0), b + b2 * (h / 2.0));
+        let (a4, b4) = g(a + a3 * h, b + b3 * h);
+        (
+            a + (a1 + a2 * 2.0 + a3 * 2.0 + a4) * (h / 6.0),
+            b + (b1 + b2 * 2.0 + b3 * 2.0 + b4) * (h / 6.0),
+        )
+    }
+
+    pub fn record(&mut self, elapsed: f32, distance: f32) {
+        self.time += elapsed;
+        self.history.push_back((self.time, distance));
+        if self.history.len() > HISTORY {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.history.back().map_or(0.0, |(_, d)| *d)
+    }
+
+    pub fn draw(&self) {
+        let panel = &self.panel;
+        panel.draw_frame("t", "|x1 - x2|");
+        let Some(&(start, _)) = self.history.front() else {
+            return;
+        };
+        let range = (-6.0, 2.0);
+        let duration = (self.time - start).max(1e-6);
+        let points = self
+            .history
+            .iter()
+            .map(|(t, d)| {
+                let y = crate::panel::unlerp(range, d.max(1e-6).log10()).clamp(0.0, 1.0);
+                panel.to_screen(macroquad::math::vec2((t - start) / duration, y))
+            })
+            .collect::<Vec<_>>();
+        for (a, b) in points.iter().zip(points.iter().skip(1)) {
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::GREEN);
+        }
+        macroquad::text::draw_text(
+            &format!("distance {:.2e} (log scale)", self.distance()),
+            panel.rect.x + 6.0,
+            panel.rect.y + 16.0,
+            16.0,
+            macroquad::color::WHITE,
+        );
+    }
+}
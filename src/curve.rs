@@ -0,0 +1,137 @@
+const HANDLE: f32 = 5.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Preset {
+    Linear,
+    EaseIn,
+    Plateau,
+    Constant,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Linear,
+        Preset::EaseIn,
+        Preset::Plateau,
+        Preset::Constant,
+    ];
+    pub const NAMES: [&'static str; 4] = ["linear", "ease in", "plateau then drop", "constant"];
+
+    pub fn points(&self) -> Vec<macroquad::math::Vec2> {
+        let points: &[(f32, f32)] = match self {
+            Preset::Linear => &[(0.0, 0.0), (1.0, 1.0)],
+            Preset::EaseIn => &[(0.0, 0.0), (0.5, 0.1), (0.8, 0.45), (1.0, 1.0)],
+            Preset::Plateau => &[(0.0, 0.0), (0.2, 0.0), (0.35, 0.9), (1.0, 1.0)],
+            Preset::Constant => &[(0.0, 1.0), (1.0, 1.0)],
+        };
+        points
+            .iter()
+            .map(|(x, y)| macroquad::math::vec2(*x, *y))
+            .collect()
+    }
+}
+
+pub struct Curve {
+    pub points: Vec<macroquad::math::Vec2>,
+}
+
+impl Curve {
+    pub fn new(preset: Preset) -> Self {
+        Self {
+            points: preset.points(),
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        let i = self.points.partition_point(|p| p.x < t);
+        match (i.checked_sub(1), self.points.get(i)) {
+            (Some(a), Some(b)) => {
+                let a = self.points[a];
+                let u = (t - a.x) / (b.x - a.x).max(f32::EPSILON);
+                a.y + (b.y - a.y) * u
+            }
+            (None, Some(b)) => b.y,
+            (Some(a), None) => self.points[a].y,
+            (None, None) => 1.0,
+        }
+    }
+}
+
+pub struct CurveEditor {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    dragging: Option<usize>,
+}
+
+impl CurveEditor {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "alpha vs age (head at right)",
+                macroquad::math::Rect::new(780.0, 200.0, 200.0, 140.0),
+            ),
+            dragging: None,
+        }
+    }
+
+    pub fn draw(&mut self, curve: &mut Curve) {
+        let panel = &self.panel;
+        panel.draw_frame("age", "alpha");
+        let mouse: macroquad::math::Vec2 = macroquad::input::mouse_position().into();
+        let hovered = curve
+            .points
+            .iter()
+            .position(|p| panel.to_screen(*p).distance(mouse) <= HANDLE * 1.5);
+
+        if panel.contains(mouse) {
+            if macroquad::input::is_mouse_button_pressed(macroquad::input::MouseButton::Left) {
+                self.dragging = hovered.or_else(|| {
+                    let u = panel
+                        .to_unit(mouse)
+                        .clamp(macroquad::math::Vec2::ZERO, macroquad::math::Vec2::ONE);
+                    let i = curve.points.partition_point(|p| p.x < u.x);
+                    curve.points.insert(i, u);
+                    Some(i)
+                });
+            }
+            if macroquad::input::is_mouse_button_pressed(macroquad::input::MouseButton::Right)
+                && let Some(i) = hovered
+                && i != 0
+                && i != curve.points.len() - 1
+            {
+                curve.points.remove(i);
+            }
+        }
+        if !macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Left) {
+            self.dragging = None;
+        }
+        if let Some(i) = self.dragging {
+            let u = panel.to_unit(mouse);
+            let last = curve.points.len() - 1;
+            let x = match i {
+                0 => 0.0,
+                i if i == last => 1.0,
+                i => u.x.clamp(curve.points[i - 1].x, curve.points[i + 1].x),
+            };
+            curve.points[i] = macroquad::math::vec2(x, u.y.clamp(0.0, 1.0));
+        }
+
+        let samples = 64;
+        for k in 0..samples {
+            let (t0, t1) = (k as f32 / samples as f32, (k + 1) as f32 / samples as f32);
+            let a = panel.to_screen(macroquad::math::vec2(t0, curve.sample(t0)));
+            let b = panel.to_screen(macroquad::math::vec2(t1, curve.sample(t1)));
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.5, macroquad::color::SKYBLUE);
+        }
+        for (i, p) in curve.points.iter().enumerate() {
+            let p = panel.to_screen(*p);
+            let color = if Some(i) == hovered || Some(i) == self.dragging {
+                macroquad::color::ORANGE
+            } else {
+                macroquad::color::WHITE
+            };
+            macroquad::shapes::draw_circle(p.x, p.y, HANDLE, color);
+        }
+    }
+}
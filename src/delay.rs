@@ -0,0 +1,71 @@
+/// Past values of a scalar delay differential equation x' = f(x(t), x(t - tau)), sampled every
+/// `dt` with the newest at the back. Before the start the history is the constant initial value.
+pub struct DelayLine {
+    dt: f32,
+    history: std::collections::VecDeque<f32>,
+}
+
+impl DelayLine {
+    pub fn new(x: f32, dt: f32) -> Self {
+        Self {
+            dt,
+            history: std::collections::VecDeque::from([x]),
+        }
+    }
+
+    pub fn current(&self) -> f32 {
+        *self.history.back().unwrap()
+    }
+
+    /// x(t - lag), linearly interpolated between samples.
+    pub fn at(&self, lag: f32) -> f32 {
+        let position = (lag / self.dt).max(0.0);
+        let i = position.floor() as usize;
+        let n = self.history.len();
+        if i + 1 >= n {
+            return self.history[0];
+        }
+        let (newer, older) = (self.history[n - 1 - i], self.history[n - 2 - i]);
+        newer + (older - newer) * position.fract()
+    }
+
+    /// Advances by `dt` with Euler or, for any other integrator, RK4. `keep` is the longest lag
+    /// that will be asked for afterwards.
+    pub fn step(
+        &mut self,
+        f: impl Fn(f32, f32) -> f32,
+        tau: f32,
+        keep: f32,
+        dt: f32,
+        integrator: crate::integrator::Integrator,
+    ) -> f32 {
+        if dt != self.dt {
+            *self = Self::new(self.current(), dt);
+        }
+        let x = self.current();
+        let next = match integrator {
+            crate::integrator::Integrator::Euler => x + f(x, self.at(tau)) * dt,
+            _ => {
+                let half = self.at(tau - dt / 2.0);
+                let k1 = f(x, self.at(tau));
+                let k2 = f(x + k1 * (dt / 2.0), half);
+                let k3 = f(x + k2 * (dt / 2.0), half);
+                let k4 = f(x + k3 * dt, self.at(tau - dt));
+                x + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (dt / 6.0)
+            }
+        };
+        self.history.push_back(next);
+        let capacity = (keep.max(tau) / dt).ceil() as usize + 2;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+        next
+    }
+
+    /// Adds an external perturbation to the newest value.
+    pub fn perturb(&mut self, delta: f32) {
+        if let Some(last) = self.history.back_mut() {
+            *last += delta;
+        }
+    }
+}
@@ -0,0 +1,141 @@
+const RESOLUTION: usize = 128;
+const MARGIN: f32 = 0.1;
+const MAX_OUTSIDE: f32 = 0.2;
+
+/// Histogram of where the first trajectory spends its time, projected along `axis` onto a grid
+/// fitted to its trail. Every bin decays with `half_life` seconds, so the picture follows the
+/// attractor as parameters change; the grid is refitted once too much of the time is spent
+/// outside it.
+pub struct Density {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub axis: usize,
+    pub half_life: f32,
+    bins: Vec<f32>,
+    outside: f32,
+    fitted_axis: usize,
+    bounds: Option<(macroquad::math::Vec2, macroquad::math::Vec2)>,
+    image: macroquad::texture::Image,
+    texture: macroquad::texture::Texture2D,
+}
+
+impl Density {
+    pub fn new() -> Self {
+        let image = macroquad::texture::Image::gen_image_color(
+            RESOLUTION as u16,
+            RESOLUTION as u16,
+            macroquad::color::BLANK,
+        );
+        let texture = macroquad::texture::Texture2D::from_image(&image);
+        texture.set_filter(macroquad::texture::FilterMode::Linear);
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "visit density",
+                macroquad::math::Rect::new(780.0, 600.0, 220.0, 220.0),
+            ),
+            axis: 1,
+            half_life: 5.0,
+            bins: vec![0.0; RESOLUTION * RESOLUTION],
+            outside: 0.0,
+            fitted_axis: 1,
+            bounds: None,
+            image,
+            texture,
+        }
+    }
+
+    fn plane_axes(&self) -> (usize, usize) {
+        match self.axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        }
+    }
+
+    fn project(&self, p: macroquad::math::Vec3) -> macroquad::math::Vec2 {
+        let (a, b) = self.plane_axes();
+        macroquad::math::vec2(p[a], p[b])
+    }
+
+    pub fn clear(&mut self) {
+        self.bins.fill(0.0);
+        self.outside = 0.0;
+        self.bounds = None;
+    }
+
+    pub fn observe(&mut self, p: macroquad::math::Vec3) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        let uv = (self.project(p) - min) / (max - min);
+        if !(0.0..1.0).contains(&uv.x) || !(0.0..1.0).contains(&uv.y) {
+            self.outside += 1.0;
+            return;
+        }
+        let (column, row) = (
+            (uv.x * RESOLUTION as f32) as usize,
+            (uv.y * RESOLUTION as f32) as usize,
+        );
+        self.bins[row * RESOLUTION + column] += 1.0;
+    }
+
+    /// Decays the bins by one frame, refits the grid to `points` when needed and redraws the
+    /// texture on a logarithmic scale.
+    pub fn update(
+        &mut self,
+        points: &std::collections::VecDeque<macroquad::math::DVec3>,
+        colormap: crate::palette::Colormap,
+    ) {
+        let total = self.bins.iter().sum::<f32>();
+        if self.bounds.is_none()
+            || self.fitted_axis != self.axis
+            || self.outside > MAX_OUTSIDE * (total + self.outside)
+        {
+            self.clear();
+            self.fitted_axis = self.axis;
+            let trail = points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>();
+            if trail.len() < 2 {
+                return;
+            }
+            let (min, max) = crate::analysis::bounds(&trail);
+            let (min, max) = (self.project(min), self.project(max));
+            let margin = (max - min).max(macroquad::math::Vec2::splat(1e-3)) * MARGIN;
+            self.bounds = Some((min - margin, max + margin));
+        }
+        let retention = 0.5_f32.powf(macroquad::time::get_frame_time() / self.half_life);
+        self.bins.iter_mut().for_each(|bin| *bin *= retention);
+        self.outside *= retention;
+        let peak = self.bins.iter().copied().fold(0.0, f32::max).ln_1p();
+        for row in 0..RESOLUTION {
+            for column in 0..RESOLUTION {
+                let value = self.bins[row * RESOLUTION + column];
+                let color = if value < 1e-3 || peak <= 0.0 {
+                    macroquad::color::BLANK
+                } else {
+                    colormap.sample(value.ln_1p() / peak)
+                };
+                self.image
+                    .set_pixel(column as u32, (RESOLUTION - 1 - row) as u32, color);
+            }
+        }
+        self.texture.update(&self.image);
+    }
+
+    pub fn draw(&self) {
+        let r = self.panel.rect;
+        let (a, b) = self.plane_axes();
+        self.panel
+            .draw_frame(crate::poincare::AXES[a], crate::poincare::AXES[b]);
+        macroquad::texture::draw_texture_ex(
+            &self.texture,
+            r.x,
+            r.y,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(r.w, r.h)),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,105 @@
+const HISTORY: usize = 200;
+
+pub struct Diagnostics {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub accepted: usize,
+    pub rejected: usize,
+    pub step_size: f32,
+    pub error: Option<f32>,
+    evaluations: usize,
+    evaluations_per_second: f32,
+    history: std::collections::VecDeque<f32>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "integrator diagnostics",
+                macroquad::math::Rect::new(520.0, 30.0, 240.0, 160.0),
+            ),
+            accepted: 0,
+            rejected: 0,
+            step_size: 0.0,
+            error: None,
+            evaluations: 0,
+            evaluations_per_second: 0.0,
+            history: std::collections::VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            visible: self.visible,
+            ..Self::new()
+        };
+    }
+
+    pub fn record(&mut self, evaluations: usize, rejected: usize) {
+        self.accepted += 1;
+        self.rejected += rejected;
+        self.evaluations += evaluations;
+    }
+
+    pub fn end_frame(&mut self, frame_time: f32) {
+        if frame_time > 0.0 {
+            let rate = self.evaluations as f32 / frame_time;
+            self.evaluations_per_second += (rate - self.evaluations_per_second) * 0.1;
+        }
+        self.evaluations = 0;
+        self.history.push_back(self.step_size);
+        if self.history.len() > HISTORY {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn draw(&self) {
+        let panel = &self.panel;
+        panel.draw_frame("frames", "h");
+        let (lo, hi) = self
+            .history
+            .iter()
+            .filter(|h| **h > 0.0)
+            .fold((f32::MAX, f32::MIN), |(lo, hi), h| {
+                (lo.min(h.log10()), hi.max(h.log10()))
+            });
+        let range = (lo - 0.1, hi.max(lo) + 0.1);
+        let points = self
+            .history
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| **h > 0.0)
+            .map(|(i, h)| {
+                panel.to_screen(macroquad::math::vec2(
+                    i as f32 / (HISTORY - 1) as f32,
+                    crate::panel::unlerp(range, h.log10()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        for (a, b) in points.iter().zip(points.iter().skip(1)) {
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::SKYBLUE);
+        }
+
+        let error = self
+            .error
+            .map_or("n/a".to_owned(), |error| format!("{error:.2e}"));
+        let lines = [
+            format!("accepted {}", self.accepted),
+            format!("rejected {}", self.rejected),
+            format!("h = {:.5}", self.step_size),
+            format!("rhs evals/s {:.0}", self.evaluations_per_second),
+            format!("local error {error}"),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            macroquad::text::draw_text(
+                line,
+                panel.rect.x + 6.0,
+                panel.rect.y + 16.0 + i as f32 * 16.0,
+                16.0,
+                macroquad::color::WHITE,
+            );
+        }
+    }
+}
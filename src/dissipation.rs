@@ -0,0 +1,78 @@
+const EDGE: f32 = 1e-2;
+
+pub struct VolumeMonitor {
+    pub visible: bool,
+    edges: [macroquad::math::Vec3; 3],
+    log_volume: f32,
+    divergence_sum: f32,
+    divergence: f32,
+    elapsed: f32,
+}
+
+impl VolumeMonitor {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            edges: [
+                macroquad::math::Vec3::X * EDGE,
+                macroquad::math::Vec3::Y * EDGE,
+                macroquad::math::Vec3::Z * EDGE,
+            ],
+            log_volume: 0.0,
+            divergence_sum: 0.0,
+            divergence: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            visible: self.visible,
+            ..Self::new()
+        };
+    }
+
+    pub fn step(
+        &mut self,
+        p: macroquad::math::Vec3,
+        advance: impl Fn(&macroquad::math::Vec3) -> macroquad::math::Vec3,
+        divergence: f32,
+        dt: f32,
+    ) {
+        let next = advance(&p);
+        let [a, b, c] = self.edges.map(|e| advance(&(p + e)) - next);
+
+        let u1 = a;
+        let r1 = u1.length();
+        let e1 = u1 / r1;
+        let u2 = b - e1 * b.dot(e1);
+        let r2 = u2.length();
+        let e2 = u2 / r2;
+        let u3 = c - e1 * c.dot(e1) - e2 * c.dot(e2);
+        let r3 = u3.length();
+        let e3 = u3 / r3;
+        let growth = (r1 * r2 * r3 / EDGE.powi(3)).ln();
+        if !growth.is_finite() {
+            self.reset();
+            return;
+        }
+
+        self.edges = [e1 * EDGE, e2 * EDGE, e3 * EDGE];
+        self.log_volume += growth;
+        self.divergence = divergence;
+        self.divergence_sum += divergence * dt;
+        self.elapsed += dt;
+    }
+
+    pub fn divergence(&self) -> f32 {
+        self.divergence
+    }
+
+    pub fn mean_divergence(&self) -> f32 {
+        self.divergence_sum / self.elapsed.max(f32::EPSILON)
+    }
+
+    pub fn observed_rate(&self) -> f32 {
+        self.log_volume / self.elapsed.max(f32::EPSILON)
+    }
+}
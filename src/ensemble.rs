@@ -0,0 +1,325 @@
+const MAX_SPREAD: usize = 2_000;
+
+/// Shape of the initial cloud, `spread` across.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Seeding {
+    Cube,
+    Ball,
+    Grid,
+}
+
+impl Seeding {
+    pub const ALL: [Seeding; 3] = [Seeding::Cube, Seeding::Ball, Seeding::Grid];
+    pub const NAMES: [&'static str; 3] = ["random cube", "random ball", "regular grid"];
+}
+
+pub struct Ensemble {
+    pub visible: bool,
+    pub show_spread: bool,
+    /// Draw the centroid and the one standard deviation ellipsoid of the members.
+    pub show_statistics: bool,
+    pub spread_panel: crate::panel::Panel,
+    pub lead_time: f32,
+    spread_history: Vec<(f32, f32)>,
+    pub count: f32,
+    pub spread: f32,
+    pub radius: f32,
+    pub seeding: Seeding,
+    /// Recent frames kept for each particle's tail; 0 draws the particles alone.
+    pub tail: f32,
+    /// Integrate and draw the members on the GPU, for counts the CPU path cannot keep up with.
+    pub gpu: bool,
+    points: Vec<macroquad::math::Vec3>,
+    history: std::collections::VecDeque<Vec<macroquad::math::Vec3>>,
+    displacements: Vec<f32>,
+    renderer: std::cell::RefCell<Option<crate::instancing::InstancedSpheres>>,
+    swarm: std::cell::RefCell<Option<crate::swarm::Swarm>>,
+    /// Whether the swarm holds the current members rather than stale ones.
+    swarm_seeded: bool,
+}
+
+impl Ensemble {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            show_spread: false,
+            show_statistics: false,
+            spread_panel: crate::panel::Panel::new(
+                "ensemble spread vs lead time",
+                macroquad::math::Rect::new(520.0, 230.0, 240.0, 160.0),
+            ),
+            lead_time: 0.0,
+            spread_history: Vec::new(),
+            count: 2_000.0,
+            spread: 1.0,
+            radius: 0.3,
+            seeding: Seeding::Cube,
+            tail: 0.0,
+            gpu: false,
+            points: Vec::new(),
+            history: std::collections::VecDeque::new(),
+            displacements: Vec::new(),
+            renderer: std::cell::RefCell::new(None),
+            swarm: std::cell::RefCell::new(None),
+            swarm_seeded: false,
+        }
+    }
+
+    pub fn seed(&mut self, center: macroquad::math::Vec3, rng: &crate::rng::Rng) {
+        let spread = self.spread;
+        let count = self.count as usize;
+        let cube = || {
+            macroquad::math::vec3(
+                rng.range(-spread, spread),
+                rng.range(-spread, spread),
+                rng.range(-spread, spread),
+            )
+        };
+        self.points = match self.seeding {
+            Seeding::Cube => (0..count).map(|_| center + cube()).collect(),
+            Seeding::Ball => (0..count)
+                .map(|_| {
+                    loop {
+                        let offset = cube();
+                        if offset.length() <= spread {
+                            break center + offset;
+                        }
+                    }
+                })
+                .collect(),
+            Seeding::Grid => {
+                let side = (count as f32).cbrt().round().max(2.0) as usize;
+                let coordinate = |i: usize| spread * (2.0 * i as f32 / (side - 1) as f32 - 1.0);
+                itertools::iproduct!(0..side, 0..side, 0..side)
+                    .map(|(i, j, k)| {
+                        center + macroquad::math::vec3(coordinate(i), coordinate(j), coordinate(k))
+                    })
+                    .collect()
+            }
+        };
+        self.displacements = vec![0.0; self.points.len()];
+        self.swarm_seeded = false;
+        self.lead_time = 0.0;
+        self.spread_history.clear();
+        self.history.clear();
+        self.record(0.0);
+    }
+
+    /// Root-mean-square distance of the members from the ensemble mean.
+    pub fn rms_spread(&self) -> f32 {
+        let members = self.points.iter().filter(|p| p.is_finite());
+        let n = members.clone().count().max(1) as f32;
+        let mean = members.clone().copied().sum::<macroquad::math::Vec3>() / n;
+        (members.map(|p| p.distance_squared(mean)).sum::<f32>() / n).sqrt()
+    }
+
+    /// Mean and covariance of the finite members.
+    pub fn statistics(&self) -> Option<(macroquad::math::Vec3, macroquad::math::Mat3)> {
+        let members = self.points.iter().filter(|p| p.is_finite());
+        let n = members.clone().count();
+        if n < 2 {
+            return None;
+        }
+        let mean = members.clone().copied().sum::<macroquad::math::Vec3>() / n as f32;
+        let covariance = members.fold(macroquad::math::Mat3::ZERO, |sum, p| {
+            let d = *p - mean;
+            sum + macroquad::math::Mat3::from_cols(d * d.x, d * d.y, d * d.z)
+        }) * (1.0 / (n - 1) as f32);
+        Some((mean, covariance))
+    }
+
+    pub fn record(&mut self, elapsed: f32) {
+        self.lead_time += elapsed;
+        // The members on the GPU are not read back, so there is no spread or tail to record.
+        if self.on_gpu() {
+            return;
+        }
+        let spread = self.rms_spread();
+        self.spread_history.push((self.lead_time, spread));
+        if self.spread_history.len() > MAX_SPREAD {
+            self.spread_history = self.spread_history.iter().step_by(2).copied().collect();
+        }
+        let tail = self.tail as usize;
+        if tail > 0 {
+            self.history.push_back(self.points.clone());
+        }
+        while self.history.len() > tail {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn draw_spread(&self) {
+        let panel = &self.spread_panel;
+        panel.draw_frame("lead time", "spread");
+        let (lo, hi) = self
+            .spread_history
+            .iter()
+            .filter(|(_, s)| *s > 0.0)
+            .fold((f32::MAX, f32::MIN), |(lo, hi), (_, s)| {
+                (lo.min(s.log10()), hi.max(s.log10()))
+            });
+        let range = (lo - 0.1, hi.max(lo) + 0.1);
+        let duration = self.lead_time.max(1e-6);
+        let points = self
+            .spread_history
+            .iter()
+            .filter(|(_, s)| *s > 0.0)
+            .map(|(t, s)| {
+                panel.to_screen(macroquad::math::vec2(
+                    t / duration,
+                    crate::panel::unlerp(range, s.log10()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        for (a, b) in points.iter().zip(points.iter().skip(1)) {
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::ORANGE);
+        }
+        let spread = self.spread_history.last().map_or(0.0, |(_, s)| *s);
+        macroquad::text::draw_text(
+            &format!(
+                "t = {:.2}, spread = {spread:.3} (log scale)",
+                self.lead_time
+            ),
+            panel.rect.x + 6.0,
+            panel.rect.y + 16.0,
+            16.0,
+            macroquad::color::WHITE,
+        );
+    }
+
+    pub fn step(&mut self, advance: impl Fn(&macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        for (p, displacement) in self.points.iter_mut().zip(self.displacements.iter_mut()) {
+            let next = advance(p);
+            if next.is_finite() {
+                *displacement = (next - *p).length();
+                *p = next;
+            }
+        }
+    }
+
+    fn on_gpu(&self) -> bool {
+        self.gpu && self.swarm_seeded
+    }
+
+    /// Advances the members on the GPU, seeding the swarm first when needed; false when the
+    /// GPU path is off or unavailable for `system`, which switches it off.
+    pub fn step_gpu(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        dt: f32,
+        steps: usize,
+    ) -> bool {
+        if !self.gpu || self.points.is_empty() {
+            return false;
+        }
+        let swarm = self.swarm.get_mut();
+        if swarm
+            .as_ref()
+            .is_none_or(|s| s.system != system || s.count() != self.points.len())
+        {
+            *swarm = crate::swarm::Swarm::new(system, self.points.len());
+            self.swarm_seeded = false;
+        }
+        let Some(swarm) = swarm else {
+            eprintln!(
+                "ensemble: no GPU integration for {}, using the CPU",
+                system.name()
+            );
+            self.gpu = false;
+            return false;
+        };
+        if !self.swarm_seeded {
+            swarm.seed(&self.points);
+            self.swarm_seeded = true;
+        }
+        swarm.step(params, dt, steps);
+        true
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        colormap: crate::palette::Colormap,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        if self.on_gpu()
+            && let Some(swarm) = self.swarm.borrow_mut().as_mut()
+        {
+            // The scene transform is a uniform scale about a center, so two points pin it down.
+            let origin = transform(macroquad::math::Vec3::ZERO);
+            let scale = transform(macroquad::math::Vec3::X).x - origin.x;
+            swarm.draw(camera, colormap, origin, scale, self.radius);
+            return;
+        }
+        let instances = self
+            .points
+            .iter()
+            .zip(self.displacements.iter())
+            .map(|(p, displacement)| {
+                let color = colormap.sample(displacement.clamp(0.0, 2.0) / 2.0);
+                crate::instancing::Instance {
+                    position: transform(*p).to_array(),
+                    color: [color.r, color.g, color.b, color.a],
+                }
+            })
+            .collect::<Vec<_>>();
+        let frames = self.history.len();
+        for (age, (older, newer)) in self
+            .history
+            .iter()
+            .zip(self.history.iter().skip(1))
+            .enumerate()
+        {
+            let alpha = (age + 1) as f32 / frames as f32 * 0.6;
+            for ((a, b), displacement) in older.iter().zip(newer).zip(self.displacements.iter()) {
+                if a.is_finite() && b.is_finite() {
+                    let color = colormap.sample(displacement.clamp(0.0, 2.0) / 2.0);
+                    macroquad::models::draw_line_3d(
+                        transform(*a),
+                        transform(*b),
+                        color.with_alpha(alpha),
+                    );
+                }
+            }
+        }
+        if self.show_statistics
+            && let Some((mean, covariance)) = self.statistics()
+        {
+            macroquad::models::draw_sphere(transform(mean), 0.6, None, macroquad::color::WHITE);
+            crate::tangent::draw_wireframe(
+                mean,
+                cholesky(covariance),
+                macroquad::color::WHITE.with_alpha(0.6),
+                &transform,
+            );
+        }
+        self.renderer
+            .borrow_mut()
+            .get_or_insert_with(crate::instancing::InstancedSpheres::new)
+            .draw(camera, &instances, self.radius);
+    }
+}
+
+/// Lower triangular L with L L^T = m, for a symmetric positive semi-definite m.
+fn cholesky(m: macroquad::math::Mat3) -> macroquad::math::Mat3 {
+    let a = |i: usize, j: usize| m.col(j)[i];
+    let mut l = [[0.0_f32; 3]; 3];
+    for i in 0..3 {
+        for j in 0..=i {
+            let sum = (0..j).map(|k| l[i][k] * l[j][k]).sum::<f32>();
+            l[i][j] = if i == j {
+                (a(i, i) - sum).max(0.0).sqrt()
+            } else if l[j][j] > 1e-12 {
+                (a(i, j) - sum) / l[j][j]
+            } else {
+                0.0
+            };
+        }
+    }
+    macroquad::math::Mat3::from_cols(
+        macroquad::math::vec3(l[0][0], l[1][0], l[2][0]),
+        macroquad::math::vec3(l[0][1], l[1][1], l[2][1]),
+        macroquad::math::vec3(l[0][2], l[1][2], l[2][2]),
+    )
+}
@@ -0,0 +1,122 @@
+/// Teaching overlay showing how the vector field at the head of the first trajectory breaks down
+/// into its components.
+pub struct Explain {
+    pub enabled: bool,
+    pub scale: f32,
+}
+
+const COLORS: [macroquad::color::Color; 3] = [
+    macroquad::color::RED,
+    macroquad::color::GREEN,
+    macroquad::color::SKYBLUE,
+];
+
+impl Explain {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            scale: 0.1,
+        }
+    }
+
+    pub fn draw_3d(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        p: macroquad::math::Vec3,
+        derivative: macroquad::math::Vec3,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        let origin = transform(p);
+        let axes = [
+            macroquad::math::Vec3::X,
+            macroquad::math::Vec3::Y,
+            macroquad::math::Vec3::Z,
+        ];
+        for (i, (axis, value)) in axes.iter().zip(derivative.to_array()).enumerate() {
+            let tip = transform(p + *axis * value * self.scale);
+            draw_arrow(camera, origin, tip, COLORS[i]);
+            crate::labels::draw_label(
+                camera,
+                tip,
+                &format!("{} {value:.2}", ["dx", "dy", "dz"][i]),
+                COLORS[i],
+                16.0,
+                false,
+            );
+        }
+        let tip = transform(p + derivative * self.scale);
+        draw_arrow(camera, origin, tip, macroquad::color::WHITE);
+    }
+
+    pub fn draw_equations(
+        &self,
+        system: crate::system::System,
+        params: &[f32],
+        p: macroquad::math::Vec3,
+        derivative: macroquad::math::Vec3,
+    ) {
+        let (x, y, z) = (p.x, p.y, p.z);
+        let terms = match system {
+            crate::system::System::Lorenz | crate::system::System::FractionalLorenz => [
+                format!("dx/dt = sigma (y - x) = {:.2} ({y:.2} - {x:.2})", params[0]),
+                format!(
+                    "dy/dt = x (rho - z) - y = {x:.2} ({:.2} - {z:.2}) - {y:.2}",
+                    params[2]
+                ),
+                format!(
+                    "dz/dt = x y - beta z = {x:.2} * {y:.2} - {:.2} * {z:.2}",
+                    params[1]
+                ),
+            ],
+            crate::system::System::Rossler => [
+                format!("dx/dt = -y - z = -{y:.2} - {z:.2}"),
+                format!("dy/dt = x + a y = {x:.2} + {:.2} * {y:.2}", params[0]),
+                format!(
+                    "dz/dt = b + z (x - c) = {:.2} + {z:.2} ({x:.2} - {:.2})",
+                    params[1], params[2]
+                ),
+            ],
+            _ => ["dx/dt".to_owned(), "dy/dt".to_owned(), "dz/dt".to_owned()],
+        };
+        let values = derivative.to_array();
+        let left = macroquad::window::screen_width() / 2.0 - 220.0;
+        let top = macroquad::window::screen_height() - 90.0;
+        macroquad::text::draw_text(
+            &format!(
+                "at ({x:.2}, {y:.2}, {z:.2}), |v| = {:.2}",
+                derivative.length()
+            ),
+            left,
+            top,
+            20.0,
+            macroquad::color::WHITE,
+        );
+        for (i, (term, value)) in terms.iter().zip(values).enumerate() {
+            macroquad::text::draw_text(
+                &format!("{term} = {value:.2}"),
+                left,
+                top + 20.0 * (i + 1) as f32,
+                20.0,
+                COLORS[i],
+            );
+        }
+    }
+}
+
+fn draw_arrow(
+    camera: &macroquad::camera::Camera3D,
+    from: macroquad::math::Vec3,
+    to: macroquad::math::Vec3,
+    color: macroquad::color::Color,
+) {
+    macroquad::models::draw_line_3d(from, to, color);
+    let direction = to - from;
+    let length = direction.length();
+    if length < 1e-3 {
+        return;
+    }
+    let side = direction.cross(camera.position - to).normalize_or_zero() * length * 0.08;
+    let back = to - direction * 0.15;
+    macroquad::models::draw_line_3d(to, back + side, color);
+    macroquad::models::draw_line_3d(to, back - side, color);
+}
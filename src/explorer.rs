@@ -0,0 +1,272 @@
+const RESOLUTION: usize = 64;
+const TRANSIENT: usize = 1_000;
+const STEPS: usize = 4_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Metric {
+    Lyapunov,
+    KaplanYorke,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 2] = [Metric::Lyapunov, Metric::KaplanYorke];
+    pub const NAMES: [&'static str; 2] = ["largest lyapunov exponent", "kaplan-yorke dimension"];
+    pub const KEYS: [&'static str; 2] = ["lyapunov", "kaplan-yorke"];
+
+    pub fn evaluate(
+        &self,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) -> f32 {
+        let f = |p| system.derivative(p, params);
+        match self {
+            Metric::Lyapunov => crate::lyapunov::largest_exponent(
+                f,
+                integrator,
+                system.start(),
+                dt,
+                TRANSIENT,
+                STEPS,
+            ),
+            Metric::KaplanYorke => crate::lyapunov::kaplan_yorke(&crate::lyapunov::spectrum(
+                f,
+                |p| system.jacobian(p, params),
+                integrator,
+                system.start(),
+                dt,
+                TRANSIENT,
+                STEPS,
+            )),
+        }
+    }
+}
+
+/// A metric sampled over two parameters, row by row on all available cores.
+pub struct Grid {
+    pub system: crate::system::System,
+    pub params: Vec<f32>,
+    pub axes: (usize, usize),
+    pub ranges: [(f32, f32); 2],
+    pub resolution: usize,
+    pub metric: Metric,
+    pub integrator: crate::integrator::Integrator,
+    pub dt: f32,
+}
+
+impl Grid {
+    pub fn spawn(
+        &self,
+        values: std::sync::Arc<std::sync::Mutex<Vec<f32>>>,
+        cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ) -> Vec<std::thread::JoinHandle<()>> {
+        let next_row = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        (0..workers)
+            .map(|_| {
+                let (values, cancel, next_row) = (values.clone(), cancel.clone(), next_row.clone());
+                let (system, axes, ranges, resolution) =
+                    (self.system, self.axes, self.ranges, self.resolution);
+                let (metric, integrator, dt) = (self.metric, self.integrator, self.dt);
+                let mut params = self.params.clone();
+                std::thread::spawn(move || {
+                    loop {
+                        let row = next_row.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        if row >= resolution || cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                            return;
+                        }
+                        params[axes.1] =
+                            crate::panel::lerp(ranges[1], row as f32 / (resolution - 1) as f32);
+                        let row_values = (0..resolution)
+                            .map(|column| {
+                                params[axes.0] = crate::panel::lerp(
+                                    ranges[0],
+                                    column as f32 / (resolution - 1) as f32,
+                                );
+                                metric.evaluate(system, &params, integrator, dt)
+                            })
+                            .collect::<Vec<_>>();
+                        values.lock().unwrap()[row * resolution..(row + 1) * resolution]
+                            .copy_from_slice(&row_values);
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// Colors a row-major grid of values into `image`, with the first row at the bottom.
+pub fn paint(
+    values: &[f32],
+    resolution: usize,
+    colormap: crate::palette::Colormap,
+    image: &mut macroquad::texture::Image,
+) {
+    let (lo, hi) = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    for (i, value) in values.iter().enumerate() {
+        let color = if value.is_finite() {
+            colormap.sample((value - lo) / (hi - lo).max(1e-6))
+        } else {
+            macroquad::color::DARKGRAY
+        };
+        let (x, y) = (i % resolution, resolution - 1 - i / resolution);
+        image.set_pixel(x as u32, y as u32, color);
+    }
+}
+
+pub struct ParameterPlane {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    axes: (usize, usize),
+    ranges: [(f32, f32); 2],
+    values: std::sync::Arc<std::sync::Mutex<Vec<f32>>>,
+    cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    image: macroquad::texture::Image,
+    texture: macroquad::texture::Texture2D,
+}
+
+impl ParameterPlane {
+    pub fn new() -> Self {
+        let image = macroquad::texture::Image::gen_image_color(
+            RESOLUTION as u16,
+            RESOLUTION as u16,
+            macroquad::color::BLACK,
+        );
+        let texture = macroquad::texture::Texture2D::from_image(&image);
+        texture.set_filter(macroquad::texture::FilterMode::Nearest);
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "largest lyapunov exponent",
+                macroquad::math::Rect::new(0.0, 0.0, 256.0, 256.0),
+            ),
+            axes: (0, 2),
+            ranges: [(0.0, 1.0); 2],
+            values: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            cancel: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            image,
+            texture,
+        }
+    }
+
+    /// Whether `system` has the two parameters the plane needs.
+    pub fn available(system: crate::system::System) -> bool {
+        system.params().len() >= 2
+    }
+
+    /// Drops the plane of the previous system and picks axes among the parameters of `system`,
+    /// the first and the third (or the last, with only two).
+    pub fn switch_system(&mut self, system: crate::system::System) {
+        self.cancel
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.values = std::sync::Arc::new(std::sync::Mutex::new(vec![
+            f32::NAN;
+            RESOLUTION * RESOLUTION
+        ]));
+        let count = system.params().len();
+        self.axes = (0, count.clamp(2, 3) - 1);
+        if !Self::available(system) {
+            self.visible = false;
+        }
+    }
+
+    pub fn compute(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) {
+        self.switch_system(system);
+        if !Self::available(system) {
+            return;
+        }
+        self.cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let descriptors = system.params();
+        let range = |i: usize| (descriptors[i].min, descriptors[i].max);
+        self.ranges = [range(self.axes.0), range(self.axes.1)];
+
+        Grid {
+            system,
+            params: params.to_vec(),
+            axes: self.axes,
+            ranges: self.ranges,
+            resolution: RESOLUTION,
+            metric: Metric::Lyapunov,
+            integrator,
+            dt,
+        }
+        .spawn(self.values.clone(), self.cancel.clone());
+    }
+
+    pub fn update_texture(&mut self, colormap: crate::palette::Colormap) {
+        paint(
+            &self.values.lock().unwrap(),
+            RESOLUTION,
+            colormap,
+            &mut self.image,
+        );
+        self.texture.update(&self.image);
+    }
+
+    pub fn draw(&mut self, system: crate::system::System, params: &mut [f32]) {
+        let screen = macroquad::math::vec2(
+            macroquad::window::screen_width(),
+            macroquad::window::screen_height(),
+        );
+        self.panel.rect.x = screen.x - self.panel.rect.w - 20.0;
+        self.panel.rect.y = screen.y - self.panel.rect.h - 30.0;
+        let descriptors = system.params();
+        if self.axes.1 >= descriptors.len() {
+            return;
+        }
+        self.panel
+            .draw_frame(descriptors[self.axes.0].name, descriptors[self.axes.1].name);
+        let r = self.panel.rect;
+        macroquad::texture::draw_texture_ex(
+            &self.texture,
+            r.x,
+            r.y,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(r.w, r.h)),
+                ..Default::default()
+            },
+        );
+
+        let current = macroquad::math::vec2(
+            crate::panel::unlerp(self.ranges[0], params[self.axes.0]),
+            crate::panel::unlerp(self.ranges[1], params[self.axes.1]),
+        );
+        let marker = self.panel.to_screen(current);
+        macroquad::shapes::draw_circle_lines(marker.x, marker.y, 4.0, 1.0, macroquad::color::WHITE);
+
+        let mouse = macroquad::input::mouse_position().into();
+        if self.panel.contains(mouse) {
+            let u = self.panel.to_unit(mouse);
+            let (a, b) = (
+                crate::panel::lerp(self.ranges[0], u.x),
+                crate::panel::lerp(self.ranges[1], u.y),
+            );
+            macroquad::text::draw_text(
+                &format!(
+                    "{} = {a:.2}, {} = {b:.2}",
+                    descriptors[self.axes.0].name, descriptors[self.axes.1].name
+                ),
+                r.x,
+                r.y + r.h + 14.0,
+                16.0,
+                macroquad::color::WHITE,
+            );
+            if macroquad::input::is_mouse_button_pressed(macroquad::input::MouseButton::Left) {
+                params[self.axes.0] = a;
+                params[self.axes.1] = b;
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use macroquad::miniquad;
+
+const SCALE: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform float exposure;
+void main() {
+    gl_FragColor = vec4(texture2D(Texture, uv).rgb * exposure, 1.0);
+}
+"#;
+
+struct Buffers {
+    add: macroquad::material::Material,
+    frame: macroquad::texture::RenderTarget,
+    accumulated: macroquad::texture::RenderTarget,
+    size: (u32, u32),
+}
+
+/// Long exposure: every frame of the scene is rendered on black and added, scaled by
+/// `exposure`, into a buffer that is only cleared on request, so the image builds up wherever
+/// the trails have been.
+pub struct Exposure {
+    pub enabled: bool,
+    pub exposure: f32,
+    clear: bool,
+    buffers: Option<Buffers>,
+}
+
+impl Exposure {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            exposure: 0.05,
+            clear: true,
+            buffers: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.clear = true;
+    }
+
+    /// The camera to draw the frame with, like `Bloom::scene_camera`.
+    pub fn scene_camera(
+        &mut self,
+        camera: macroquad::camera::Camera3D,
+    ) -> macroquad::camera::Camera3D {
+        let size = (
+            macroquad::window::screen_width() as u32,
+            macroquad::window::screen_height() as u32,
+        );
+        if self.buffers.as_ref().is_none_or(|b| b.size != size) {
+            self.buffers = buffers(size);
+            self.clear = true;
+        }
+        let Some(buffers) = &self.buffers else {
+            return camera;
+        };
+        macroquad::camera::Camera3D {
+            render_target: Some(buffers.frame.clone()),
+            ..camera
+        }
+    }
+
+    /// Adds the frame to the buffer and draws the buffer over the background; expects the
+    /// default camera.
+    pub fn composite(&mut self) {
+        let Some(buffers) = &self.buffers else {
+            return;
+        };
+        if std::mem::take(&mut self.clear) {
+            macroquad::camera::set_camera(&macroquad::camera::Camera2D {
+                render_target: Some(buffers.accumulated.clone()),
+                ..Default::default()
+            });
+            macroquad::window::clear_background(macroquad::color::BLACK);
+            macroquad::camera::set_default_camera();
+        }
+        buffers.add.set_uniform("exposure", self.exposure);
+        crate::bloom::pass(&buffers.add, &buffers.frame.texture, &buffers.accumulated);
+        buffers.add.set_uniform("exposure", 1.0_f32);
+        macroquad::material::gl_use_material(&buffers.add);
+        macroquad::texture::draw_texture_ex(
+            &buffers.accumulated.texture,
+            0.0,
+            0.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(
+                    buffers.size.0 as f32,
+                    buffers.size.1 as f32,
+                )),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        macroquad::material::gl_use_default_material();
+    }
+}
+
+fn buffers((width, height): (u32, u32)) -> Option<Buffers> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some(Buffers {
+        add: crate::bloom::material(SCALE, &[("exposure", miniquad::UniformType::Float1)], true)?,
+        frame: crate::bloom::target(width, height, true),
+        accumulated: crate::bloom::target(width, height, false),
+        size: (width, height),
+    })
+}
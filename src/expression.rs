@@ -0,0 +1,281 @@
+#[derive(Clone, Copy)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Tanh,
+    Exp,
+    Ln,
+    Sqrt,
+    Abs,
+}
+
+impl Function {
+    const NAMES: [(&'static str, Function); 8] = [
+        ("sin", Function::Sin),
+        ("cos", Function::Cos),
+        ("tan", Function::Tan),
+        ("tanh", Function::Tanh),
+        ("exp", Function::Exp),
+        ("ln", Function::Ln),
+        ("sqrt", Function::Sqrt),
+        ("abs", Function::Abs),
+    ];
+
+    /// Whether `name` is a function, and so cannot name a variable.
+    pub fn is_name(name: &str) -> bool {
+        Self::NAMES.iter().any(|(n, _)| *n == name)
+    }
+
+    fn apply(&self, v: f32) -> f32 {
+        match self {
+            Function::Sin => v.sin(),
+            Function::Cos => v.cos(),
+            Function::Tan => v.tan(),
+            Function::Tanh => v.tanh(),
+            Function::Exp => v.exp(),
+            Function::Ln => v.ln(),
+            Function::Sqrt => v.sqrt(),
+            Function::Abs => v.abs(),
+        }
+    }
+}
+
+pub enum Expression {
+    Number(f32),
+    Variable(usize),
+    Negate(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Divide(Box<Expression>, Box<Expression>),
+    Power(Box<Expression>, Box<Expression>),
+    Call(Function, Box<Expression>),
+}
+
+impl Expression {
+    pub fn parse(text: &str, variables: &[&str]) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            position: 0,
+            variables,
+        };
+        let expression = parser.sum()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(expression),
+            Some(c) => Err(format!("unexpected '{c}' in '{text}'")),
+        }
+    }
+
+    pub fn evaluate(&self, variables: &[f32]) -> f32 {
+        match self {
+            Expression::Number(v) => *v,
+            Expression::Variable(i) => variables.get(*i).copied().unwrap_or(0.0),
+            Expression::Negate(a) => -a.evaluate(variables),
+            Expression::Add(a, b) => a.evaluate(variables) + b.evaluate(variables),
+            Expression::Subtract(a, b) => a.evaluate(variables) - b.evaluate(variables),
+            Expression::Multiply(a, b) => a.evaluate(variables) * b.evaluate(variables),
+            Expression::Divide(a, b) => a.evaluate(variables) / b.evaluate(variables),
+            Expression::Power(a, b) => a.evaluate(variables).powf(b.evaluate(variables)),
+            Expression::Call(f, a) => f.apply(a.evaluate(variables)),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    variables: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(c);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn sum(&mut self) -> Result<Expression, String> {
+        let mut left = self.product()?;
+        loop {
+            if self.eat('+') {
+                left = Expression::Add(Box::new(left), Box::new(self.product()?));
+            } else if self.eat('-') {
+                left = Expression::Subtract(Box::new(left), Box::new(self.product()?));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Expression, String> {
+        let mut left = self.unary()?;
+        loop {
+            if self.eat('*') {
+                left = Expression::Multiply(Box::new(left), Box::new(self.unary()?));
+            } else if self.eat('/') {
+                left = Expression::Divide(Box::new(left), Box::new(self.unary()?));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression, String> {
+        if self.eat('-') {
+            Ok(Expression::Negate(Box::new(self.unary()?)))
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Expression, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            Ok(Expression::Power(Box::new(base), Box::new(self.unary()?)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expression, String> {
+        if self.eat('(') {
+            let inner = self.sum()?;
+            if !self.eat(')') {
+                return Err("missing ')'".to_owned());
+            }
+            return Ok(inner);
+        }
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                while let Some(c) = self.peek() {
+                    if c == 'e' && matches!(self.chars.get(self.position + 1), Some('-' | '+')) {
+                        self.position += 2;
+                    } else if c.is_ascii_digit() || c == '.' || c == 'e' {
+                        self.position += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let text = self.chars[start..self.position].iter().collect::<String>();
+                text.parse()
+                    .map(Expression::Number)
+                    .map_err(|_| format!("invalid number '{text}'"))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    self.position += 1;
+                }
+                let name = self.chars[start..self.position].iter().collect::<String>();
+                if let Some((_, function)) = Function::NAMES.iter().find(|(n, _)| *n == name) {
+                    if !self.eat('(') {
+                        return Err(format!("missing '(' after '{name}'"));
+                    }
+                    let argument = self.sum()?;
+                    if !self.eat(')') {
+                        return Err("missing ')'".to_owned());
+                    }
+                    return Ok(Expression::Call(*function, Box::new(argument)));
+                }
+                self.variables
+                    .iter()
+                    .position(|v| *v == name)
+                    .map(Expression::Variable)
+                    .ok_or(format!("unknown variable '{name}'"))
+            }
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err("unexpected end of expression".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(text: &str) -> f32 {
+        Expression::parse(text, &["x", "y"])
+            .unwrap()
+            .evaluate(&[2.0, 3.0])
+    }
+
+    fn error(text: &str) -> String {
+        match Expression::parse(text, &["x", "y"]) {
+            Ok(_) => panic!("'{text}' should not parse"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("x - y - 1"), -2.0);
+        assert_eq!(eval("12 / x / y"), 2.0);
+        assert_eq!(eval("2 * x ^ 2"), 8.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-x"), -2.0);
+        assert_eq!(eval("--x"), 2.0);
+        assert_eq!(eval("-x ^ 2"), -4.0);
+        assert_eq!(eval("y * -x"), -6.0);
+        assert_eq!(eval("x ^ -1"), 0.5);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("(2 ^ 3) ^ 2"), 64.0);
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(eval("1e3"), 1000.0);
+        assert_eq!(eval("2.5e-1 * x"), 0.5);
+        assert_eq!(eval("1e+2 + y"), 103.0);
+        assert_eq!(eval(".5"), 0.5);
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(eval("abs(-x)"), 2.0);
+        assert_eq!(eval("sqrt(x * 8)"), 4.0);
+        assert_eq!(eval("sin(0) + cos(0)"), 1.0);
+    }
+
+    #[test]
+    fn unknown_identifiers() {
+        assert_eq!(error("x + z"), "unknown variable 'z'");
+        assert_eq!(error("floor(x)"), "unknown variable 'floor'");
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert_eq!(error(""), "unexpected end of expression");
+        assert_eq!(error("x +"), "unexpected end of expression");
+        assert_eq!(error("(x + y"), "missing ')'");
+        assert_eq!(error("x + y)"), "unexpected ')' in 'x + y)'");
+        assert_eq!(error("sin x"), "missing '(' after 'sin'");
+        assert_eq!(error("1.2.3"), "invalid number '1.2.3'");
+        assert_eq!(error("x $ y"), "unexpected '$' in 'x $ y'");
+        assert_eq!(error("* x"), "unexpected '*'");
+    }
+}
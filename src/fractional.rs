@@ -0,0 +1,62 @@
+pub struct GrunwaldLetnikov {
+    order: f32,
+    memory: usize,
+    origin: macroquad::math::Vec3,
+    coefficients: Vec<f32>,
+    history: std::collections::VecDeque<macroquad::math::Vec3>,
+}
+
+impl GrunwaldLetnikov {
+    pub fn new(origin: macroquad::math::Vec3, order: f32, memory: usize) -> Self {
+        let mut integrator = Self {
+            order,
+            memory,
+            origin,
+            coefficients: Vec::new(),
+            history: std::collections::VecDeque::from([macroquad::math::Vec3::ZERO]),
+        };
+        integrator.update_coefficients();
+        integrator
+    }
+
+    pub fn configure(&mut self, order: f32, memory: usize) {
+        if order != self.order || memory != self.memory {
+            self.order = order;
+            self.memory = memory;
+            self.update_coefficients();
+            while self.history.len() > self.memory {
+                self.history.pop_front();
+            }
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        self.coefficients.clear();
+        let mut c = 1.0;
+        self.coefficients.push(c);
+        for j in 1..=self.memory {
+            c *= 1.0 - (1.0 + self.order) / j as f32;
+            self.coefficients.push(c);
+        }
+    }
+
+    pub fn step(
+        &mut self,
+        f: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+        dt: f32,
+    ) -> macroquad::math::Vec3 {
+        let previous = self.origin + *self.history.back().unwrap();
+        let memory = self
+            .history
+            .iter()
+            .rev()
+            .zip(self.coefficients.iter().skip(1))
+            .fold(macroquad::math::Vec3::ZERO, |sum, (y, c)| sum + *y * *c);
+        let next = f(previous) * dt.powf(self.order) - memory;
+        self.history.push_back(next);
+        if self.history.len() > self.memory {
+            self.history.pop_front();
+        }
+        self.origin + next
+    }
+}
@@ -0,0 +1,41 @@
+/// Finite-time Lyapunov exponent over a sliding time window, from a tangent vector that is
+/// renormalized every step and so aligns with the fastest-growing direction.
+pub struct Ftle {
+    vector: macroquad::math::Vec3,
+    growth: std::collections::VecDeque<(f32, f32)>,
+    sum: f32,
+    elapsed: f32,
+}
+
+impl Ftle {
+    pub fn new() -> Self {
+        Self {
+            vector: macroquad::math::Vec3::ONE.normalize(),
+            growth: std::collections::VecDeque::new(),
+            sum: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn step(&mut self, jacobian: macroquad::math::Mat3, h: f32, window: f32) -> f32 {
+        let j = jacobian * h;
+        let next = (macroquad::math::Mat3::IDENTITY + j + j * j * 0.5) * self.vector;
+        let length = next.length();
+        if !length.is_finite() || length == 0.0 {
+            *self = Self::new();
+            return 0.0;
+        }
+        self.vector = next / length;
+        let log = length.ln();
+        self.growth.push_back((log, h));
+        self.sum += log;
+        self.elapsed += h;
+        while self.elapsed > window
+            && let Some((log, h)) = self.growth.pop_front()
+        {
+            self.sum -= log;
+            self.elapsed -= h;
+        }
+        self.sum / self.elapsed.max(f32::EPSILON)
+    }
+}
@@ -0,0 +1,165 @@
+const RESOLUTION: usize = 96;
+const DELTA: f32 = 1e-2;
+
+/// Forward (or, with negative `time`, backward) FTLE over a square slice of initial conditions,
+/// computed on worker threads and drawn as a textured quad in the scene.
+pub struct FtleField {
+    pub visible: bool,
+    pub axis: usize,
+    pub offset: f32,
+    pub extent: f32,
+    pub time: f32,
+    center: macroquad::math::Vec3,
+    values: std::sync::Arc<std::sync::Mutex<Vec<f32>>>,
+    rows_done: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    image: macroquad::texture::Image,
+    texture: macroquad::texture::Texture2D,
+}
+
+impl FtleField {
+    pub fn new() -> Self {
+        let image = macroquad::texture::Image::gen_image_color(
+            RESOLUTION as u16,
+            RESOLUTION as u16,
+            macroquad::color::BLANK,
+        );
+        let texture = macroquad::texture::Texture2D::from_image(&image);
+        texture.set_filter(macroquad::texture::FilterMode::Linear);
+        Self {
+            visible: false,
+            axis: 1,
+            offset: 0.0,
+            extent: 30.0,
+            time: 1.0,
+            center: macroquad::math::Vec3::ZERO,
+            values: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            rows_done: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            cancel: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            image,
+            texture,
+        }
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.rows_done.load(std::sync::atomic::Ordering::Relaxed) as f32 / RESOLUTION as f32
+    }
+
+    fn plane_axes(&self) -> (usize, usize) {
+        match self.axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        }
+    }
+
+    fn corner(&self, u: f32, v: f32) -> macroquad::math::Vec3 {
+        let (a, b) = self.plane_axes();
+        let mut p = self.center;
+        p[self.axis] = self.offset;
+        p[a] += (u * 2.0 - 1.0) * self.extent;
+        p[b] += (v * 2.0 - 1.0) * self.extent;
+        p
+    }
+
+    /// Starts computing the slice centred on `center` in the plane's own coordinates.
+    pub fn compute(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+        center: macroquad::math::Vec3,
+    ) {
+        self.cancel
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        self.values = std::sync::Arc::new(std::sync::Mutex::new(vec![
+            f32::NAN;
+            RESOLUTION * RESOLUTION
+        ]));
+        self.rows_done = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        self.center = center;
+
+        let next_row = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        let corners = [
+            self.corner(0.0, 0.0),
+            self.corner(1.0, 0.0),
+            self.corner(0.0, 1.0),
+        ];
+        let (time, params) = (self.time, params.to_vec());
+        for _ in 0..workers {
+            let (values, cancel) = (self.values.clone(), self.cancel.clone());
+            let (next_row, rows_done) = (next_row.clone(), self.rows_done.clone());
+            let params = params.clone();
+            std::thread::spawn(move || {
+                let steps = (time.abs() / dt).round().max(1.0) as usize;
+                let h = time.signum() * dt;
+                let flow_map = |mut p: macroquad::math::Vec3| {
+                    for _ in 0..steps {
+                        p = integrator.step(|q| system.derivative(q, &params), p, h);
+                    }
+                    p
+                };
+                loop {
+                    let row = next_row.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    if row >= RESOLUTION || cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    let v = row as f32 / (RESOLUTION - 1) as f32;
+                    let row_values = (0..RESOLUTION)
+                        .map(|column| {
+                            let u = column as f32 / (RESOLUTION - 1) as f32;
+                            let p = corners[0]
+                                + (corners[1] - corners[0]) * u
+                                + (corners[2] - corners[0]) * v;
+                            let column = |e: macroquad::math::Vec3| {
+                                (flow_map(p + e * DELTA) - flow_map(p - e * DELTA)) / (2.0 * DELTA)
+                            };
+                            let gradient = macroquad::math::Mat3::from_cols(
+                                column(macroquad::math::Vec3::X),
+                                column(macroquad::math::Vec3::Y),
+                                column(macroquad::math::Vec3::Z),
+                            );
+                            let cauchy_green = gradient.transpose() * gradient;
+                            let largest = crate::analysis::eigenvalues(cauchy_green)[0].0;
+                            largest.sqrt().ln() / time.abs()
+                        })
+                        .collect::<Vec<_>>();
+                    values.lock().unwrap()[row * RESOLUTION..(row + 1) * RESOLUTION]
+                        .copy_from_slice(&row_values);
+                    rows_done.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+            });
+        }
+    }
+
+    pub fn update_texture(&mut self, colormap: crate::palette::Colormap) {
+        crate::explorer::paint(
+            &self.values.lock().unwrap(),
+            RESOLUTION,
+            colormap,
+            &mut self.image,
+        );
+        self.texture.update(&self.image);
+    }
+
+    pub fn draw(&self, transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        let color = macroquad::color::Color::new(1.0, 1.0, 1.0, 0.85);
+        let vertices = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .map(|(u, v)| {
+                macroquad::models::Vertex::new2(
+                    transform(self.corner(u, v)),
+                    macroquad::math::vec2(u, 1.0 - v),
+                    color,
+                )
+            })
+            .to_vec();
+        macroquad::models::draw_mesh(&macroquad::models::Mesh {
+            vertices,
+            indices: vec![0, 1, 2, 0, 2, 3],
+            texture: Some(self.texture.clone()),
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use macroquad::miniquad;
+
+const FXAA: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform vec2 texel;
+const float SPAN_MAX = 8.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float REDUCE_MIN = 1.0 / 128.0;
+float luma(vec2 at) {
+    return dot(texture2D(Texture, at).rgb, vec3(0.299, 0.587, 0.114));
+}
+void main() {
+    float nw = luma(uv + vec2(-1.0, -1.0) * texel);
+    float ne = luma(uv + vec2(1.0, -1.0) * texel);
+    float sw = luma(uv + vec2(-1.0, 1.0) * texel);
+    float se = luma(uv + vec2(1.0, 1.0) * texel);
+    float m = luma(uv);
+    float lo = min(m, min(min(nw, ne), min(sw, se)));
+    float hi = max(m, max(max(nw, ne), max(sw, se)));
+    vec2 direction = vec2((sw + se) - (nw + ne), (nw + sw) - (ne + se));
+    float reduce = max((nw + ne + sw + se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, -SPAN_MAX, SPAN_MAX) * texel;
+    vec3 near = 0.5 * (texture2D(Texture, uv - direction / 6.0).rgb
+        + texture2D(Texture, uv + direction / 6.0).rgb);
+    vec3 far = 0.5 * near + 0.25 * (texture2D(Texture, uv - direction * 0.5).rgb
+        + texture2D(Texture, uv + direction * 0.5).rgb);
+    float edge = dot(far, vec3(0.299, 0.587, 0.114));
+    gl_FragColor = vec4(edge < lo || edge > hi ? near : far, 1.0);
+}
+"#;
+
+struct Pass {
+    material: macroquad::material::Material,
+    scene: macroquad::texture::RenderTarget,
+    size: (u32, u32),
+}
+
+/// Fast approximate anti-aliasing: the scene is rendered offscreen and blurred along the
+/// edges found from its luminance, which smooths thin lines for the cost of a single pass.
+pub struct Fxaa {
+    pub enabled: bool,
+    pass: Option<Pass>,
+}
+
+impl Fxaa {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            pass: None,
+        }
+    }
+
+    /// The camera to draw the scene with, like `Bloom::scene_camera`.
+    pub fn scene_camera(
+        &mut self,
+        camera: macroquad::camera::Camera3D,
+    ) -> macroquad::camera::Camera3D {
+        let size = (
+            macroquad::window::screen_width() as u32,
+            macroquad::window::screen_height() as u32,
+        );
+        if self.pass.as_ref().is_none_or(|p| p.size != size) {
+            self.pass = pass(size);
+        }
+        let Some(pass) = &self.pass else {
+            return camera;
+        };
+        macroquad::camera::Camera3D {
+            render_target: Some(pass.scene.clone()),
+            ..camera
+        }
+    }
+
+    /// Draws the filtered scene to the screen; expects the default camera.
+    pub fn composite(&self) {
+        let Some(pass) = &self.pass else {
+            return;
+        };
+        let (width, height) = (pass.size.0 as f32, pass.size.1 as f32);
+        pass.material
+            .set_uniform("texel", macroquad::math::vec2(1.0 / width, 1.0 / height));
+        macroquad::material::gl_use_material(&pass.material);
+        macroquad::texture::draw_texture_ex(
+            &pass.scene.texture,
+            0.0,
+            0.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(width, height)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        macroquad::material::gl_use_default_material();
+    }
+}
+
+fn pass((width, height): (u32, u32)) -> Option<Pass> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some(Pass {
+        material: crate::bloom::material(FXAA, &[("texel", miniquad::UniformType::Float2)], false)?,
+        scene: crate::bloom::target(width, height, true),
+        size: (width, height),
+    })
+}
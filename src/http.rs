@@ -0,0 +1,192 @@
+use std::io::{Read, Write};
+
+const MAX_REQUEST: usize = 64 * 1024;
+/// Connections served at once; more are closed right away.
+const MAX_CONNECTIONS: usize = 16;
+
+/// Serves each connection on one background thread, which reads the request, waits for the
+/// response and writes it, so slow clients never hold up a frame; `poll` hands over the
+/// requests that have arrived completely.
+pub struct Server {
+    requests: std::sync::mpsc::Receiver<Request>,
+}
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+    reply: std::sync::mpsc::Sender<Vec<u8>>,
+}
+
+impl Server {
+    pub fn bind(address: &str) -> std::io::Result<Self> {
+        let listener = std::net::TcpListener::bind(address)?;
+        let (sender, requests) = std::sync::mpsc::channel();
+        let active = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        eprintln!("http: {err}");
+                        continue;
+                    }
+                };
+                let ordering = std::sync::atomic::Ordering::SeqCst;
+                if active.fetch_add(1, ordering) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, ordering);
+                    eprintln!("http: too many connections");
+                    continue;
+                }
+                let (sender, active) = (sender.clone(), active.clone());
+                std::thread::spawn(move || {
+                    if let Err(err) = serve(stream, &sender) {
+                        eprintln!("http: {err}");
+                    }
+                    active.fetch_sub(1, ordering);
+                });
+            }
+        });
+        Ok(Self { requests })
+    }
+
+    pub fn poll(&self) -> Vec<Request> {
+        self.requests.try_iter().collect()
+    }
+}
+
+/// Reads one request from `stream`, hands it to the main loop and writes back its response;
+/// a request dropped without one closes the connection.
+fn serve(
+    mut stream: std::net::TcpStream,
+    sender: &std::sync::mpsc::Sender<Request>,
+) -> std::io::Result<()> {
+    let (reply, response) = std::sync::mpsc::channel();
+    let request = read_request(&mut stream, reply)?;
+    if sender.send(request).is_err() {
+        return Ok(());
+    }
+    if let Ok(response) = response.recv() {
+        stream.set_write_timeout(Some(std::time::Duration::from_secs(5)))?;
+        stream.write_all(&response)?;
+    }
+    Ok(())
+}
+
+fn read_request(
+    stream: &mut std::net::TcpStream,
+    reply: std::sync::mpsc::Sender<Vec<u8>>,
+) -> std::io::Result<Request> {
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+    let mut data = Vec::new();
+    let mut chunk = [0; 4096];
+    let (head, length) = loop {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 || data.len() > MAX_REQUEST {
+            return Err(std::io::Error::other("incomplete request"));
+        }
+        data.extend_from_slice(&chunk[..n]);
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&data[..end]).into_owned();
+            let length = head
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            data.drain(..end + 4);
+            break (head, length);
+        }
+    };
+    while data.len() < length.min(MAX_REQUEST) {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..n]);
+    }
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    Ok(Request {
+        method: request_line.next().unwrap_or_default().to_owned(),
+        path: request_line.next().unwrap_or_default().to_owned(),
+        body: String::from_utf8_lossy(&data).into_owned(),
+        reply,
+    })
+}
+
+impl Request {
+    /// Hands the response to the connection's thread, which writes it, so a client that reads
+    /// slowly does not hold up the frame either.
+    pub fn respond(self, status: &str, content_type: &str, body: &[u8]) {
+        let mut response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(body);
+        let _ = self.reply.send(response);
+    }
+
+    pub fn text(self, status: &str, body: &str) {
+        self.respond(status, "text/plain", body.as_bytes());
+    }
+
+    /// The `name=value` pairs of a form body, percent-decoded.
+    pub fn form(&self) -> impl Iterator<Item = (String, String)> {
+        self.body
+            .split(['&', '\n'])
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (decode(name.trim()), decode(value.trim())))
+    }
+}
+
+/// Decodes `+` and `%XX` escapes of a form field, keeping malformed escapes as they are.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding() {
+        assert_eq!(decode("a+b"), "a b");
+        assert_eq!(decode("1%2B1"), "1+1");
+        assert_eq!(decode("%41%62"), "Ab");
+    }
+
+    #[test]
+    fn malformed_escapes_are_kept() {
+        assert_eq!(decode("50%4"), "50%4");
+        assert_eq!(decode("%"), "%");
+        assert_eq!(decode("%zz"), "%zz");
+        assert_eq!(decode("%+1"), "% 1");
+    }
+
+    #[test]
+    fn multibyte_text() {
+        assert_eq!(decode("R%C3%B6ssler"), "Rössler");
+        assert_eq!(decode("Rössler"), "Rössler");
+        assert_eq!(decode("%C3"), "\u{fffd}");
+    }
+}
@@ -0,0 +1,27 @@
+/// PNG bytes of `image`, whose rows run bottom to top as read back from the GPU.
+pub fn encode_png(image: &macroquad::texture::Image) -> Result<Vec<u8>, String> {
+    let row = image.width as usize * 4;
+    let flipped = image
+        .bytes
+        .chunks(row)
+        .rev()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&flipped))
+        .map_err(|err| err.to_string())?;
+    Ok(bytes)
+}
+
+/// Writes `image` to `path` like `encode_png`, reporting failures instead of panicking as
+/// `Image::export_png` does.
+pub fn save_png(image: &macroquad::texture::Image, path: &str) -> Result<(), String> {
+    let bytes = encode_png(image)?;
+    std::fs::write(path, bytes).map_err(|err| format!("{path}: {err}"))
+}
@@ -0,0 +1,196 @@
+use macroquad::miniquad;
+
+const VERTEX: &str = r#"#version 100
+attribute vec2 corner;
+attribute vec3 position;
+attribute vec4 color;
+uniform mat4 view_projection;
+uniform vec3 right;
+uniform vec3 up;
+uniform float radius;
+varying mediump vec2 uv;
+varying lowp vec4 tint;
+void main() {
+    vec3 world = position + (right * corner.x + up * corner.y) * radius;
+    gl_Position = view_projection * vec4(world, 1.0);
+    uv = corner;
+    tint = color;
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 100
+precision mediump float;
+varying mediump vec2 uv;
+varying lowp vec4 tint;
+void main() {
+    float r2 = dot(uv, uv);
+    if (r2 > 1.0) {
+        discard;
+    }
+    vec3 normal = vec3(uv, sqrt(1.0 - r2));
+    float light = 0.3 + 0.7 * max(dot(normal, normalize(vec3(0.4, 0.6, 0.7))), 0.0);
+    gl_FragColor = vec4(tint.rgb * light, tint.a);
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Instance {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+#[repr(C)]
+struct Uniforms {
+    view_projection: macroquad::math::Mat4,
+    right: macroquad::math::Vec3,
+    up: macroquad::math::Vec3,
+    radius: f32,
+}
+
+pub struct InstancedSpheres {
+    pipeline: miniquad::Pipeline,
+    bindings: miniquad::Bindings,
+    capacity: usize,
+}
+
+impl InstancedSpheres {
+    pub fn new() -> Self {
+        let ctx = unsafe { macroquad::window::get_internal_gl() }.quad_context;
+        let corners: [[f32; 2]; 4] = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+        let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+        let corner_buffer = ctx.new_buffer(
+            miniquad::BufferType::VertexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&corners),
+        );
+        let index_buffer = ctx.new_buffer(
+            miniquad::BufferType::IndexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&indices),
+        );
+        let capacity = 1024;
+        let instance_buffer = new_instance_buffer(ctx, capacity);
+        let shader = ctx
+            .new_shader(
+                miniquad::ShaderSource::Glsl {
+                    vertex: VERTEX,
+                    fragment: FRAGMENT,
+                },
+                miniquad::ShaderMeta {
+                    images: Vec::new(),
+                    uniforms: miniquad::UniformBlockLayout {
+                        uniforms: vec![
+                            miniquad::UniformDesc::new(
+                                "view_projection",
+                                miniquad::UniformType::Mat4,
+                            ),
+                            miniquad::UniformDesc::new("right", miniquad::UniformType::Float3),
+                            miniquad::UniformDesc::new("up", miniquad::UniformType::Float3),
+                            miniquad::UniformDesc::new("radius", miniquad::UniformType::Float1),
+                        ],
+                    },
+                },
+            )
+            .expect("instanced sphere shader must compile");
+        let pipeline = ctx.new_pipeline(
+            &[
+                miniquad::BufferLayout::default(),
+                miniquad::BufferLayout {
+                    step_func: miniquad::VertexStep::PerInstance,
+                    ..Default::default()
+                },
+            ],
+            &[
+                miniquad::VertexAttribute::with_buffer("corner", miniquad::VertexFormat::Float2, 0),
+                miniquad::VertexAttribute::with_buffer(
+                    "position",
+                    miniquad::VertexFormat::Float3,
+                    1,
+                ),
+                miniquad::VertexAttribute::with_buffer("color", miniquad::VertexFormat::Float4, 1),
+            ],
+            shader,
+            miniquad::PipelineParams {
+                depth_test: miniquad::Comparison::LessOrEqual,
+                depth_write: true,
+                ..Default::default()
+            },
+        );
+        Self {
+            pipeline,
+            bindings: miniquad::Bindings {
+                vertex_buffers: vec![corner_buffer, instance_buffer],
+                index_buffer,
+                images: Vec::new(),
+            },
+            capacity,
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        camera: &macroquad::camera::Camera3D,
+        instances: &[Instance],
+        radius: f32,
+    ) {
+        if instances.is_empty() {
+            return;
+        }
+        let mut gl = unsafe { macroquad::window::get_internal_gl() };
+        gl.flush();
+        let ctx = gl.quad_context;
+        if instances.len() > self.capacity {
+            ctx.delete_buffer(self.bindings.vertex_buffers[1]);
+            self.capacity = instances.len().next_power_of_two();
+            self.bindings.vertex_buffers[1] = new_instance_buffer(ctx, self.capacity);
+        }
+        ctx.buffer_update(
+            self.bindings.vertex_buffers[1],
+            miniquad::BufferSource::slice(instances),
+        );
+
+        let forward = (camera.target - camera.position).normalize();
+        let right = forward.cross(camera.up).normalize();
+        let uniforms = Uniforms {
+            view_projection: macroquad::camera::Camera::matrix(camera),
+            right,
+            up: right.cross(forward),
+            radius,
+        };
+        let pass = macroquad::camera::Camera::render_pass(camera);
+        let (width, height) = match &pass {
+            Some(pass) => {
+                let texture = ctx.render_pass_texture(pass.raw_miniquad_id());
+                ctx.texture_size(texture)
+            }
+            None => {
+                let (w, h) = miniquad::window::screen_size();
+                (w as u32, h as u32)
+            }
+        };
+        match &pass {
+            Some(pass) => {
+                ctx.begin_pass(Some(pass.raw_miniquad_id()), miniquad::PassAction::Nothing)
+            }
+            None => ctx.begin_default_pass(miniquad::PassAction::Nothing),
+        }
+        ctx.apply_viewport(0, 0, width as i32, height as i32);
+        ctx.apply_pipeline(&self.pipeline);
+        ctx.apply_bindings(&self.bindings);
+        ctx.apply_uniforms(miniquad::UniformsSource::table(&uniforms));
+        ctx.draw(0, 6, instances.len() as i32);
+        ctx.end_render_pass();
+    }
+}
+
+fn new_instance_buffer(
+    ctx: &mut dyn miniquad::RenderingBackend,
+    capacity: usize,
+) -> miniquad::BufferId {
+    ctx.new_buffer(
+        miniquad::BufferType::VertexBuffer,
+        miniquad::BufferUsage::Stream,
+        miniquad::BufferSource::empty::<Instance>(capacity),
+    )
+}
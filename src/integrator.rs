@@ -0,0 +1,271 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum Integrator {
+    Euler,
+    Rk4,
+    DormandPrince,
+    Fehlberg,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 4] = [
+        Integrator::Euler,
+        Integrator::Rk4,
+        Integrator::DormandPrince,
+        Integrator::Fehlberg,
+    ];
+    pub const NAMES: [&'static str; 4] = [
+        "euler",
+        "rk4",
+        "adaptive (dormand-prince)",
+        "adaptive (rkf45)",
+    ];
+
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, Integrator::DormandPrince | Integrator::Fehlberg)
+    }
+
+    pub fn evaluations(&self) -> usize {
+        match self {
+            Integrator::Euler => 1,
+            Integrator::Rk4 => 4,
+            Integrator::DormandPrince => 7,
+            Integrator::Fehlberg => 6,
+        }
+    }
+
+    pub fn step<P: Phase>(&self, f: impl Fn(P) -> P, p: P, dt: f32) -> P {
+        match self {
+            Integrator::Euler => p + f(p) * dt,
+            Integrator::Rk4 => {
+                let k1 = f(p);
+                let k2 = f(p + k1 * (dt / 2.0));
+                let k3 = f(p + k2 * (dt / 2.0));
+                let k4 = f(p + k3 * dt);
+                p + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (dt / 6.0)
+            }
+            Integrator::Fehlberg => fehlberg(f, p, dt).0,
+            _ => dormand_prince(f, p, dt).0,
+        }
+    }
+
+    /// One step of the embedded pair together with its error estimate. Fixed-step integrators
+    /// fall back to Dormand-Prince.
+    fn embedded<P: Phase>(&self, f: impl Fn(P) -> P, p: P, h: f32) -> (P, P) {
+        match self {
+            Integrator::Fehlberg => fehlberg(f, p, h),
+            _ => dormand_prince(f, p, h),
+        }
+    }
+
+    pub fn adaptive_step<P: Phase>(
+        &self,
+        f: impl Fn(P) -> P,
+        p: P,
+        h: f32,
+        tolerance: f32,
+        h_min: f32,
+    ) -> AdaptiveStep<P> {
+        let mut h = h.max(h_min);
+        let mut rejected = 0;
+        loop {
+            let (next, error) = self.embedded(&f, p, h);
+            let scale = (P::ONE + p.abs().max(next.abs())) * tolerance;
+            let ratio = (error / scale).length() / (P::DIMENSION as f32).sqrt();
+            let factor = if ratio > 0.0 {
+                (0.9 * ratio.powf(-0.2)).clamp(0.2, 5.0)
+            } else {
+                5.0
+            };
+            if ratio <= 1.0 || h <= h_min || !ratio.is_finite() {
+                return AdaptiveStep {
+                    p: next,
+                    h,
+                    next_h: h * factor,
+                    error: error.length(),
+                    rejected,
+                };
+            }
+            h = (h * factor).max(h_min);
+            rejected += 1;
+        }
+    }
+}
+
+/// State vectors the integrators can advance: the usual three coordinates, or four for
+/// hyperchaotic systems.
+pub trait Phase:
+    Copy
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<f32, Output = Self>
+    + std::ops::Div<Output = Self>
+    + std::ops::Neg<Output = Self>
+{
+    const DIMENSION: usize;
+    const ONE: Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn length(self) -> f32;
+}
+
+impl Phase for macroquad::math::Vec3 {
+    const DIMENSION: usize = 3;
+    const ONE: Self = macroquad::math::Vec3::ONE;
+
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.max(other)
+    }
+
+    fn length(self) -> f32 {
+        self.length()
+    }
+}
+
+impl Phase for macroquad::math::Vec4 {
+    const DIMENSION: usize = 4;
+    const ONE: Self = macroquad::math::Vec4::ONE;
+
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.max(other)
+    }
+
+    fn length(self) -> f32 {
+        self.length()
+    }
+}
+
+/// Double precision state for long runs. The step sizes stay f32; every product is formed in
+/// f64 so the increments are not rounded against the much larger coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Precise(pub macroquad::math::DVec3);
+
+impl std::ops::Add for Precise {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl std::ops::Sub for Precise {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl std::ops::Mul<f32> for Precise {
+    type Output = Self;
+
+    fn mul(self, factor: f32) -> Self {
+        Self(self.0 * factor as f64)
+    }
+}
+
+impl std::ops::Div for Precise {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self(self.0 / other.0)
+    }
+}
+
+impl std::ops::Neg for Precise {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Phase for Precise {
+    const DIMENSION: usize = 3;
+    const ONE: Self = Self(macroquad::math::DVec3::ONE);
+
+    fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self(self.0.max(other.0))
+    }
+
+    fn length(self) -> f32 {
+        self.0.length() as f32
+    }
+}
+
+pub fn reversed<P: Phase>(f: impl Fn(P) -> P) -> impl Fn(P) -> P {
+    move |p| -f(p)
+}
+
+pub struct AdaptiveStep<P> {
+    pub p: P,
+    pub h: f32,
+    pub next_h: f32,
+    pub error: f32,
+    pub rejected: usize,
+}
+
+fn dormand_prince<P: Phase>(f: impl Fn(P) -> P, p: P, h: f32) -> (P, P) {
+    let k1 = f(p);
+    let k2 = f(p + k1 * (h / 5.0));
+    let k3 = f(p + (k1 * (3.0 / 40.0) + k2 * (9.0 / 40.0)) * h);
+    let k4 = f(p + (k1 * (44.0 / 45.0) - k2 * (56.0 / 15.0) + k3 * (32.0 / 9.0)) * h);
+    let k5 = f(p
+        + (k1 * (19372.0 / 6561.0) - k2 * (25360.0 / 2187.0) + k3 * (64448.0 / 6561.0)
+            - k4 * (212.0 / 729.0))
+            * h);
+    let k6 = f(p
+        + (k1 * (9017.0 / 3168.0) - k2 * (355.0 / 33.0)
+            + k3 * (46732.0 / 5247.0)
+            + k4 * (49.0 / 176.0)
+            - k5 * (5103.0 / 18656.0))
+            * h);
+    let next = p
+        + (k1 * (35.0 / 384.0) + k3 * (500.0 / 1113.0) + k4 * (125.0 / 192.0)
+            - k5 * (2187.0 / 6784.0)
+            + k6 * (11.0 / 84.0))
+            * h;
+    let k7 = f(next);
+    let error = (k1 * (71.0 / 57600.0) - k3 * (71.0 / 16695.0) + k4 * (71.0 / 1920.0)
+        - k5 * (17253.0 / 339200.0)
+        + k6 * (22.0 / 525.0)
+        - k7 * (1.0 / 40.0))
+        * h;
+    (next, error)
+}
+
+/// Runge-Kutta-Fehlberg 4(5), advanced with the fifth-order solution.
+fn fehlberg<P: Phase>(f: impl Fn(P) -> P, p: P, h: f32) -> (P, P) {
+    let k1 = f(p);
+    let k2 = f(p + k1 * (h / 4.0));
+    let k3 = f(p + (k1 * (3.0 / 32.0) + k2 * (9.0 / 32.0)) * h);
+    let k4 = f(p + (k1 * (1932.0 / 2197.0) - k2 * (7200.0 / 2197.0) + k3 * (7296.0 / 2197.0)) * h);
+    let k5 = f(
+        p + (k1 * (439.0 / 216.0) - k2 * 8.0 + k3 * (3680.0 / 513.0) - k4 * (845.0 / 4104.0)) * h
+    );
+    let k6 = f(p
+        + (k1 * (-8.0 / 27.0) + k2 * 2.0 - k3 * (3544.0 / 2565.0) + k4 * (1859.0 / 4104.0)
+            - k5 * (11.0 / 40.0))
+            * h);
+    let next = p
+        + (k1 * (16.0 / 135.0) + k3 * (6656.0 / 12825.0) + k4 * (28561.0 / 56430.0)
+            - k5 * (9.0 / 50.0)
+            + k6 * (2.0 / 55.0))
+            * h;
+    let error = (k1 * (1.0 / 360.0) - k3 * (128.0 / 4275.0) - k4 * (2197.0 / 75240.0)
+        + k5 * (1.0 / 50.0)
+        + k6 * (2.0 / 55.0))
+        * h;
+    (next, error)
+}
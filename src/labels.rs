@@ -0,0 +1,81 @@
+pub struct Label {
+    pub position: macroquad::math::Vec3,
+    pub text: String,
+    pub color: macroquad::color::Color,
+    pub size: f32,
+    pub depth_test: bool,
+}
+
+impl Label {
+    pub fn new(
+        position: macroquad::math::Vec3,
+        text: String,
+        color: macroquad::color::Color,
+    ) -> Self {
+        Self {
+            position,
+            text,
+            color,
+            size: 20.0,
+            depth_test: false,
+        }
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        draw_label(
+            camera,
+            transform(self.position),
+            &self.text,
+            self.color,
+            self.size,
+            self.depth_test,
+        );
+    }
+}
+
+pub fn draw_label(
+    camera: &macroquad::camera::Camera3D,
+    position: macroquad::math::Vec3,
+    text: &str,
+    color: macroquad::color::Color,
+    size: f32,
+    depth_test: bool,
+) {
+    let forward = (camera.target - camera.position).normalize();
+    let right = forward.cross(camera.up).normalize();
+    let up = right.cross(forward);
+    let to_label = position - camera.position;
+    if to_label.dot(forward) <= 0.0 {
+        return;
+    }
+    let pixel_size = 2.0 * (camera.fovy / 2.0).tan() / macroquad::window::screen_height();
+    let scale = to_label.length() * pixel_size;
+    let model = macroquad::math::Mat4::from_cols(
+        (right * scale).extend(0.0),
+        (-up * scale).extend(0.0),
+        forward.extend(0.0),
+        position.extend(1.0),
+    );
+
+    let font_size = size as u16;
+    let dimensions = macroquad::text::measure_text(text, None, font_size, 1.0);
+    let gl = unsafe { macroquad::window::get_internal_gl().quad_gl };
+    gl.push_model_matrix(model);
+    gl.depth_test(depth_test);
+    macroquad::text::draw_text_ex(
+        text,
+        -dimensions.width / 2.0,
+        dimensions.offset_y / 2.0,
+        macroquad::text::TextParams {
+            font_size,
+            color,
+            ..Default::default()
+        },
+    );
+    gl.depth_test(true);
+    gl.pop_model_matrix();
+}
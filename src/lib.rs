@@ -0,0 +1,65 @@
+#![allow(clippy::new_without_default)]
+
+pub mod analysis;
+pub mod assimilation;
+pub mod batch;
+pub mod bifurcation;
+pub mod bloom;
+pub mod bookmarks;
+pub mod butterfly;
+pub mod camera;
+pub mod camera_path;
+pub mod comparison;
+pub mod config;
+pub mod console;
+pub mod correlation;
+pub mod coupling;
+pub mod curve;
+pub mod delay;
+pub mod density;
+pub mod diagnostics;
+pub mod dissipation;
+pub mod ensemble;
+pub mod explain;
+pub mod explorer;
+pub mod exposure;
+pub mod expression;
+pub mod fractional;
+pub mod ftle;
+pub mod ftle_field;
+pub mod fxaa;
+pub mod http;
+pub mod image_file;
+pub mod instancing;
+pub mod integrator;
+pub mod labels;
+pub mod lighting;
+pub mod lyapunov;
+pub mod manifold;
+pub mod palette;
+pub mod panel;
+pub mod periodic;
+pub mod plugin;
+pub mod poincare;
+pub mod profile;
+pub mod profiler;
+pub mod recurrence;
+pub mod render;
+pub mod return_map;
+pub mod rng;
+pub mod script_system;
+pub mod shadowing;
+pub mod spectrum;
+pub mod state;
+pub mod stereo;
+pub mod swarm;
+pub mod sync;
+pub mod system;
+pub mod tangent;
+pub mod theme;
+pub mod time_series;
+pub mod trajectory;
+pub mod volume;
+pub mod zero_one;
+
+pub use state::State;
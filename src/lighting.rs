@@ -0,0 +1,65 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// A camera facing strip as wide as the trail's thickness in pixels.
+    Ribbon,
+    /// A round tube of `radius` in scene units.
+    Tube,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 2] = [Shape::Ribbon, Shape::Tube];
+    pub const NAMES: [&'static str; 2] = ["ribbon", "tube"];
+}
+
+pub struct Lighting {
+    pub enabled: bool,
+    pub shape: Shape,
+    pub radius: f32,
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub ambient: f32,
+    pub specular: f32,
+    pub shininess: f32,
+}
+
+impl Lighting {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            shape: Shape::Ribbon,
+            radius: 0.3,
+            azimuth: 0.8,
+            elevation: 0.6,
+            ambient: 0.3,
+            specular: 0.5,
+            shininess: 32.0,
+        }
+    }
+
+    pub fn direction(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(
+            self.elevation.cos() * self.azimuth.sin(),
+            self.elevation.sin(),
+            self.elevation.cos() * self.azimuth.cos(),
+        )
+    }
+
+    pub fn shade(
+        &self,
+        color: macroquad::color::Color,
+        normal: macroquad::math::Vec3,
+        view: macroquad::math::Vec3,
+    ) -> macroquad::color::Color {
+        let light = self.direction();
+        let diffuse = normal.dot(light).max(0.0);
+        let half = (light + view).normalize_or_zero();
+        let specular = self.specular * normal.dot(half).max(0.0).powf(self.shininess);
+        let intensity = self.ambient + (1.0 - self.ambient) * diffuse;
+        macroquad::color::Color::new(
+            (color.r * intensity + specular).min(1.0),
+            (color.g * intensity + specular).min(1.0),
+            (color.b * intensity + specular).min(1.0),
+            color.a,
+        )
+    }
+}
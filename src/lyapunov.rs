@@ -0,0 +1,175 @@
+const SEPARATION: f32 = 1e-3;
+const RENORMALIZE_EVERY: usize = 10;
+
+pub fn largest_exponent(
+    f: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    integrator: crate::integrator::Integrator,
+    start: macroquad::math::Vec3,
+    dt: f32,
+    transient: usize,
+    steps: usize,
+) -> f32 {
+    let mut p = start;
+    for _ in 0..transient {
+        p = integrator.step(&f, p, dt);
+        if !p.is_finite() {
+            return f32::NAN;
+        }
+    }
+
+    let mut q = p + macroquad::math::vec3(SEPARATION, 0.0, 0.0);
+    let mut sum = 0.0;
+    for i in 1..=steps {
+        p = integrator.step(&f, p, dt);
+        q = integrator.step(&f, q, dt);
+        if i % RENORMALIZE_EVERY == 0 {
+            let d = (q - p).length();
+            if !d.is_finite() || d == 0.0 {
+                return f32::NAN;
+            }
+            sum += (d / SEPARATION).ln();
+            q = p + (q - p) * (SEPARATION / d);
+        }
+    }
+    sum / (steps as f32 * dt)
+}
+
+/// All three exponents from tangent vectors evolved with the local Jacobian and re-orthonormalized
+/// every step, sorted from largest to smallest.
+pub fn spectrum(
+    f: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    jacobian: impl Fn(macroquad::math::Vec3) -> macroquad::math::Mat3,
+    integrator: crate::integrator::Integrator,
+    start: macroquad::math::Vec3,
+    dt: f32,
+    transient: usize,
+    steps: usize,
+) -> [f32; 3] {
+    let mut p = start;
+    for _ in 0..transient {
+        p = integrator.step(&f, p, dt);
+        if !p.is_finite() {
+            return [f32::NAN; 3];
+        }
+    }
+
+    let mut basis = macroquad::math::Mat3::IDENTITY;
+    let mut sums = [0.0_f64; 3];
+    for _ in 0..steps {
+        let j = jacobian(p) * dt;
+        let propagator = macroquad::math::Mat3::IDENTITY + j + j * j * 0.5;
+        p = integrator.step(&f, p, dt);
+        if !p.is_finite() {
+            return [f32::NAN; 3];
+        }
+        let mut vectors = [
+            propagator * basis.x_axis,
+            propagator * basis.y_axis,
+            propagator * basis.z_axis,
+        ];
+        for i in 0..3 {
+            for k in 0..i {
+                vectors[i] -= vectors[k] * vectors[i].dot(vectors[k]);
+            }
+            let length = vectors[i].length();
+            if !length.is_finite() || length == 0.0 {
+                return [f32::NAN; 3];
+            }
+            sums[i] += (length as f64).ln();
+            vectors[i] /= length;
+        }
+        basis = macroquad::math::Mat3::from_cols(vectors[0], vectors[1], vectors[2]);
+    }
+    let mut exponents = sums.map(|sum| (sum / (steps as f64 * dt as f64)) as f32);
+    exponents.sort_by(|a, b| b.total_cmp(a));
+    exponents
+}
+
+pub fn kaplan_yorke(exponents: &[f32; 3]) -> f32 {
+    if exponents.iter().any(|e| !e.is_finite()) {
+        return f32::NAN;
+    }
+    let mut sum = 0.0;
+    for (j, exponent) in exponents.iter().enumerate() {
+        if sum + exponent < 0.0 {
+            return j as f32 + sum / exponent.abs();
+        }
+        sum += exponent;
+    }
+    exponents.len() as f32
+}
+
+/// Benettin's algorithm run alongside the first trajectory: a shadow `SEPARATION` away is
+/// advanced with it and pulled back every `RENORMALIZE_EVERY` steps. The estimate averages the
+/// logarithmic growth over the last `window` time units, so it follows the sliders.
+pub struct Live {
+    pub enabled: bool,
+    pub window: f32,
+    shadow: Option<macroquad::math::Vec3>,
+    steps: usize,
+    elapsed: f32,
+    growth: std::collections::VecDeque<(f32, f32)>,
+}
+
+impl Live {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            window: 50.0,
+            shadow: None,
+            steps: 0,
+            elapsed: 0.0,
+            growth: std::collections::VecDeque::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.shadow = None;
+        self.steps = 0;
+        self.elapsed = 0.0;
+        self.growth.clear();
+    }
+
+    /// The mean growth rate over the window, once it holds enough of it to be meaningful.
+    pub fn estimate(&self) -> Option<f32> {
+        let (log, time) = self
+            .growth
+            .iter()
+            .fold((0.0, 0.0), |(log, time), (l, t)| (log + l, time + t));
+        (time >= self.window.min(5.0)).then(|| log / time)
+    }
+
+    pub fn observe(
+        &mut self,
+        head: macroquad::math::Vec3,
+        h: f32,
+        advance: impl Fn(macroquad::math::Vec3, f32) -> macroquad::math::Vec3,
+    ) {
+        // A new shadow starts at the head, so this step has not separated them yet.
+        let Some(shadow) = self.shadow.map(|shadow| advance(shadow, h)) else {
+            self.shadow = Some(head + macroquad::math::vec3(SEPARATION, 0.0, 0.0));
+            return;
+        };
+        self.shadow = Some(shadow);
+        self.steps += 1;
+        self.elapsed += h;
+        if self.steps < RENORMALIZE_EVERY {
+            return;
+        }
+        let d = (shadow - head).length();
+        if d.is_finite() && d > 0.0 {
+            self.growth.push_back(((d / SEPARATION).ln(), self.elapsed));
+            self.shadow = Some(head + (shadow - head) * (SEPARATION / d));
+        } else {
+            self.shadow = None;
+        }
+        self.steps = 0;
+        self.elapsed = 0.0;
+        let mut total = self.growth.iter().map(|(_, t)| t).sum::<f32>();
+        while total > self.window
+            && let Some((_, t)) = self.growth.pop_front()
+        {
+            total -= t;
+        }
+    }
+}
@@ -1,207 +1,1210 @@
-use itertools::Itertools;
-use macroquad::prelude::*;
+use lorenz::state::{
+    BlowupPolicy, CAMERA_PATH, NormalizationMode, REFERENCE_FRAME_TIME, SpawnTarget, State,
+};
+use lorenz::{
+    analysis, batch, bookmarks, camera, camera_path, config, console, curve, ensemble, explorer,
+    integrator, labels, lighting, palette, poincare, profiler, spectrum, sync, system, theme,
+    trajectory,
+};
 
-fn lorenz(p: &macroquad::math::Vec3, sigma: f32, beta: f32, rho: f32) -> macroquad::math::Vec3 {
-    let x = sigma * (p.y - p.x);
-    let y = p.x * (rho - p.z) - p.y;
-    let z = p.x * p.y - beta * p.z;
-    macroquad::math::Vec3 { x, y, z }
+/// `input_text` that sets `typing` when the text was changed from the keyboard, since macroquad
+/// does not tell which field has the input focus.
+fn text_field(
+    ui: &mut macroquad::ui::Ui,
+    id: macroquad::ui::Id,
+    label: &str,
+    text: &mut String,
+    typing: &mut bool,
+) {
+    let before = text.clone();
+    ui.input_text(id, label, text);
+    *typing |= *text != before;
 }
 
-fn lorenz_integrate(
-    p: &macroquad::math::Vec3,
-    sigma: f32,
-    beta: f32,
-    rho: f32,
-    dt: f32,
-) -> macroquad::math::Vec3 {
-    let d = lorenz(p, sigma, beta, rho);
-    *p + d * dt
-}
-
-struct State {
-    sigma: f32,
-    beta: f32,
-    rho: f32,
-    dt: f32,
-    tail: f32,
-    speed: f32,
-    start: macroquad::math::Vec3,
-    points: std::collections::VecDeque<macroquad::math::Vec3>,
-}
-
-impl State {
-    fn new() -> Self {
-        let start = macroquad::math::vec3(0.0, 1.0, 1.05);
-        Self {
-            sigma: 10.0,
-            beta: 8.0 / 3.0,
-            rho: 28.0,
-            dt: 0.005,
-            tail: 5_000.0,
-            speed: 10.0,
-            start,
-            points: std::collections::VecDeque::from([start]),
-        }
-    }
-
-    fn step(&mut self) {
-        let max_len = self.tail as usize;
-        for _ in 0..self.speed as usize {
-            self.points.push_back(lorenz_integrate(
-                self.points.back().unwrap(),
-                self.sigma,
-                self.beta,
-                self.rho,
-                self.dt,
-            ));
-            while self.points.len() > max_len {
-                self.points.pop_front();
+fn draw_ui(state: &mut State, camera: &mut camera::OrbitCamera) {
+    let previous_theme = state.theme;
+    macroquad::ui::root_ui().window(
+        1,
+        macroquad::math::vec2(10.0, 10.0),
+        macroquad::math::vec2(250.0, 640.0),
+        |ui| {
+            let systems = system::System::all();
+            let names = systems.iter().map(|s| s.name()).collect::<Vec<_>>();
+            let mut system = systems.iter().position(|s| *s == state.system).unwrap();
+            ui.combo_box(17, "system", &names, &mut system);
+            state.switch_system(systems[system], camera);
+            for (i, (value, param)) in state
+                .params
+                .iter_mut()
+                .zip(state.system.params())
+                .enumerate()
+            {
+                ui.slider(
+                    macroquad::hash!("param", i),
+                    param.name,
+                    param.min..param.max,
+                    value,
+                );
             }
-        }
-    }
-
-    fn draw(&self) {
-        macroquad::models::draw_grid(
-            12,
-            10.,
-            macroquad::color::DARKGRAY,
-            macroquad::color::DARKGRAY,
-        );
-        self.points
-            .iter()
-            .tuple_windows()
-            .enumerate()
-            .for_each(|(i, (start, end))| {
-                let d = (*end - *start).length().clamp(0.0, 2.0) / 2.0;
-                macroquad::models::draw_line_3d(
-                    *start,
-                    *end,
-                    macroquad::color::hsl_to_rgb(1.0 - d, 1.0, 0.5)
-                        .with_alpha(i as f32 / self.points.len() as f32),
+            if state.system.dimension() == 4
+                && !state.system.driven()
+                && state
+                    .system
+                    .joints(state.system.start4(), &state.params)
+                    .is_none()
+            {
+                let projection = state.projection;
+                ui.combo_box(
+                    85,
+                    "projection",
+                    &system::PROJECTION_NAMES,
+                    &mut state.projection,
                 );
+                if state.projection != projection {
+                    state.reset_position();
+                }
+            }
+            ui.tree_node(83, "custom system", |ui| {
+                state.typing |= ui.editbox(
+                    84,
+                    macroquad::math::vec2(225.0, 180.0),
+                    &mut state.custom_system,
+                );
+                if ui.button(None, "compile") {
+                    state.define_custom_system(camera);
+                }
+                if !state.custom_error.is_empty() {
+                    ui.label(None, &state.custom_error);
+                }
             });
+            let tail = state.tail;
+            ui.slider(5, "tail length", 0.1..100.0, &mut state.tail);
+            if state.tail != tail {
+                for trajectory in state.trajectories.iter_mut() {
+                    trajectory.tail = state.tail;
+                }
+            }
+            ui.slider(6, "speed", 0.1..20.0, &mut state.speed);
+            ui.label(
+                None,
+                &format!(
+                    "{:.2} time units per second",
+                    state.speed * state.dt / REFERENCE_FRAME_TIME
+                ),
+            );
+            ui.slider(86, "noise", 0.0..10.0, &mut state.noise);
+            let mut policy = BlowupPolicy::ALL
+                .iter()
+                .position(|p| *p == state.blowup_policy)
+                .unwrap();
+            ui.combo_box(92, "on divergence", &BlowupPolicy::NAMES, &mut policy);
+            state.blowup_policy = BlowupPolicy::ALL[policy];
+            if state.system.fractional_order(&state.params).is_some() {
+                ui.slider(23, "memory", 10.0..5_000.0, &mut state.fractional_memory);
+            }
+            if ui.button(None, "reset params") {
+                state.params = state.system.defaults();
+            }
+            if ui.button(None, "reset position") {
+                state.reset_position();
+            }
+            if ui.button(None, "random start") {
+                state.random_start();
+            }
+            if ui.button(None, "randomize params") {
+                state.randomize_params();
+            }
+            text_field(ui, 16, "seed", &mut state.seed_text, &mut state.typing);
+            if ui.button(None, "reseed") {
+                match state.seed_text.trim().parse() {
+                    Ok(seed) => state.rng.reseed(seed),
+                    Err(_) => state.seed_text = state.rng.seed().to_string(),
+                }
+            }
+            let names = state
+                .themes
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>();
+            ui.combo_box(13, "theme", &names, &mut state.theme);
+            if state.theme != previous_theme {
+                state.colormap = state.theme().colormap;
+            }
+            let mut colormap = palette::Colormap::ALL
+                .iter()
+                .position(|c| *c == state.colormap)
+                .unwrap();
+            ui.combo_box(12, "colormap", &palette::Colormap::NAMES, &mut colormap);
+            state.colormap = palette::Colormap::ALL[colormap];
+            let mut coloring = trajectory::Coloring::ALL
+                .iter()
+                .position(|c| *c == state.trail_coloring)
+                .unwrap();
+            ui.combo_box(
+                69,
+                "trail color",
+                &trajectory::Coloring::NAMES,
+                &mut coloring,
+            );
+            state.trail_coloring = trajectory::Coloring::ALL[coloring];
+            if state.trail_coloring == trajectory::Coloring::Ftle {
+                ui.slider(70, "ftle window", 0.1..5.0, &mut state.ftle_window);
+                ui.slider(71, "ftle scale", 0.5..20.0, &mut state.ftle_scale);
+            }
+            ui.slider(91, "smoothing", 1.0..8.0, &mut state.subdivisions);
+            ui.slider(121, "line width", 0.5..8.0, &mut state.line_width);
+            ui.slider(
+                136,
+                "tail detail",
+                1_000.0..100_000.0,
+                &mut state.tail_detail,
+            );
+            ui.checkbox(119, "points", &mut state.point_cloud);
+            if state.point_cloud || state.system.discrete() {
+                ui.slider(120, "point size", 1.0..12.0, &mut state.point_size);
+            }
+            ui.separator();
+            let mut mode = state.normalization.mode as usize;
+            ui.combo_box(7, "normalize", &["off", "auto", "manual"], &mut mode);
+            state.normalization.mode = match mode {
+                1 => NormalizationMode::Auto,
+                2 => NormalizationMode::Manual,
+                _ => NormalizationMode::Off,
+            };
+            if state.normalization.mode == NormalizationMode::Manual {
+                ui.slider(
+                    8,
+                    "center x",
+                    -100.0..100.0,
+                    &mut state.normalization.center.x,
+                );
+                ui.slider(
+                    9,
+                    "center y",
+                    -100.0..100.0,
+                    &mut state.normalization.center.y,
+                );
+                ui.slider(
+                    10,
+                    "center z",
+                    -100.0..100.0,
+                    &mut state.normalization.center.z,
+                );
+                ui.slider(11, "scale", 0.01..10.0, &mut state.normalization.scale);
+            }
+            let head = state.trajectories[0].head();
+            ui.label(
+                None,
+                &format!("head: {:.2}, {:.2}, {:.2}", head.x, head.y, head.z),
+            );
+            ui.separator();
+            ui.checkbox(14, "labels", &mut state.show_labels);
+            ui.checkbox(130, "coordinate axes", &mut state.show_axes);
+            ui.checkbox(134, "shadows on planes", &mut state.show_shadows);
+            ui.checkbox(110, "equilibria", &mut state.show_equilibria);
+            if state.show_equilibria {
+                ui.checkbox(115, "manifolds", &mut state.manifolds.visible);
+                if state.manifolds.visible {
+                    ui.slider(
+                        116,
+                        "manifold length",
+                        1.0..50.0,
+                        &mut state.manifolds.length,
+                    );
+                }
+            }
+            text_field(
+                ui,
+                15,
+                "annotation",
+                &mut state.annotation_text,
+                &mut state.typing,
+            );
+            if ui.button(None, "annotate head") && !state.annotation_text.is_empty() {
+                state.annotations.push(labels::Label::new(
+                    head,
+                    std::mem::take(&mut state.annotation_text),
+                    macroquad::color::YELLOW,
+                ));
+            }
+            if !state.annotations.is_empty() && ui.button(None, "clear annotations") {
+                state.annotations.clear();
+            }
+            ui.separator();
+            ui.tree_node(24, "analysis", |ui| {
+                if explorer::ParameterPlane::available(state.system) {
+                    ui.checkbox(18, "parameter plane", &mut state.parameter_plane.visible);
+                }
+                if state.parameter_plane.visible && ui.button(None, "compute plane") {
+                    state.parameter_plane.compute(
+                        state.system,
+                        &state.params,
+                        state.integrator,
+                        state.dt,
+                    );
+                }
+                ui.checkbox(20, "return map", &mut state.return_map.visible);
+                if state.return_map.visible {
+                    ui.checkbox(21, "cobweb", &mut state.return_map.cobweb);
+                    ui.slider(
+                        22,
+                        "cobweb steps",
+                        1.0..30.0,
+                        &mut state.return_map.cobweb_steps,
+                    );
+                }
+                ui.checkbox(50, "poincare section", &mut state.poincare.visible);
+                if state.poincare.visible {
+                    let axis = state.poincare.axis;
+                    ui.combo_box(51, "plane axis", &poincare::AXES, &mut state.poincare.axis);
+                    let offset = state.poincare.offset;
+                    ui.slider(52, "plane offset", -50.0..50.0, &mut state.poincare.offset);
+                    if let Some(rho) = state.system.params().iter().position(|p| p.name == "rho")
+                        && ui.button(None, "plane z = rho - 1")
+                    {
+                        state.poincare.axis = 2;
+                        state.poincare.offset = state.params[rho] - 1.0;
+                    }
+                    if state.poincare.axis != axis || state.poincare.offset != offset {
+                        state.poincare.clear();
+                    }
+                    ui.checkbox(101, "show in scene", &mut state.poincare.in_scene);
+                    if state.poincare.is_recording() {
+                        if ui.button(None, "stop recording") {
+                            state.poincare.stop_recording();
+                        }
+                    } else if ui.button(None, "record section") {
+                        state.poincare.start_recording(&state.render_settings);
+                    }
+                }
+                ui.checkbox(105, "time series", &mut state.time_series.visible);
+                if state.time_series.visible {
+                    ui.slider(
+                        106,
+                        "steps shown",
+                        100.0..20_000.0,
+                        &mut state.time_series.window,
+                    );
+                }
+                ui.checkbox(107, "power spectrum", &mut state.spectrum.visible);
+                if state.spectrum.visible {
+                    ui.combo_box(108, "coordinate", &poincare::AXES, &mut state.spectrum.axis);
+                    ui.combo_box(
+                        109,
+                        "samples",
+                        &spectrum::SIZE_NAMES,
+                        &mut state.spectrum.size,
+                    );
+                }
+                ui.checkbox(111, "correlation dimension", &mut state.correlation.visible);
+                if state.correlation.visible && ui.button(None, "estimate dimension") {
+                    state.correlation.compute(&state.trajectories[0].points);
+                }
+                ui.checkbox(112, "visit density", &mut state.density.visible);
+                if state.density.visible {
+                    ui.combo_box(
+                        113,
+                        "project along",
+                        &poincare::AXES,
+                        &mut state.density.axis,
+                    );
+                    ui.slider(114, "half-life", 0.5..60.0, &mut state.density.half_life);
+                }
+                ui.checkbox(128, "density volume", &mut state.volume.visible);
+                if state.volume.visible {
+                    ui.slider(129, "volume opacity", 0.01..1.0, &mut state.volume.opacity);
+                }
+                ui.checkbox(78, "recurrence plot", &mut state.recurrence.visible);
+                if state.recurrence.visible {
+                    let recurrence = &mut state.recurrence;
+                    ui.slider(79, "threshold", 0.1..10.0, &mut recurrence.threshold);
+                    ui.slider(80, "window", 50.0..5_000.0, &mut recurrence.window);
+                }
+                if ui.button(None, "export analysis") {
+                    match state.export_analysis() {
+                        Ok(()) => eprintln!("analysis: wrote {}", analysis::ANALYSIS_PATH),
+                        Err(err) => eprintln!("analysis: {err}"),
+                    }
+                }
+                let enabled = state.zero_one.enabled;
+                ui.checkbox(81, "0-1 test", &mut state.zero_one.enabled);
+                if state.zero_one.enabled {
+                    if !enabled {
+                        state.zero_one.clear();
+                    }
+                    ui.slider(
+                        82,
+                        "sample interval",
+                        0.05..2.0,
+                        &mut state.zero_one.interval,
+                    );
+                }
+                let enabled = state.lyapunov.enabled;
+                ui.checkbox(99, "live lyapunov exponent", &mut state.lyapunov.enabled);
+                if state.lyapunov.enabled {
+                    if !enabled {
+                        state.lyapunov.clear();
+                    }
+                    ui.slider(
+                        100,
+                        "averaging window",
+                        5.0..500.0,
+                        &mut state.lyapunov.window,
+                    );
+                    let estimate = state.lyapunov.estimate();
+                    ui.label(
+                        None,
+                        &estimate.map_or("lambda1 collecting".to_owned(), |l| {
+                            format!("lambda1 = {l:.3}")
+                        }),
+                    );
+                }
+                ui.checkbox(63, "periodic orbits", &mut state.periodic.visible);
+                if !state.system.params().is_empty() {
+                    ui.checkbox(19, "bifurcation", &mut state.bifurcation.visible);
+                }
+                if state.bifurcation.visible {
+                    let params = state.system.params();
+                    let names = params.iter().map(|p| p.name).collect::<Vec<_>>();
+                    let mut param = state.bifurcation.param.min(names.len().saturating_sub(1));
+                    ui.combo_box(102, "sweep", &names, &mut param);
+                    let descriptor = &params[param];
+                    let bifurcation = &mut state.bifurcation;
+                    bifurcation.select(param, descriptor);
+                    let range = descriptor.min..descriptor.max;
+                    ui.slider(103, "from", range.clone(), &mut bifurcation.from);
+                    ui.slider(104, "to", range, &mut bifurcation.to);
+                    if ui.button(None, "compute bifurcation") {
+                        bifurcation.compute(
+                            state.system,
+                            &state.params,
+                            state.integrator,
+                            state.dt,
+                        );
+                    }
+                }
+                ui.checkbox(25, "volume monitor", &mut state.volume_monitor.visible);
+                if state.volume_monitor.visible {
+                    let monitor = &state.volume_monitor;
+                    ui.label(None, &format!("div f = {:.3}", monitor.divergence()));
+                    ui.label(None, &format!("<div f> = {:.3}", monitor.mean_divergence()));
+                    ui.label(
+                        None,
+                        &format!("d ln V / dt = {:.3}", monitor.observed_rate()),
+                    );
+                }
+                ui.checkbox(26, "integrator diagnostics", &mut state.diagnostics.visible);
+                ui.checkbox(34, "profiler", &mut state.profiler.visible);
+            });
+            ui.tree_node(47, "trail alpha", |ui| {
+                ui.checkbox(48, "edit curve", &mut state.curve_editor.visible);
+                for (preset, name) in curve::Preset::ALL.iter().zip(curve::Preset::NAMES) {
+                    if ui.button(None, name) {
+                        state.alpha_curve = curve::Curve::new(*preset);
+                    }
+                }
+            });
+            ui.tree_node(40, "lighting", |ui| {
+                let lighting = &mut state.lighting;
+                ui.checkbox(41, "lit tubes", &mut lighting.enabled);
+                let mut shape = lighting::Shape::ALL
+                    .iter()
+                    .position(|s| *s == lighting.shape)
+                    .unwrap();
+                ui.combo_box(117, "shape", &lighting::Shape::NAMES, &mut shape);
+                lighting.shape = lighting::Shape::ALL[shape];
+                if lighting.shape == lighting::Shape::Tube {
+                    ui.slider(118, "radius", 0.02..2.0, &mut lighting.radius);
+                }
+                ui.slider(
+                    42,
+                    "azimuth",
+                    -std::f32::consts::PI..std::f32::consts::PI,
+                    &mut lighting.azimuth,
+                );
+                ui.slider(43, "elevation", -1.5..1.5, &mut lighting.elevation);
+                ui.slider(44, "ambient", 0.0..1.0, &mut lighting.ambient);
+                ui.slider(45, "specular", 0.0..1.0, &mut lighting.specular);
+                ui.slider(46, "shininess", 1.0..128.0, &mut lighting.shininess);
+                ui.separator();
+                let bloom = &mut state.bloom;
+                ui.checkbox(122, "bloom", &mut bloom.enabled);
+                if bloom.enabled {
+                    ui.slider(123, "bloom threshold", 0.0..1.0, &mut bloom.threshold);
+                    ui.slider(124, "bloom intensity", 0.0..5.0, &mut bloom.intensity);
+                    ui.slider(125, "bloom radius", 1.0..10.0, &mut bloom.radius);
+                }
+                let exposure = &mut state.exposure;
+                ui.checkbox(126, "long exposure", &mut exposure.enabled);
+                if exposure.enabled {
+                    ui.slider(127, "exposure", 0.005..0.5, &mut exposure.exposure);
+                    if ui.button(None, "clear exposure (C)") {
+                        exposure.clear();
+                    }
+                }
+                ui.checkbox(133, "FXAA", &mut state.fxaa.enabled);
+                ui.checkbox(131, "red/cyan anaglyph", &mut state.anaglyph.enabled);
+                if state.anaglyph.enabled {
+                    ui.slider(
+                        132,
+                        "eye separation",
+                        0.0..0.1,
+                        &mut state.anaglyph.separation,
+                    );
+                }
+            });
+            ui.tree_node(53, "assimilation", |ui| {
+                let enabled = state.assimilation.enabled;
+                ui.checkbox(54, "nudged forecast", &mut state.assimilation.enabled);
+                let restart = ui.button(None, "restart forecast");
+                if (state.assimilation.enabled && !enabled || restart)
+                    && let Some(truth) = state.trajectories.first()
+                {
+                    state.assimilation.restart(truth.head(), &state.rng);
+                    state.assimilation.enabled = true;
+                }
+                let assimilation = &mut state.assimilation;
+                ui.slider(55, "observation noise", 0.0..10.0, &mut assimilation.noise);
+                ui.slider(56, "nudging strength", 0.0..1.0, &mut assimilation.strength);
+                ui.slider(57, "interval", 0.01..2.0, &mut assimilation.interval);
+                ui.label(None, &format!("forecast error {:.2}", assimilation.error));
+            });
+            ui.tree_node(64, "tangent ellipsoid", |ui| {
+                let ellipsoid = &mut state.ellipsoid;
+                let enabled = ellipsoid.enabled;
+                ui.checkbox(65, "show ellipsoid", &mut ellipsoid.enabled);
+                if ellipsoid.enabled && !enabled {
+                    ellipsoid.reset();
+                }
+                ui.checkbox(66, "normalize size", &mut ellipsoid.normalize);
+                ui.slider(67, "radius", 0.5..10.0, &mut ellipsoid.radius);
+                ui.slider(68, "window", 0.1..5.0, &mut ellipsoid.window);
+                let axes = ellipsoid.semi_axes();
+                ui.label(
+                    None,
+                    &format!("axes {:.2e} {:.2e} {:.2e}", axes[0], axes[1], axes[2]),
+                );
+                let age = ellipsoid.age.max(1e-6);
+                ui.label(
+                    None,
+                    &format!(
+                        "rates {:.2} {:.2} {:.2}",
+                        axes[0].ln() / age,
+                        axes[1].ln() / age,
+                        axes[2].ln() / age
+                    ),
+                );
+            });
+            ui.tree_node(72, "ftle field", |ui| {
+                let field = &mut state.ftle_field;
+                ui.checkbox(73, "show field", &mut field.visible);
+                ui.combo_box(74, "slice normal", &poincare::AXES, &mut field.axis);
+                ui.slider(75, "slice offset", -50.0..50.0, &mut field.offset);
+                ui.slider(76, "slice extent", 1.0..60.0, &mut field.extent);
+                ui.slider(77, "horizon", -3.0..3.0, &mut field.time);
+                if ui.button(None, "compute field")
+                    && field.time != 0.0
+                    && let Some(trajectory) = state.trajectories.first()
+                {
+                    let center = (trajectory.points.iter().sum::<macroquad::math::DVec3>()
+                        / trajectory.points.len().max(1) as f64)
+                        .as_vec3();
+                    field.compute(
+                        state.system,
+                        &state.params,
+                        state.integrator,
+                        state.dt,
+                        center,
+                    );
+                    field.visible = true;
+                }
+                ui.label(None, &format!("progress {:.0}%", field.progress() * 100.0));
+            });
+            ui.tree_node(95, "butterfly effect", |ui| {
+                ui.checkbox(96, "show separation", &mut state.butterfly.enabled);
+                ui.slider(
+                    97,
+                    "log10 epsilon",
+                    -6.0..-1.0,
+                    &mut state.butterfly.log_epsilon,
+                );
+                if ui.button(None, "butterfly demo") {
+                    state.start_butterfly_demo();
+                }
+            });
+            ui.tree_node(87, "coupled systems", |ui| {
+                ui.checkbox(
+                    88,
+                    "couple trajectories 1 and 2",
+                    &mut state.coupling.enabled,
+                );
+                ui.slider(
+                    89,
+                    "coupling strength",
+                    0.0..5.0,
+                    &mut state.coupling.strength,
+                );
+                ui.checkbox(
+                    90,
+                    "one-way (drive and response)",
+                    &mut state.coupling.one_way,
+                );
+                if ui.button(None, "synchronization demo") {
+                    state.start_synchronization_demo();
+                }
+                if state.coupling.enabled {
+                    ui.label(None, &format!("distance {:.3e}", state.coupling.distance()));
+                }
+            });
+            ui.tree_node(59, "shadowing", |ui| {
+                ui.checkbox(60, "show orbits", &mut state.shadowing.visible);
+                ui.slider(
+                    61,
+                    "log10 noise",
+                    -4.0..-1.0,
+                    &mut state.shadowing.log_noise,
+                );
+                ui.slider(62, "samples", 20.0..500.0, &mut state.shadowing.samples);
+                if ui.button(None, "compute shadow")
+                    && let Some(trajectory) = state.trajectories.first()
+                {
+                    let start = trajectory.head();
+                    state.shadowing.compute(
+                        state.system,
+                        &state.params,
+                        state.integrator,
+                        state.dt,
+                        start,
+                        &state.rng,
+                    );
+                    state.shadowing.visible = true;
+                }
+                ui.label(None, &state.shadowing.status);
+            });
+            ui.tree_node(35, "ensemble", |ui| {
+                ui.checkbox(36, "show ensemble", &mut state.ensemble.visible);
+                ui.checkbox(135, "integrate on GPU", &mut state.ensemble.gpu);
+                let most = if state.ensemble.gpu {
+                    1_000_000.0
+                } else {
+                    20_000.0
+                };
+                state.ensemble.count = state.ensemble.count.min(most);
+                ui.slider(37, "particles", 100.0..most, &mut state.ensemble.count);
+                ui.slider(38, "spread", 0.1..10.0, &mut state.ensemble.spread);
+                let mut seeding = ensemble::Seeding::ALL
+                    .iter()
+                    .position(|s| *s == state.ensemble.seeding)
+                    .unwrap();
+                ui.combo_box(94, "shape", &ensemble::Seeding::NAMES, &mut seeding);
+                state.ensemble.seeding = ensemble::Seeding::ALL[seeding];
+                ui.slider(39, "radius", 0.05..1.0, &mut state.ensemble.radius);
+                ui.slider(93, "tail frames", 0.0..60.0, &mut state.ensemble.tail);
+                if ui.button(None, "seed ensemble") {
+                    state.ensemble.seed(state.start, &state.rng);
+                    state.ensemble.visible = true;
+                }
+                if ui.button(None, "forecast from trail head")
+                    && let Some(analysis) = state.trajectories.first()
+                {
+                    state.ensemble.seed(analysis.head(), &state.rng);
+                    state.ensemble.visible = true;
+                    state.ensemble.show_spread = true;
+                }
+                ui.checkbox(58, "spread plot", &mut state.ensemble.show_spread);
+                ui.checkbox(
+                    98,
+                    "centroid and spread",
+                    &mut state.ensemble.show_statistics,
+                );
+            });
+            ui.tree_node(137, "camera", |ui| {
+                let mut controller = camera::Controller::ALL
+                    .iter()
+                    .position(|c| *c == camera.controller)
+                    .unwrap();
+                ui.combo_box(
+                    138,
+                    "controls (F)",
+                    &camera::Controller::NAMES,
+                    &mut controller,
+                );
+                camera.controller = camera::Controller::ALL[controller];
+                if camera.controller == camera::Controller::Fly {
+                    ui.slider(139, "fly speed", 0.5..500.0, &mut camera.fly_speed);
+                }
+                ui.checkbox(140, "turntable", &mut camera.turntable);
+                if camera.turntable {
+                    ui.slider(
+                        141,
+                        "degrees per second",
+                        -90.0..90.0,
+                        &mut camera.turntable_speed,
+                    );
+                }
+                ui.checkbox(142, "follow the head", &mut camera.follow);
+                if camera.follow {
+                    ui.slider(143, "lag (s)", 0.05..5.0, &mut camera.follow_lag);
+                }
+                ui.checkbox(144, "inertia", &mut camera.inertia);
+            });
+            ui.tree_node(27, "camera path", |ui| {
+                ui.label(
+                    None,
+                    &format!(
+                        "{} keyframes, {:.1} s",
+                        state.camera_path.keyframes.len(),
+                        state.camera_path.duration()
+                    ),
+                );
+                ui.slider(28, "spacing", 0.5..10.0, &mut state.key_spacing);
+                if ui.button(None, "add keyframe") {
+                    state.camera_path.add(camera, state.key_spacing);
+                }
+                if ui.button(None, "clear keyframes") {
+                    state.camera_path = camera_path::CameraPath::new();
+                }
+                if ui.button(None, "save path")
+                    && let Err(err) = state.camera_path.save(CAMERA_PATH)
+                {
+                    eprintln!("camera path: {err}");
+                }
+                if ui.button(None, "load path") {
+                    match camera_path::CameraPath::load(CAMERA_PATH) {
+                        Ok(path) => state.camera_path = path,
+                        Err(err) => eprintln!("camera path: {err}"),
+                    }
+                }
+                let settings = &mut state.render_settings;
+                ui.slider(29, "width", 320.0..3840.0, &mut settings.width);
+                ui.slider(30, "height", 240.0..2160.0, &mut settings.height);
+                ui.slider(31, "fps", 10.0..120.0, &mut settings.fps);
+                (settings.width, settings.height, settings.fps) = (
+                    settings.width.round(),
+                    settings.height.round(),
+                    settings.fps.round(),
+                );
+                match &state.render {
+                    Some(render) => {
+                        ui.label(None, &format!("frame {} / {}", render.frame, render.frames));
+                        if ui.button(None, "cancel render") {
+                            state.render = None;
+                        }
+                    }
+                    None => {
+                        if ui.button(None, "render video") {
+                            state.start_render();
+                        }
+                    }
+                }
+            });
+            ui.tree_node(32, "sync", |ui| match &state.sync {
+                Some(session) => {
+                    ui.label(None, &session.status());
+                    if ui.button(None, "disconnect") {
+                        state.sync = None;
+                    }
+                }
+                None => {
+                    text_field(
+                        ui,
+                        33,
+                        "address",
+                        &mut state.sync_address,
+                        &mut state.typing,
+                    );
+                    let session = if ui.button(None, "host") {
+                        Some(sync::Session::host(&state.sync_address))
+                    } else if ui.button(None, "connect") {
+                        Some(sync::Session::connect(&state.sync_address))
+                    } else {
+                        None
+                    };
+                    match session {
+                        Some(Ok(session)) => state.sync = Some(session),
+                        Some(Err(err)) => eprintln!("sync: {err}"),
+                        None => {}
+                    }
+                }
+            });
+        },
+    );
+    if state.theme != previous_theme {
+        theme::apply_skin(state.theme());
+        config::store("theme", &state.theme().name);
     }
+    store_follow(state, camera);
 }
 
-struct OrbitCamera {
-    distance: f32,
-    yaw: f32,
-    pitch: f32,
-    sensitivity: f32,
-    pan_sensitivity: f32,
-    target: macroquad::math::Vec3,
-    last_left_mouse: Option<macroquad::math::Vec2>,
-    last_right_mouse: Option<macroquad::math::Vec2>,
+/// Writes the camera follow settings once they have settled: the mouse is up or they have not
+/// changed for half a second, so dragging the lag slider does not rewrite the file every frame.
+fn store_follow(state: &mut State, camera: &camera::OrbitCamera) {
+    let follow = (camera.follow, camera.follow_lag);
+    if follow == state.stored_follow {
+        state.pending_follow = None;
+        return;
+    }
+    let now = macroquad::time::get_time();
+    let changed = match state.pending_follow {
+        Some((pending, changed)) if pending == follow => changed,
+        _ => now,
+    };
+    state.pending_follow = Some((follow, changed));
+    let dragging = macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Left);
+    if dragging && now - changed < 0.5 {
+        return;
+    }
+    config::store_all(&[
+        ("follow", &camera.follow.to_string()),
+        ("follow-lag", &camera.follow_lag.to_string()),
+    ]);
+    state.stored_follow = follow;
+    state.pending_follow = None;
 }
 
-impl OrbitCamera {
-    fn new() -> Self {
-        Self {
-            distance: 100.0,
-            yaw: 0.0,
-            pitch: 0.0,
-            sensitivity: 0.005,
-            pan_sensitivity: 0.001,
-            target: macroquad::math::vec3(0.0, 0.0, 0.0),
-            last_left_mouse: None,
-            last_right_mouse: None,
-        }
+fn draw_blowup_ui(state: &mut State) {
+    let Some(blowup) = &state.blowup else {
+        return;
+    };
+    let size = macroquad::math::vec2(360.0, 150.0);
+    let position = macroquad::math::vec2(
+        (macroquad::window::screen_width() - size.x) / 2.0,
+        (macroquad::window::screen_height() - size.y) / 2.0,
+    );
+    let settings = state
+        .system
+        .params()
+        .iter()
+        .zip(blowup.params.iter())
+        .map(|(param, value)| format!("{} = {value:.3}", param.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let integrator = integrator::Integrator::NAMES[blowup.integrator as usize];
+    let mut recover = false;
+    let mut restart = false;
+    macroquad::ui::root_ui().window(400, position, size, |ui| {
+        ui.label(None, "TRAJECTORY DIVERGED");
+        ui.label(
+            None,
+            &format!("'{}' blew up at t = {:.3}", blowup.trajectory, blowup.time),
+        );
+        ui.label(None, &settings);
+        ui.label(
+            None,
+            &format!("dt = {}, integrator = {integrator}", blowup.dt),
+        );
+        recover = ui.button(None, "recover (default params + reset position)");
+        restart = ui.button(None, "reset position only");
+    });
+    if recover {
+        state.params = state.system.defaults();
     }
-
-    fn update(&mut self) {
-        if macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Left) {
-            let mouse = macroquad::input::mouse_position().into();
-            if let Some(last) = self.last_left_mouse {
-                let delta: macroquad::math::Vec2 = mouse - last;
-                self.yaw -= delta.x * self.sensitivity;
-                self.pitch += delta.y * self.sensitivity;
-                self.pitch = self.pitch.clamp(
-                    -std::f32::consts::FRAC_PI_2 + 0.1,
-                    std::f32::consts::FRAC_PI_2 - 0.1,
-                );
-            }
-            self.last_left_mouse = Some(mouse);
-        } else {
-            self.last_left_mouse = None;
-        }
-
-        if macroquad::input::is_mouse_button_down(macroquad::input::MouseButton::Right) {
-            let mouse = macroquad::input::mouse_position().into();
-            if let Some(last) = self.last_right_mouse {
-                let forward = (self.target - self.get_position()).normalize();
-                let right = forward.cross(vec3(0.0, 1.0, 0.0)).normalize();
-                let up = right.cross(forward).normalize();
-                let delta: macroquad::math::Vec2 = mouse - last;
-                self.target -= right * delta.x * self.pan_sensitivity * self.distance;
-                self.target += up * delta.y * self.pan_sensitivity * self.distance;
-            }
-            self.last_right_mouse = Some(mouse);
-        } else {
-            self.last_right_mouse = None;
-        }
-
-        self.distance -= macroquad::input::mouse_wheel().1 * 5.0;
-        self.distance = self.distance.clamp(1.0, 200.0);
+    if recover || restart {
+        state.reset_position();
+        state.paused = false;
     }
+}
 
-    fn get_position(&self) -> Vec3 {
-        let x = self.distance * self.pitch.cos() * self.yaw.sin();
-        let y = self.distance * self.pitch.sin();
-        let z = self.distance * self.pitch.cos() * self.yaw.cos();
-        self.target + vec3(x, y, z)
-    }
+fn draw_clock_ui(state: &mut State) {
+    macroquad::ui::root_ui().window(
+        200,
+        macroquad::math::vec2(10.0, macroquad::window::screen_height() - 200.0),
+        macroquad::math::vec2(250.0, 190.0),
+        |ui| {
+            ui.label(None, &format!("t = {:.3}", state.time));
+            ui.label(
+                None,
+                &format!("{:.3} sim s / wall s", state.time_compression),
+            );
+            if ui.button(None, if state.paused { "resume" } else { "pause" }) {
+                state.paused = !state.paused;
+                state.run_remaining = None;
+            }
+            ui.same_line(80.0);
+            if ui.button(None, "step") {
+                state.run_remaining = Some(state.dt);
+                state.paused = false;
+            }
+            ui.same_line(130.0);
+            ui.checkbox(206, "explain", &mut state.explain.enabled);
+            let reversed = state.reversed;
+            ui.checkbox(202, "reverse time", &mut state.reversed);
+            if state.reversed != reversed {
+                state.reverse_changed();
+            }
+            let mut integrator = integrator::Integrator::ALL
+                .iter()
+                .position(|i| *i == state.integrator)
+                .unwrap();
+            ui.combo_box(
+                203,
+                "integrator",
+                &integrator::Integrator::NAMES,
+                &mut integrator,
+            );
+            state.integrator = integrator::Integrator::ALL[integrator];
+            let enabled = state.comparison.enabled;
+            ui.checkbox(207, "compare integrators", &mut state.comparison.enabled);
+            if state.comparison.enabled {
+                let previous = state.comparison.integrator;
+                let mut other = integrator::Integrator::ALL
+                    .iter()
+                    .position(|i| *i == previous)
+                    .unwrap();
+                ui.combo_box(208, "against", &integrator::Integrator::NAMES, &mut other);
+                state.comparison.integrator = integrator::Integrator::ALL[other];
+                if (!enabled || state.comparison.integrator != previous)
+                    && let Some(trajectory) = state.trajectories.first()
+                {
+                    state.comparison.restart(trajectory.head());
+                }
+                ui.label(None, &format!("gap {:.3e}", state.comparison.error));
+            }
+            if state.integrator.is_adaptive()
+                && state.system.fractional_order(&state.params).is_none()
+            {
+                ui.slider(205, "log10 tol", -7.0..-2.0, &mut state.log_tolerance);
+                let h = state.trajectories.first().map_or(0.0, |t| t.step_size);
+                ui.label(None, &format!("h = {h:.5}"));
+            } else {
+                ui.slider(204, "dt", 0.0005..0.02, &mut state.dt);
+            }
+            ui.slider(201, "T", 0.1..100.0, &mut state.run_for);
+            if ui.button(None, "run for T") {
+                state.run_remaining = Some(state.run_for);
+                state.paused = false;
+            }
+        },
+    );
+}
 
-    fn get_camera(&self) -> macroquad::camera::Camera3D {
-        macroquad::camera::Camera3D {
-            position: self.get_position(),
-            up: macroquad::math::vec3(0.0, 1.0, 0.0),
-            target: self.target,
-            ..Default::default()
-        }
+fn draw_periodic_ui(state: &mut State) {
+    if !state.periodic.visible {
+        return;
     }
+    macroquad::ui::root_ui().window(
+        400,
+        macroquad::math::vec2(macroquad::window::screen_width() - 520.0, 10.0),
+        macroquad::math::vec2(250.0, 300.0),
+        |ui| {
+            let library = &mut state.periodic;
+            ui.checkbox(401, "detect close returns", &mut library.searching);
+            ui.slider(402, "tolerance", 0.1..5.0, &mut library.tolerance);
+            ui.label(None, &format!("{} orbits", library.orbits.len()));
+            if ui.button(None, "clear") {
+                library.orbits.clear();
+            }
+            ui.same_line(60.0);
+            if ui.button(None, "scan trail") {
+                state.scan_for_orbits();
+            }
+            let library = &mut state.periodic;
+            let mut start = None;
+            for (i, orbit) in library.orbits.iter_mut().enumerate() {
+                ui.checkbox(
+                    macroquad::hash!("upo", i),
+                    &format!(
+                        "{} T={:.3} |mu|={:.1}",
+                        orbit.name,
+                        orbit.period,
+                        orbit.instability()
+                    ),
+                    &mut orbit.visible,
+                );
+                ui.same_line(200.0);
+                if ui.button(None, "start") {
+                    start = Some(i);
+                }
+            }
+            if let Some(i) = start {
+                state.start_on_orbit(i);
+            }
+        },
+    );
 }
 
-fn draw_ui(state: &mut State) {
+fn draw_trajectories_ui(state: &mut State) {
     macroquad::ui::root_ui().window(
-        1,
-        macroquad::math::vec2(10.0, 10.0),
-        macroquad::math::vec2(250.0, 155.0),
+        100,
+        macroquad::math::vec2(macroquad::window::screen_width() - 260.0, 10.0),
+        macroquad::math::vec2(250.0, 300.0),
         |ui| {
-            ui.slider(2, "sigma", -20.0..20.0, &mut state.sigma);
-            ui.slider(3, "beta", -20.0..20.0, &mut state.beta);
-            ui.slider(4, "rho", -20.0..40.0, &mut state.rho);
-            ui.slider(5, "tail", 10.0..10_000.0, &mut state.tail);
-            ui.slider(6, "speed", 1.0..20.0, &mut state.speed);
-            if ui.button(None, "reset params") {
-                state.sigma = 10.0;
-                state.beta = 8.0 / 3.0;
-                state.rho = 28.0;
+            let mut remove = None;
+            for (i, trajectory) in state.trajectories.iter_mut().enumerate() {
+                let label = trajectory.name.clone();
+                ui.tree_node(macroquad::hash!("trajectory", i), &label, |ui| {
+                    text_field(
+                        ui,
+                        macroquad::hash!("name", i),
+                        "name",
+                        &mut trajectory.name,
+                        &mut state.typing,
+                    );
+                    ui.checkbox(
+                        macroquad::hash!("visible", i),
+                        "visible",
+                        &mut trajectory.visible,
+                    );
+                    ui.checkbox(
+                        macroquad::hash!("solid", i),
+                        "solid color",
+                        &mut trajectory.solid_color,
+                    );
+                    if trajectory.solid_color {
+                        ui.slider(
+                            macroquad::hash!("r", i),
+                            "red",
+                            0.0..1.0,
+                            &mut trajectory.color.r,
+                        );
+                        ui.slider(
+                            macroquad::hash!("g", i),
+                            "green",
+                            0.0..1.0,
+                            &mut trajectory.color.g,
+                        );
+                        ui.slider(
+                            macroquad::hash!("b", i),
+                            "blue",
+                            0.0..1.0,
+                            &mut trajectory.color.b,
+                        );
+                    }
+                    ui.slider(
+                        macroquad::hash!("thickness", i),
+                        "thickness",
+                        1.0..10.0,
+                        &mut trajectory.thickness,
+                    );
+                    let mut taper = trajectory::Taper::ALL
+                        .iter()
+                        .position(|t| *t == trajectory.taper)
+                        .unwrap();
+                    ui.combo_box(
+                        macroquad::hash!("taper", i),
+                        "taper",
+                        &trajectory::Taper::NAMES,
+                        &mut taper,
+                    );
+                    trajectory.taper = trajectory::Taper::ALL[taper];
+                    ui.slider(
+                        macroquad::hash!("tail", i),
+                        "tail length",
+                        0.1..100.0,
+                        &mut trajectory.tail,
+                    );
+                    if i > 0 && ui.button(None, "remove") {
+                        remove = Some(i);
+                    }
+                });
             }
-            if ui.button(None, "reset position") {
-                state.points.clear();
-                state.points.push_back(state.start);
+            if let Some(i) = remove {
+                state.trajectories.remove(i);
+            }
+            if ui.button(None, "add trajectory") {
+                state.add_trajectory();
             }
+            if ui.button(None, "distinct colors") {
+                for (i, trajectory) in state.trajectories.iter_mut().enumerate() {
+                    trajectory.color = palette::categorical(i);
+                    trajectory.solid_color = true;
+                }
+            }
+            ui.checkbox(macroquad::hash!("legend"), "legend", &mut state.show_legend);
+            let mut target = SpawnTarget::ALL
+                .iter()
+                .position(|t| *t == state.spawn_target)
+                .unwrap();
+            ui.combo_box(
+                macroquad::hash!("spawn"),
+                "ctrl-click",
+                &SpawnTarget::NAMES,
+                &mut target,
+            );
+            state.spawn_target = SpawnTarget::ALL[target];
         },
     );
 }
 
-#[macroquad::main("Lorenz attractor")]
-async fn main() {
-    let mut state = State::new();
-    let mut camera = OrbitCamera::new();
+fn main() {
+    let config = config::Config::load();
+    if let Some(output) = config.get::<String>("batch") {
+        if let Err(err) = batch::Settings::new(&config, output).and_then(|s| batch::run(&s)) {
+            eprintln!("batch: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    let conf = macroquad::window::Conf {
+        window_title: "Lorenz attractor".to_owned(),
+        sample_count: config.get("msaa").unwrap_or(4).max(1),
+        ..Default::default()
+    };
+    macroquad::Window::from_config(conf, run(config));
+}
+
+async fn run(config: config::Config) {
+    let mut state = State::new(&config);
+    theme::apply_skin(state.theme());
+    let mut camera = camera::OrbitCamera::new(state.system.camera_distance());
+    camera.follow = config.get("follow").unwrap_or(false);
+    camera.follow_lag = config.get("follow-lag").unwrap_or(0.5);
+    state.stored_follow = (camera.follow, camera.follow_lag);
+    let session = if let Some(address) = config.get::<String>("sync-host") {
+        Some(sync::Session::host(&address))
+    } else {
+        config
+            .get::<String>("sync-connect")
+            .map(|address| sync::Session::connect(&address))
+    };
+    match session {
+        Some(Ok(session)) => state.sync = Some(session),
+        Some(Err(err)) => eprintln!("sync: {err}"),
+        None => {}
+    }
+    if let Some(path) = config.get::<String>("script")
+        && let Err(err) = state.execute(console::Command::Run(path), &mut camera, 0)
+    {
+        eprintln!("script: {err}");
+    }
+    if let Some(path) = config.get::<String>("render-path") {
+        match camera_path::CameraPath::load(&path) {
+            Ok(path) => {
+                state.camera_path = path;
+                state.exit_after_render = true;
+                state.start_render();
+            }
+            Err(err) => eprintln!("camera path: {err}"),
+        }
+    }
 
     loop {
-        macroquad::window::clear_background(macroquad::color::BLACK);
+        macroquad::window::clear_background(state.theme().background);
 
-        draw_ui(&mut state);
-        if !macroquad::ui::root_ui().is_mouse_over(macroquad::input::mouse_position().into()) {
-            camera.update();
+        // Clicking moves the input focus, possibly out of the text field.
+        if [
+            macroquad::input::MouseButton::Left,
+            macroquad::input::MouseButton::Right,
+            macroquad::input::MouseButton::Middle,
+        ]
+        .into_iter()
+        .any(macroquad::input::is_mouse_button_pressed)
+        {
+            state.typing = false;
+        }
+        let ui = profiler::scope("ui");
+        draw_ui(&mut state, &mut camera);
+        draw_clock_ui(&mut state);
+        draw_blowup_ui(&mut state);
+        draw_trajectories_ui(&mut state);
+        draw_periodic_ui(&mut state);
+        if let Some(line) = state.console.draw() {
+            state.console.print(format!("> {line}"));
+            if let Err(err) = state.run_script(&line, &mut camera, 0) {
+                state.console.print(err);
+            }
+        }
+        drop(ui);
+        state.reload_scripts();
+        state.synchronize(&mut camera);
+        if let Some(mut render) = state.render.take() {
+            if !state.render_frame(&mut render, &mut camera) {
+                state.render = Some(render);
+            } else if state.exit_after_render {
+                break;
+            }
+            macroquad::window::next_frame().await;
+            continue;
+        }
+        if !state.console.open && !state.typing {
+            let ctrl = macroquad::input::is_key_down(macroquad::input::KeyCode::LeftControl)
+                || macroquad::input::is_key_down(macroquad::input::KeyCode::RightControl);
+            for (slot, key) in bookmarks::KEYS.into_iter().enumerate() {
+                if !macroquad::input::is_key_pressed(key) {
+                    continue;
+                }
+                if ctrl {
+                    state.bookmarks.save(slot, &camera);
+                } else {
+                    state.bookmarks.recall(slot, &camera);
+                }
+            }
+        }
+        state
+            .bookmarks
+            .update(&mut camera, macroquad::time::get_frame_time());
+        camera.advance(macroquad::time::get_frame_time());
+        if let Some(head) = state.followed_head() {
+            camera.track(head, macroquad::time::get_frame_time());
+        }
+        let mouse = macroquad::input::mouse_position().into();
+        let camera_3d = camera.get_camera();
+        if !macroquad::ui::root_ui().is_mouse_over(mouse) && !state.mouse_over_panels(mouse) {
+            let ctrl = macroquad::input::is_key_down(macroquad::input::KeyCode::LeftControl)
+                || macroquad::input::is_key_down(macroquad::input::KeyCode::RightControl);
+            if !ctrl {
+                camera.update(!state.console.open && !state.typing);
+            } else if macroquad::input::is_mouse_button_pressed(macroquad::input::MouseButton::Left)
+            {
+                let (origin, direction) = camera::mouse_ray(&camera_3d, mouse);
+                state.spawn_at_ray(origin, direction, camera.target);
+            }
+        }
+        let shortcuts = !state.console.open && !state.typing;
+        if shortcuts && macroquad::input::is_key_pressed(macroquad::input::KeyCode::C) {
+            state.exposure.clear();
+        }
+        if shortcuts && macroquad::input::is_key_pressed(macroquad::input::KeyCode::F) {
+            camera.controller = match camera.controller {
+                camera::Controller::Orbit => camera::Controller::Fly,
+                camera::Controller::Fly => camera::Controller::Orbit,
+            };
+        }
+        if state.anaglyph.enabled {
+            state.step(macroquad::time::get_frame_time());
+            for eye in state.anaglyph.eye_cameras(&camera) {
+                macroquad::camera::set_camera(&eye);
+                macroquad::window::clear_background(state.theme().background);
+                state.draw(&eye);
+            }
+            macroquad::camera::set_default_camera();
+            state.anaglyph.composite();
+            state.draw_panels();
+            state.serve_requests();
+            profiler::end_frame();
+            let _render = profiler::scope("render");
+            macroquad::window::next_frame().await;
+            continue;
+        }
+        let mut camera_3d = camera.get_camera();
+        if state.exposure.enabled {
+            camera_3d = state.exposure.scene_camera(camera_3d);
+        } else if state.bloom.enabled {
+            camera_3d = state.bloom.scene_camera(camera_3d);
+        } else if state.fxaa.enabled {
+            camera_3d = state.fxaa.scene_camera(camera_3d);
+        }
+        macroquad::camera::set_camera(&camera_3d);
+        if state.exposure.enabled {
+            macroquad::window::clear_background(macroquad::color::BLACK);
+        } else if state.bloom.enabled || state.fxaa.enabled {
+            macroquad::window::clear_background(state.theme().background);
+        }
+        state.step(macroquad::time::get_frame_time());
+        state.draw(&camera_3d);
+        macroquad::camera::set_default_camera();
+        if state.exposure.enabled {
+            state.exposure.composite();
+        } else if state.bloom.enabled {
+            state.bloom.composite();
+        } else if state.fxaa.enabled {
+            state.fxaa.composite();
         }
-        macroquad::camera::set_camera(&camera.get_camera());
-        state.step();
-        state.draw();
+        state.draw_panels();
+        state.serve_requests();
 
+        profiler::end_frame();
+        let _render = profiler::scope("render");
         macroquad::window::next_frame().await
     }
 }
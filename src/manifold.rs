@@ -0,0 +1,182 @@
+const RAYS: usize = 16;
+const RING_EVERY: usize = 20;
+
+/// One invariant manifold of an equilibrium, traced by integrating seeds placed just off the
+/// equilibrium along its eigenspace: forward for the unstable manifold, backward for the stable
+/// one. Two dimensional manifolds are a fan of rays seeded on a small circle.
+pub struct Manifold {
+    pub unstable: bool,
+    pub rays: Vec<Vec<macroquad::math::Vec3>>,
+    pub surface: bool,
+}
+
+/// Stable and unstable manifolds of the equilibria, recomputed when the system, its
+/// parameters or the traced `length` change.
+pub struct Manifolds {
+    pub visible: bool,
+    pub length: f32,
+    pub manifolds: Vec<Manifold>,
+    computed_for: Option<(crate::system::System, Vec<f32>, f32)>,
+}
+
+impl Manifolds {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            length: 10.0,
+            manifolds: Vec::new(),
+            computed_for: None,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        equilibria: &[crate::analysis::FixedPoint],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) {
+        let key = (system, params.to_vec(), self.length);
+        if self.computed_for.as_ref() == Some(&key) {
+            return;
+        }
+        self.computed_for = Some(key);
+        self.manifolds.clear();
+        let scale = system.camera_distance();
+        for point in equilibria {
+            let jacobian = system.jacobian(point.position, params);
+            for unstable in [false, true] {
+                let eigenvalues = point
+                    .eigenvalues
+                    .iter()
+                    .copied()
+                    .filter(|(re, _)| (*re > 0.0) == unstable)
+                    .collect::<Vec<_>>();
+                let Some(basis) = eigenspace(jacobian, &eigenvalues) else {
+                    continue;
+                };
+                let seeds = match basis {
+                    (u, None) => vec![u, -u],
+                    (u, Some(w)) => (0..RAYS)
+                        .map(|k| {
+                            let angle = std::f32::consts::TAU * k as f32 / RAYS as f32;
+                            u * angle.cos() + w * angle.sin()
+                        })
+                        .collect(),
+                };
+                let h = if unstable { dt } else { -dt };
+                let rays = seeds
+                    .iter()
+                    .map(|direction| {
+                        let start = point.position + *direction * (scale * 1e-4);
+                        trace(system, params, integrator, start, h, self.length, scale)
+                    })
+                    .collect();
+                self.manifolds.push(Manifold {
+                    unstable,
+                    rays,
+                    surface: basis.1.is_some(),
+                });
+            }
+        }
+    }
+
+    pub fn draw(&self, transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        for manifold in &self.manifolds {
+            let color = if manifold.unstable {
+                macroquad::color::Color::new(1.0, 0.35, 0.2, 0.45)
+            } else {
+                macroquad::color::Color::new(0.3, 0.6, 1.0, 0.45)
+            };
+            for ray in &manifold.rays {
+                for (a, b) in ray.iter().zip(ray.iter().skip(1)) {
+                    macroquad::models::draw_line_3d(transform(*a), transform(*b), color);
+                }
+            }
+            if !manifold.surface {
+                continue;
+            }
+            let next = manifold.rays.iter().cycle().skip(1);
+            for (a, b) in manifold.rays.iter().zip(next) {
+                for (p, q) in a.iter().zip(b).step_by(RING_EVERY) {
+                    macroquad::models::draw_line_3d(transform(*p), transform(*q), color);
+                }
+            }
+        }
+    }
+}
+
+/// Orthonormal basis of the real invariant subspace belonging to one or two `eigenvalues`;
+/// none when there are no eigenvalues or the whole space belongs to them.
+fn eigenspace(
+    jacobian: macroquad::math::Mat3,
+    eigenvalues: &[(f32, f32)],
+) -> Option<(macroquad::math::Vec3, Option<macroquad::math::Vec3>)> {
+    let identity = macroquad::math::Mat3::IDENTITY;
+    match eigenvalues {
+        [(re, _)] => Some((null_vector(jacobian - identity * *re)?, None)),
+        [(re, im), _] if im.abs() > 1e-6 => {
+            let shifted = jacobian - identity * *re;
+            let normal = largest_row(shifted * shifted + identity * (im * im))?;
+            Some(plane(normal))
+        }
+        [(a, _), (b, _)] => {
+            let u = null_vector(jacobian - identity * *a)?;
+            let v = null_vector(jacobian - identity * *b)?;
+            let normal = u.cross(v);
+            if normal.length() < 1e-4 {
+                return None;
+            }
+            Some(plane(normal.normalize()))
+        }
+        _ => None,
+    }
+}
+
+fn plane(normal: macroquad::math::Vec3) -> (macroquad::math::Vec3, Option<macroquad::math::Vec3>) {
+    let u = normal.any_orthonormal_vector();
+    (u, Some(normal.cross(u)))
+}
+
+/// Unit vector spanning the kernel of a rank two matrix.
+fn null_vector(m: macroquad::math::Mat3) -> Option<macroquad::math::Vec3> {
+    let rows = m.transpose();
+    let (a, b, c) = (rows.x_axis, rows.y_axis, rows.z_axis);
+    [a.cross(b), a.cross(c), b.cross(c)]
+        .into_iter()
+        .max_by(|p, q| p.length_squared().total_cmp(&q.length_squared()))
+        .filter(|v| v.length_squared() > 1e-12)
+        .map(|v| v.normalize())
+}
+
+/// Unit normal of the kernel of a rank one matrix.
+fn largest_row(m: macroquad::math::Mat3) -> Option<macroquad::math::Vec3> {
+    let rows = m.transpose();
+    [rows.x_axis, rows.y_axis, rows.z_axis]
+        .into_iter()
+        .max_by(|p, q| p.length_squared().total_cmp(&q.length_squared()))
+        .filter(|v| v.length_squared() > 1e-12)
+        .map(|v| v.normalize())
+}
+
+fn trace(
+    system: crate::system::System,
+    params: &[f32],
+    integrator: crate::integrator::Integrator,
+    mut p: macroquad::math::Vec3,
+    h: f32,
+    length: f32,
+    scale: f32,
+) -> Vec<macroquad::math::Vec3> {
+    let f = |p| system.derivative(p, params);
+    let mut ray = vec![p];
+    for _ in 0..(length / h.abs()) as usize {
+        p = integrator.step(f, p, h);
+        if !p.is_finite() || p.length() > 4.0 * scale {
+            break;
+        }
+        ray.push(p);
+    }
+    ray
+}
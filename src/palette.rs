@@ -0,0 +1,161 @@
+pub const OKABE_ITO: [macroquad::color::Color; 7] = [
+    macroquad::color::Color::new(0.902, 0.624, 0.0, 1.0),
+    macroquad::color::Color::new(0.337, 0.706, 0.914, 1.0),
+    macroquad::color::Color::new(0.0, 0.620, 0.451, 1.0),
+    macroquad::color::Color::new(0.941, 0.894, 0.259, 1.0),
+    macroquad::color::Color::new(0.0, 0.447, 0.698, 1.0),
+    macroquad::color::Color::new(0.835, 0.369, 0.0, 1.0),
+    macroquad::color::Color::new(0.800, 0.475, 0.655, 1.0),
+];
+
+const CIVIDIS: [[f32; 3]; 9] = [
+    [0.000, 0.133, 0.306],
+    [0.071, 0.208, 0.439],
+    [0.231, 0.286, 0.424],
+    [0.341, 0.365, 0.427],
+    [0.439, 0.443, 0.451],
+    [0.541, 0.529, 0.475],
+    [0.651, 0.616, 0.459],
+    [0.769, 0.710, 0.424],
+    [0.996, 0.910, 0.220],
+];
+
+const BLUE_ORANGE: [[f32; 3]; 5] = [
+    [0.000, 0.447, 0.698],
+    [0.337, 0.706, 0.914],
+    [0.800, 0.800, 0.800],
+    [0.902, 0.624, 0.000],
+    [0.835, 0.369, 0.000],
+];
+
+const MATRIX: [[f32; 3]; 3] = [
+    [0.000, 0.235, 0.000],
+    [0.000, 0.667, 0.176],
+    [0.706, 1.000, 0.706],
+];
+
+const BLUEPRINT: [[f32; 3]; 3] = [
+    [0.361, 0.561, 0.839],
+    [0.678, 0.835, 1.000],
+    [1.000, 1.000, 1.000],
+];
+
+const VIRIDIS: [[f32; 3]; 9] = [
+    [0.267, 0.005, 0.329],
+    [0.278, 0.175, 0.484],
+    [0.230, 0.322, 0.546],
+    [0.173, 0.449, 0.558],
+    [0.128, 0.567, 0.551],
+    [0.153, 0.680, 0.506],
+    [0.361, 0.786, 0.388],
+    [0.668, 0.862, 0.196],
+    [0.993, 0.906, 0.144],
+];
+
+const PLASMA: [[f32; 3]; 9] = [
+    [0.050, 0.030, 0.528],
+    [0.294, 0.012, 0.631],
+    [0.492, 0.012, 0.658],
+    [0.665, 0.139, 0.586],
+    [0.798, 0.280, 0.470],
+    [0.899, 0.424, 0.360],
+    [0.973, 0.585, 0.252],
+    [0.994, 0.765, 0.159],
+    [0.940, 0.975, 0.131],
+];
+
+const INFERNO: [[f32; 3]; 9] = [
+    [0.001, 0.000, 0.014],
+    [0.122, 0.047, 0.282],
+    [0.335, 0.060, 0.428],
+    [0.531, 0.134, 0.426],
+    [0.729, 0.212, 0.333],
+    [0.894, 0.347, 0.191],
+    [0.981, 0.548, 0.035],
+    [0.976, 0.786, 0.212],
+    [0.988, 1.000, 0.645],
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Colormap {
+    Rainbow,
+    Cividis,
+    BlueOrange,
+    Matrix,
+    Blueprint,
+    Viridis,
+    Plasma,
+    Inferno,
+}
+
+impl Colormap {
+    pub const ALL: [Colormap; 8] = [
+        Colormap::Rainbow,
+        Colormap::Cividis,
+        Colormap::BlueOrange,
+        Colormap::Matrix,
+        Colormap::Blueprint,
+        Colormap::Viridis,
+        Colormap::Plasma,
+        Colormap::Inferno,
+    ];
+    pub const NAMES: [&'static str; 8] = [
+        "rainbow",
+        "cividis (cvd safe)",
+        "blue-orange (cvd safe)",
+        "matrix",
+        "blueprint",
+        "viridis",
+        "plasma",
+        "inferno",
+    ];
+    pub const KEYS: [&'static str; 8] = [
+        "rainbow",
+        "cividis",
+        "blue-orange",
+        "matrix",
+        "blueprint",
+        "viridis",
+        "plasma",
+        "inferno",
+    ];
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::KEYS
+            .iter()
+            .position(|k| *k == key)
+            .map(|i| Self::ALL[i])
+    }
+
+    pub fn sample(&self, t: f32) -> macroquad::color::Color {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Colormap::Rainbow => macroquad::color::hsl_to_rgb(1.0 - t, 1.0, 0.5),
+            Colormap::Cividis => interpolate(&CIVIDIS, t),
+            Colormap::BlueOrange => interpolate(&BLUE_ORANGE, t),
+            Colormap::Matrix => interpolate(&MATRIX, t),
+            Colormap::Blueprint => interpolate(&BLUEPRINT, t),
+            Colormap::Viridis => interpolate(&VIRIDIS, t),
+            Colormap::Plasma => interpolate(&PLASMA, t),
+            Colormap::Inferno => interpolate(&INFERNO, t),
+        }
+    }
+}
+
+pub fn categorical(i: usize) -> macroquad::color::Color {
+    OKABE_ITO[i % OKABE_ITO.len()]
+}
+
+fn interpolate(stops: &[[f32; 3]], t: f32) -> macroquad::color::Color {
+    let position = t * (stops.len() - 1) as f32;
+    let i = (position as usize).min(stops.len() - 2);
+    let f = position - i as f32;
+    let [r0, g0, b0] = stops[i];
+    let [r1, g1, b1] = stops[i + 1];
+    macroquad::color::Color::new(
+        r0 + (r1 - r0) * f,
+        g0 + (g1 - g0) * f,
+        b0 + (b1 - b0) * f,
+        1.0,
+    )
+}
@@ -0,0 +1,63 @@
+pub struct Panel {
+    pub rect: macroquad::math::Rect,
+    pub title: &'static str,
+}
+
+impl Panel {
+    pub fn new(title: &'static str, rect: macroquad::math::Rect) -> Self {
+        Self { rect, title }
+    }
+
+    pub fn contains(&self, p: macroquad::math::Vec2) -> bool {
+        self.rect.contains(p)
+    }
+
+    pub fn to_unit(&self, p: macroquad::math::Vec2) -> macroquad::math::Vec2 {
+        macroquad::math::vec2(
+            (p.x - self.rect.x) / self.rect.w,
+            1.0 - (p.y - self.rect.y) / self.rect.h,
+        )
+    }
+
+    pub fn to_screen(&self, u: macroquad::math::Vec2) -> macroquad::math::Vec2 {
+        macroquad::math::vec2(
+            self.rect.x + u.x * self.rect.w,
+            self.rect.y + (1.0 - u.y) * self.rect.h,
+        )
+    }
+
+    pub fn draw_frame(&self, x_label: &str, y_label: &str) {
+        let r = self.rect;
+        macroquad::shapes::draw_rectangle(
+            r.x,
+            r.y,
+            r.w,
+            r.h,
+            macroquad::color::Color::new(0.0, 0.0, 0.0, 0.7),
+        );
+        macroquad::shapes::draw_rectangle_lines(r.x, r.y, r.w, r.h, 1.0, macroquad::color::GRAY);
+        macroquad::text::draw_text(self.title, r.x, r.y - 4.0, 16.0, macroquad::color::WHITE);
+        macroquad::text::draw_text(
+            x_label,
+            r.x + r.w - 40.0,
+            r.y + r.h + 14.0,
+            16.0,
+            macroquad::color::GRAY,
+        );
+        macroquad::text::draw_text(
+            y_label,
+            r.x - 14.0,
+            r.y + 12.0,
+            16.0,
+            macroquad::color::GRAY,
+        );
+    }
+}
+
+pub fn lerp((lo, hi): (f32, f32), t: f32) -> f32 {
+    lo + (hi - lo) * t
+}
+
+pub fn unlerp((lo, hi): (f32, f32), value: f32) -> f32 {
+    (value - lo) / (hi - lo)
+}
@@ -0,0 +1,257 @@
+const MAX_LOOPS: usize = 6;
+const MAX_ORBITS: usize = 50;
+const ITERATIONS: usize = 20;
+
+pub struct Orbit {
+    pub name: String,
+    pub period: f32,
+    pub loops: usize,
+    /// Floquet multipliers sorted by decreasing magnitude.
+    pub multipliers: [(f32, f32); 3],
+    pub points: Vec<macroquad::math::Vec3>,
+    pub visible: bool,
+}
+
+impl Orbit {
+    pub fn instability(&self) -> f32 {
+        let (re, im) = self.multipliers[0];
+        re.hypot(im)
+    }
+}
+
+/// Watches the first trajectory for close returns between successive z maxima and refines each
+/// candidate into an exact periodic orbit with a Newton shooting method.
+pub struct Library {
+    pub visible: bool,
+    pub searching: bool,
+    pub tolerance: f32,
+    pub orbits: Vec<Orbit>,
+    maxima: std::collections::VecDeque<(macroquad::math::Vec3, f32)>,
+    previous: Option<(macroquad::math::Vec3, macroquad::math::Vec3)>,
+    time: f32,
+}
+
+impl Library {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            searching: false,
+            tolerance: 2.0,
+            orbits: Vec::new(),
+            maxima: std::collections::VecDeque::new(),
+            previous: None,
+            time: 0.0,
+        }
+    }
+
+    pub fn clear_history(&mut self) {
+        self.maxima.clear();
+        self.previous = None;
+    }
+
+    pub fn observe(
+        &mut self,
+        p: macroquad::math::Vec3,
+        h: f32,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) {
+        self.time += h;
+        let peak = self
+            .previous
+            .and_then(|(a, b)| (b.z > a.z && b.z >= p.z).then_some(b));
+        self.previous = Some((self.previous.map_or(p, |(_, b)| b), p));
+        let Some(peak) = peak else {
+            return;
+        };
+        self.maxima.push_back((peak, self.time));
+        if self.maxima.len() > MAX_LOOPS + 1 {
+            self.maxima.pop_front();
+        }
+        let Some(candidate) = (1..self.maxima.len()).find_map(|loops| {
+            let (start, t0) = self.maxima[self.maxima.len() - 1 - loops];
+            (start.distance(peak) < self.tolerance).then_some((start, self.time - t0))
+        }) else {
+            return;
+        };
+        if self.orbits.len() >= MAX_ORBITS {
+            return;
+        }
+        let Some(orbit) = refine(system, params, integrator, dt, candidate.0, candidate.1) else {
+            return;
+        };
+        // Slow spirals around an equilibrium also return closely but are not periodic.
+        let (min, max) = orbit
+            .points
+            .iter()
+            .fold((peak, peak), |(min, max), p| (min.min(*p), max.max(*p)));
+        if min.distance(max) < 2.0 * self.tolerance {
+            return;
+        }
+        let duplicate = self.orbits.iter().any(|other| {
+            other.name == orbit.name && (other.period - orbit.period).abs() < 1e-2 * orbit.period
+        });
+        if !duplicate {
+            self.orbits.push(orbit);
+            self.orbits
+                .sort_by(|a, b| a.loops.cmp(&b.loops).then(a.name.cmp(&b.name)));
+        }
+    }
+
+    /// Runs the close return search over a stored trail whose points are `h` apart, leaving the
+    /// live search where it was.
+    pub fn scan(
+        &mut self,
+        trail: &[macroquad::math::Vec3],
+        h: f32,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+    ) -> usize {
+        let found = self.orbits.len();
+        let live = (
+            std::mem::take(&mut self.maxima),
+            self.previous.take(),
+            self.time,
+        );
+        for p in trail {
+            self.observe(*p, h, system, params, integrator, dt);
+        }
+        (self.maxima, self.previous, self.time) = live;
+        self.orbits.len() - found
+    }
+
+    pub fn draw(&self, transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        for (i, orbit) in self.orbits.iter().enumerate().filter(|(_, o)| o.visible) {
+            let color = macroquad::color::hsl_to_rgb((i as f32 * 0.17).fract(), 0.9, 0.6);
+            let next = orbit.points.iter().cycle().skip(1);
+            for (a, b) in orbit.points.iter().zip(next) {
+                macroquad::models::draw_line_3d(transform(*a), transform(*b), color);
+            }
+        }
+    }
+}
+
+fn flow_for(
+    system: crate::system::System,
+    params: &[f32],
+    integrator: crate::integrator::Integrator,
+    dt: f32,
+    mut p: macroquad::math::Vec3,
+    time: f32,
+) -> macroquad::math::Vec3 {
+    let f = |p| system.derivative(p, params);
+    let steps = (time / dt).floor().max(0.0);
+    for _ in 0..steps as usize {
+        p = integrator.step(f, p, dt);
+    }
+    let rest = time - steps * dt;
+    if rest > 1e-7 {
+        p = integrator.step(f, p, rest);
+    }
+    p
+}
+
+fn refine(
+    system: crate::system::System,
+    params: &[f32],
+    integrator: crate::integrator::Integrator,
+    dt: f32,
+    start: macroquad::math::Vec3,
+    period: f32,
+) -> Option<Orbit> {
+    let f = |p| system.derivative(p, params);
+    let phase = f(start);
+    let (mut x, mut t) = (start, period);
+    for _ in 0..ITERATIONS {
+        let end = flow_for(system, params, integrator, dt, x, t);
+        let h = 1e-3;
+        let column = |e: macroquad::math::Vec3| {
+            (flow_for(system, params, integrator, dt, x + e * h, t)
+                - flow_for(system, params, integrator, dt, x - e * h, t))
+                / (2.0 * h)
+        };
+        let monodromy = macroquad::math::Mat3::from_cols(
+            column(macroquad::math::Vec3::X),
+            column(macroquad::math::Vec3::Y),
+            column(macroquad::math::Vec3::Z),
+        );
+        let residual = end - x;
+        if !residual.is_finite() {
+            return None;
+        }
+        if residual.length() < 1e-4 * (1.0 + x.length()) {
+            return Some(orbit(system, params, integrator, dt, x, t, monodromy));
+        }
+        let a = monodromy - macroquad::math::Mat3::IDENTITY;
+        let jacobian = macroquad::math::Mat4::from_cols(
+            a.x_axis.extend(phase.x),
+            a.y_axis.extend(phase.y),
+            a.z_axis.extend(phase.z),
+            f(end).extend(0.0),
+        );
+        if jacobian.determinant().abs() < 1e-9 {
+            return None;
+        }
+        let phase_error = phase.dot(x - start);
+        let delta = jacobian.inverse() * -residual.extend(phase_error);
+        x += delta.truncate();
+        t += delta.w;
+        if t <= dt {
+            return None;
+        }
+    }
+    None
+}
+
+fn orbit(
+    system: crate::system::System,
+    params: &[f32],
+    integrator: crate::integrator::Integrator,
+    dt: f32,
+    start: macroquad::math::Vec3,
+    period: f32,
+    monodromy: macroquad::math::Mat3,
+) -> Orbit {
+    let f = |p| system.derivative(p, params);
+    let mut points = vec![start];
+    let mut p = start;
+    for _ in 0..(period / dt).ceil() as usize - 1 {
+        p = integrator.step(f, p, dt);
+        points.push(p);
+    }
+    let n = points.len();
+    let peaks = (0..n)
+        .filter(|&i| {
+            let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            b.z > a.z && b.z >= c.z
+        })
+        .map(|i| if points[i].x < 0.0 { 'L' } else { 'R' })
+        .collect::<Vec<_>>();
+    let loops = peaks.len().max(1);
+    let name = if matches!(
+        system,
+        crate::system::System::Lorenz | crate::system::System::FractionalLorenz
+    ) && !peaks.is_empty()
+    {
+        (0..peaks.len())
+            .map(|r| peaks[r..].iter().chain(&peaks[..r]).collect::<String>())
+            .min()
+            .unwrap()
+    } else {
+        format!("{loops}-loop")
+    };
+    let mut multipliers = crate::analysis::eigenvalues(monodromy);
+    multipliers.sort_by(|a, b| b.0.hypot(b.1).total_cmp(&a.0.hypot(a.1)));
+    Orbit {
+        name,
+        period,
+        loops,
+        multipliers,
+        points,
+        visible: true,
+    }
+}
@@ -0,0 +1,167 @@
+pub const ABI_VERSION: u32 = 1;
+const DIRECTORY: &str = "plugins";
+const ENTRY_POINT: &std::ffi::CStr = c"lorenz_plugin";
+
+#[repr(C)]
+pub struct RawParam {
+    pub name: *const std::ffi::c_char,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+#[repr(C)]
+pub struct RawSystem {
+    pub name: *const std::ffi::c_char,
+    pub params: *const RawParam,
+    pub param_count: u32,
+    pub start: [f32; 3],
+    pub camera_distance: f32,
+    /// Null in a broken plugin, which is rejected.
+    pub derivative: Option<extern "C" fn(p: *const f32, params: *const f32, out: *mut f32)>,
+}
+
+type EntryPoint = extern "C" fn(abi_version: *mut u32, count: *mut u32) -> *const RawSystem;
+
+pub struct PluginSystem {
+    pub name: &'static str,
+    pub params: Vec<crate::system::Param>,
+    pub start: macroquad::math::Vec3,
+    pub camera_distance: f32,
+    derivative: extern "C" fn(p: *const f32, params: *const f32, out: *mut f32),
+}
+
+impl crate::system::DynamicalSystem for PluginSystem {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn params(&self) -> &[crate::system::Param] {
+        &self.params
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start
+    }
+
+    fn camera_distance(&self) -> f32 {
+        self.camera_distance
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let mut out = [0.0; 3];
+        (self.derivative)(p.to_array().as_ptr(), params.as_ptr(), out.as_mut_ptr());
+        macroquad::math::Vec3::from_array(out)
+    }
+}
+
+static SYSTEMS: std::sync::OnceLock<Vec<PluginSystem>> = std::sync::OnceLock::new();
+
+pub fn systems() -> &'static [PluginSystem] {
+    SYSTEMS.get_or_init(load)
+}
+
+fn load() -> Vec<PluginSystem> {
+    let Ok(entries) = std::fs::read_dir(DIRECTORY) else {
+        return Vec::new();
+    };
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut systems = Vec::new();
+    for path in paths {
+        match load_library(&path) {
+            Ok(loaded) => {
+                eprintln!(
+                    "plugin: loaded {} systems from {}",
+                    loaded.len(),
+                    path.display()
+                );
+                systems.extend(loaded);
+            }
+            Err(err) => eprintln!("plugin: {}: {err}", path.display()),
+        }
+    }
+    systems
+}
+
+#[cfg(unix)]
+fn load_library(path: &std::path::Path) -> Result<Vec<PluginSystem>, String> {
+    let c_path = std::ffi::CString::new(path.as_os_str().as_encoded_bytes())
+        .map_err(|err| err.to_string())?;
+    let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        let reason = unsafe { libc::dlerror() };
+        if reason.is_null() {
+            return Err("cannot open library".to_owned());
+        }
+        let reason = unsafe { std::ffi::CStr::from_ptr(reason) };
+        return Err(format!("cannot open library: {}", reason.to_string_lossy()));
+    }
+    let symbol = unsafe { libc::dlsym(handle, ENTRY_POINT.as_ptr()) };
+    if symbol.is_null() {
+        return Err(format!(
+            "missing entry point '{}'",
+            ENTRY_POINT.to_string_lossy()
+        ));
+    }
+    let entry: EntryPoint = unsafe { std::mem::transmute(symbol) };
+    let (mut abi_version, mut count) = (0, 0);
+    let raw = entry(&mut abi_version, &mut count);
+    if abi_version != ABI_VERSION {
+        return Err(format!("unsupported ABI version {abi_version}"));
+    }
+    if raw.is_null() {
+        return Ok(Vec::new());
+    }
+    let raw = unsafe { std::slice::from_raw_parts(raw, count as usize) };
+    raw.iter()
+        .map(|system| unsafe { convert(system) })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn load_library(_path: &std::path::Path) -> Result<Vec<PluginSystem>, String> {
+    Err("plugins are only supported on unix".to_owned())
+}
+
+#[cfg(unix)]
+unsafe fn convert(system: &RawSystem) -> Result<PluginSystem, String> {
+    let name = |ptr: *const std::ffi::c_char| -> Result<&'static str, String> {
+        if ptr.is_null() {
+            return Err("missing name".to_owned());
+        }
+        let name = unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(|err| err.to_string())?;
+        Ok(Box::leak(name.to_owned().into_boxed_str()))
+    };
+    let params = if system.params.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(system.params, system.param_count as usize) }
+    };
+    Ok(PluginSystem {
+        name: name(system.name)?,
+        params: params
+            .iter()
+            .map(|param| {
+                Ok(crate::system::Param {
+                    name: name(param.name)?,
+                    default: param.default,
+                    min: param.min,
+                    max: param.max,
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        start: macroquad::math::Vec3::from_array(system.start),
+        camera_distance: system.camera_distance,
+        derivative: system.derivative.ok_or("missing derivative")?,
+    })
+}
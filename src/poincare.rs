@@ -0,0 +1,220 @@
+const MAX_CROSSINGS: usize = 20_000;
+const RECORD_SIZE: u32 = 512;
+
+pub const AXES: [&str; 3] = ["x", "y", "z"];
+
+struct Recording {
+    target: macroquad::texture::RenderTarget,
+    directory: String,
+    fps: f32,
+    frame: usize,
+    recorded: usize,
+}
+
+pub struct Poincare {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub axis: usize,
+    pub offset: f32,
+    /// Also draw the plane and the crossings in the 3D view.
+    pub in_scene: bool,
+    crossings: Vec<macroquad::math::Vec2>,
+    previous: Option<macroquad::math::Vec3>,
+    recording: Option<Recording>,
+}
+
+impl Poincare {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "poincare section",
+                macroquad::math::Rect::new(280.0, 280.0, 220.0, 220.0),
+            ),
+            axis: 2,
+            offset: 27.0,
+            in_scene: false,
+            crossings: Vec::new(),
+            previous: None,
+            recording: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.crossings.clear();
+        self.previous = None;
+    }
+
+    fn plane_axes(&self) -> (usize, usize) {
+        match self.axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        }
+    }
+
+    pub fn labels(&self) -> (&'static str, &'static str) {
+        let (u, v) = self.plane_axes();
+        (AXES[u], AXES[v])
+    }
+
+    pub fn observe(&mut self, p: macroquad::math::Vec3) {
+        if let Some(a) = self.previous
+            && a[self.axis] < self.offset
+            && p[self.axis] >= self.offset
+            && self.crossings.len() < MAX_CROSSINGS
+        {
+            let t = (self.offset - a[self.axis]) / (p[self.axis] - a[self.axis]);
+            let q = a.lerp(p, t);
+            let (u, v) = self.plane_axes();
+            self.crossings.push(macroquad::math::vec2(q[u], q[v]));
+        }
+        self.previous = Some(p);
+    }
+
+    pub fn draw(&self) {
+        self.draw_in(&self.panel);
+    }
+
+    /// The plane, spanning the crossings found so far, and a marker at each crossing.
+    pub fn draw_scene(&self, transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        let (u, v) = self.plane_axes();
+        let lift = |p: macroquad::math::Vec2| {
+            let mut q = macroquad::math::Vec3::ZERO;
+            (q[self.axis], q[u], q[v]) = (self.offset, p.x, p.y);
+            q
+        };
+        let (min, max) = match self.crossings.first() {
+            Some(first) => self
+                .crossings
+                .iter()
+                .fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p))),
+            None => (
+                macroquad::math::Vec2::splat(-20.0),
+                macroquad::math::Vec2::splat(20.0),
+            ),
+        };
+        let margin = (max - min) * 0.1 + 1.0;
+        let (min, max) = (min - margin, max + margin);
+        let corner = transform(lift(min));
+        let along_u = transform(lift(macroquad::math::vec2(max.x, min.y))) - corner;
+        let along_v = transform(lift(macroquad::math::vec2(min.x, max.y))) - corner;
+        macroquad::models::draw_affine_parallelogram(
+            corner,
+            along_u,
+            along_v,
+            None,
+            macroquad::color::Color::new(0.4, 0.6, 1.0, 0.15),
+        );
+        for p in self.crossings.iter() {
+            macroquad::models::draw_cube(
+                transform(lift(*p)),
+                macroquad::math::Vec3::splat(0.3),
+                None,
+                macroquad::color::ORANGE,
+            );
+        }
+    }
+
+    fn draw_in(&self, panel: &crate::panel::Panel) {
+        let (x_label, y_label) = self.labels();
+        panel.draw_frame(x_label, y_label);
+        let Some(first) = self.crossings.first() else {
+            return;
+        };
+        let (min, max) = self
+            .crossings
+            .iter()
+            .fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p)));
+        let max = max.max(min + 1e-6);
+        let newest = self.crossings.len() - 1;
+        for (i, p) in self.crossings.iter().enumerate() {
+            let p = panel.to_screen((*p - min) / (max - min));
+            let (size, color) = if i == newest {
+                (4.0, macroquad::color::ORANGE)
+            } else {
+                (2.0, macroquad::color::WHITE)
+            };
+            macroquad::shapes::draw_rectangle(
+                p.x - size / 2.0,
+                p.y - size / 2.0,
+                size,
+                size,
+                color,
+            );
+        }
+        macroquad::text::draw_text(
+            &format!("{} crossings", self.crossings.len()),
+            panel.rect.x + 4.0,
+            panel.rect.y + 14.0,
+            16.0,
+            macroquad::color::GRAY,
+        );
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn start_recording(&mut self, settings: &crate::render::Settings) {
+        let directory = format!("{}/poincare", settings.directory);
+        if let Err(err) = std::fs::create_dir_all(&directory) {
+            eprintln!("poincare: {err}");
+            return;
+        }
+        self.recording = Some(Recording {
+            target: macroquad::texture::render_target_ex(
+                RECORD_SIZE,
+                RECORD_SIZE,
+                macroquad::texture::RenderTargetParams {
+                    sample_count: 1,
+                    depth: false,
+                },
+            ),
+            directory,
+            fps: settings.fps,
+            frame: 0,
+            recorded: usize::MAX,
+        });
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(recording) = self.recording.take()
+            && recording.frame > 0
+        {
+            crate::render::encode_video(&recording.directory, recording.fps);
+        }
+    }
+
+    /// Writes a frame whenever new crossings have accumulated since the last one.
+    pub fn record(&mut self, background: macroquad::color::Color) {
+        let Some(recording) = &self.recording else {
+            return;
+        };
+        if recording.recorded == self.crossings.len() {
+            return;
+        }
+        let size = RECORD_SIZE as f32;
+        let mut camera = macroquad::camera::Camera2D::from_display_rect(
+            macroquad::math::Rect::new(0.0, 0.0, size, size),
+        );
+        camera.render_target = Some(recording.target.clone());
+        macroquad::camera::set_camera(&camera);
+        macroquad::window::clear_background(background);
+        let panel = crate::panel::Panel::new(
+            self.panel.title,
+            macroquad::math::Rect::new(24.0, 24.0, size - 48.0, size - 48.0),
+        );
+        self.draw_in(&panel);
+        macroquad::camera::set_default_camera();
+
+        let recording = self.recording.as_mut().unwrap();
+        let path = format!("{}/frame_{:05}.png", recording.directory, recording.frame);
+        let image = recording.target.texture.get_texture_data();
+        if let Err(err) = crate::image_file::save_png(&image, &path) {
+            eprintln!("poincare: {err}");
+        }
+        recording.frame += 1;
+        recording.recorded = self.crossings.len();
+    }
+}
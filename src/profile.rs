@@ -0,0 +1,17 @@
+pub struct Profile {
+    pub params: Vec<f32>,
+    pub start: macroquad::math::Vec3,
+    pub colormap: crate::palette::Colormap,
+    pub camera: crate::camera::OrbitCamera,
+}
+
+impl Profile {
+    pub fn default_for(system: crate::system::System, colormap: crate::palette::Colormap) -> Self {
+        Self {
+            params: system.defaults(),
+            start: system.start(),
+            colormap,
+            camera: crate::camera::OrbitCamera::new(system.camera_distance()),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+const SMOOTHING: f64 = 0.1;
+
+struct Stage {
+    name: &'static str,
+    accumulated: f64,
+    smoothed: f64,
+}
+
+struct Timings {
+    stages: Vec<Stage>,
+    frame_start: Option<std::time::Instant>,
+    frame: f64,
+}
+
+thread_local! {
+    static TIMINGS: std::cell::RefCell<Timings> = const {
+        std::cell::RefCell::new(Timings {
+            stages: Vec::new(),
+            frame_start: None,
+            frame: 0.0,
+        })
+    };
+}
+
+pub struct Scope {
+    name: &'static str,
+    start: std::time::Instant,
+}
+
+pub fn scope(name: &'static str) -> Scope {
+    Scope {
+        name,
+        start: std::time::Instant::now(),
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_secs_f64() * 1000.0;
+        TIMINGS.with_borrow_mut(|timings| {
+            match timings.stages.iter_mut().find(|s| s.name == self.name) {
+                Some(stage) => stage.accumulated += elapsed,
+                None => timings.stages.push(Stage {
+                    name: self.name,
+                    accumulated: elapsed,
+                    smoothed: elapsed,
+                }),
+            }
+        });
+    }
+}
+
+pub fn end_frame() {
+    TIMINGS.with_borrow_mut(|timings| {
+        let now = std::time::Instant::now();
+        if let Some(start) = timings.frame_start {
+            let frame = (now - start).as_secs_f64() * 1000.0;
+            timings.frame += (frame - timings.frame) * SMOOTHING;
+        }
+        timings.frame_start = Some(now);
+        for stage in timings.stages.iter_mut() {
+            stage.smoothed += (stage.accumulated - stage.smoothed) * SMOOTHING;
+            stage.accumulated = 0.0;
+        }
+    });
+}
+
+pub struct Profiler {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "frame breakdown",
+                macroquad::math::Rect::new(780.0, 30.0, 300.0, 130.0),
+            ),
+        }
+    }
+
+    pub fn draw(&self) {
+        let r = self.panel.rect;
+        self.panel.draw_frame("", "");
+        TIMINGS.with_borrow(|timings| {
+            let total = timings
+                .frame
+                .max(timings.stages.iter().map(|s| s.smoothed).sum());
+            let (bar_x, bar_y, bar_h) = (r.x + 6.0, r.y + 6.0, 20.0);
+            let bar_w = r.w - 12.0;
+            macroquad::shapes::draw_rectangle(
+                bar_x,
+                bar_y,
+                bar_w,
+                bar_h,
+                macroquad::color::DARKGRAY,
+            );
+            let mut x = bar_x;
+            for (i, stage) in timings.stages.iter().enumerate() {
+                let color = crate::palette::categorical(i);
+                let w = (stage.smoothed / total.max(1e-6)) as f32 * bar_w;
+                macroquad::shapes::draw_rectangle(x, bar_y, w, bar_h, color);
+                x += w;
+                let (column, row) = (i % 2, i / 2);
+                let label_x = r.x + 6.0 + column as f32 * r.w / 2.0;
+                let label_y = bar_y + bar_h + 18.0 + row as f32 * 16.0;
+                macroquad::shapes::draw_rectangle(label_x, label_y - 9.0, 8.0, 8.0, color);
+                macroquad::text::draw_text(
+                    &format!("{} {:.2} ms", stage.name, stage.smoothed),
+                    label_x + 12.0,
+                    label_y,
+                    16.0,
+                    macroquad::color::WHITE,
+                );
+            }
+            macroquad::text::draw_text(
+                &format!(
+                    "frame {:.2} ms ({:.0} fps)",
+                    timings.frame,
+                    1000.0 / timings.frame.max(1e-6)
+                ),
+                r.x + 6.0,
+                r.y + r.h - 6.0,
+                16.0,
+                macroquad::color::GRAY,
+            );
+        });
+    }
+}
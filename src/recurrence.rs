@@ -0,0 +1,132 @@
+const RESOLUTION: usize = 256;
+const REFRESH_FRAMES: usize = 10;
+const MIN_LINE: usize = 2;
+
+/// Recurrence plot of the recent trail of the first trajectory: pixel (i, j) is lit when samples
+/// i and j lie closer than `threshold`, coloured by how close they are.
+pub struct Recurrence {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub threshold: f32,
+    pub window: f32,
+    pub rate: f32,
+    pub determinism: f32,
+    frames: usize,
+    image: macroquad::texture::Image,
+    texture: macroquad::texture::Texture2D,
+}
+
+impl Recurrence {
+    pub fn new() -> Self {
+        let image = macroquad::texture::Image::gen_image_color(
+            RESOLUTION as u16,
+            RESOLUTION as u16,
+            macroquad::color::BLANK,
+        );
+        let texture = macroquad::texture::Texture2D::from_image(&image);
+        texture.set_filter(macroquad::texture::FilterMode::Nearest);
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "recurrence plot",
+                macroquad::math::Rect::new(280.0, 530.0, 220.0, 220.0),
+            ),
+            threshold: 2.0,
+            window: 500.0,
+            rate: 0.0,
+            determinism: 0.0,
+            frames: REFRESH_FRAMES,
+            image,
+            texture,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        points: &std::collections::VecDeque<macroquad::math::DVec3>,
+        colormap: crate::palette::Colormap,
+    ) {
+        self.frames += 1;
+        if self.frames < REFRESH_FRAMES {
+            return;
+        }
+        self.frames = 0;
+        let n = points.len().min(self.window as usize);
+        let samples = points.range(points.len() - n..).collect::<Vec<_>>();
+        let sample = |i: usize| samples[i * n / RESOLUTION];
+        let threshold = self.threshold * self.threshold;
+        let mut close = vec![false; RESOLUTION * RESOLUTION];
+        for i in 0..RESOLUTION {
+            for j in 0..RESOLUTION {
+                let distance = if n > 0 {
+                    sample(i).distance_squared(*sample(j)) as f32
+                } else {
+                    f32::MAX
+                };
+                close[i * RESOLUTION + j] = distance < threshold;
+                let color = if close[i * RESOLUTION + j] {
+                    colormap.sample(1.0 - (distance / threshold).sqrt())
+                } else {
+                    macroquad::color::BLANK
+                };
+                self.image
+                    .set_pixel(i as u32, (RESOLUTION - 1 - j) as u32, color);
+            }
+        }
+        let recurrent = close.iter().filter(|c| **c).count();
+        self.rate = recurrent as f32 / (RESOLUTION * RESOLUTION) as f32;
+        self.determinism = determinism(&close);
+        self.texture.update(&self.image);
+    }
+
+    pub fn draw(&self) {
+        let r = self.panel.rect;
+        self.panel.draw_frame("t", "t");
+        macroquad::texture::draw_texture_ex(
+            &self.texture,
+            r.x,
+            r.y,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(r.w, r.h)),
+                ..Default::default()
+            },
+        );
+        macroquad::text::draw_text(
+            &format!(
+                "recurrence rate {:.1}%, determinism {:.1}%",
+                self.rate * 100.0,
+                self.determinism * 100.0
+            ),
+            r.x,
+            r.y + r.h + 14.0,
+            16.0,
+            macroquad::color::GRAY,
+        );
+    }
+}
+
+/// Share of the recurrent points off the main diagonal that belong to diagonal lines of at least
+/// `MIN_LINE` points, high for periodic and deterministic motion and low for noise.
+fn determinism(close: &[bool]) -> f32 {
+    let (mut recurrent, mut in_lines) = (0, 0);
+    for offset in 1..RESOLUTION {
+        let mut run = 0;
+        for i in 0..=RESOLUTION - offset {
+            if i < RESOLUTION - offset && close[i * RESOLUTION + i + offset] {
+                run += 1;
+                recurrent += 1;
+                continue;
+            }
+            if run >= MIN_LINE {
+                in_lines += run;
+            }
+            run = 0;
+        }
+    }
+    if recurrent == 0 {
+        0.0
+    } else {
+        in_lines as f32 / recurrent as f32
+    }
+}
@@ -0,0 +1,83 @@
+#[derive(Clone)]
+pub struct Settings {
+    pub width: f32,
+    pub height: f32,
+    pub fps: f32,
+    pub samples: i32,
+    pub directory: String,
+}
+
+impl Settings {
+    pub fn new(config: &crate::config::Config) -> Self {
+        Self {
+            width: config.get("render-width").unwrap_or(1920.0),
+            height: config.get("render-height").unwrap_or(1080.0),
+            fps: config.get("render-fps").unwrap_or(30.0),
+            samples: config.get("render-msaa").unwrap_or(4).max(1),
+            directory: config
+                .get("render-output")
+                .unwrap_or_else(|| "render".to_owned()),
+        }
+    }
+}
+
+pub struct OfflineRender {
+    pub settings: Settings,
+    pub target: macroquad::texture::RenderTarget,
+    pub frame: usize,
+    pub frames: usize,
+}
+
+impl OfflineRender {
+    pub fn new(settings: Settings, duration: f32) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&settings.directory)?;
+        let target = macroquad::texture::render_target_ex(
+            settings.width as u32,
+            settings.height as u32,
+            macroquad::texture::RenderTargetParams {
+                sample_count: settings.samples,
+                depth: true,
+            },
+        );
+        let frames = (duration * settings.fps).ceil() as usize + 1;
+        Ok(Self {
+            settings,
+            target,
+            frame: 0,
+            frames,
+        })
+    }
+
+    pub fn time(&self) -> f32 {
+        self.frame as f32 / self.settings.fps
+    }
+
+    pub fn finish_frame(&mut self) -> bool {
+        let path = format!("{}/frame_{:05}.png", self.settings.directory, self.frame);
+        let image = self.target.texture.get_texture_data();
+        if let Err(err) = crate::image_file::save_png(&image, &path) {
+            eprintln!("render: {err}");
+        }
+        self.frame += 1;
+        if self.frame < self.frames {
+            return false;
+        }
+        encode_video(&self.settings.directory, self.settings.fps);
+        true
+    }
+}
+
+pub fn encode_video(directory: &str, fps: f32) {
+    let status = std::process::Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-framerate"])
+        .arg(fps.to_string())
+        .arg("-i")
+        .arg(format!("{directory}/frame_%05d.png"))
+        .args(["-pix_fmt", "yuv420p"])
+        .arg(format!("{directory}/video.mp4"))
+        .status();
+    match status {
+        Ok(status) if status.success() => eprintln!("render: wrote {directory}/video.mp4"),
+        _ => eprintln!("render: ffmpeg unavailable, frames left in {directory}"),
+    }
+}
@@ -0,0 +1,140 @@
+use itertools::Itertools;
+
+const MAX_MAXIMA: usize = 2_000;
+
+pub struct ReturnMap {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub cobweb: bool,
+    pub cobweb_steps: f32,
+    maxima: std::collections::VecDeque<macroquad::math::Vec3>,
+    previous: Option<(macroquad::math::Vec3, macroquad::math::Vec3)>,
+}
+
+impl ReturnMap {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "return map z(n+1) vs z(n)",
+                macroquad::math::Rect::new(280.0, 30.0, 220.0, 220.0),
+            ),
+            cobweb: true,
+            cobweb_steps: 8.0,
+            maxima: std::collections::VecDeque::new(),
+            previous: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.maxima.clear();
+        self.previous = None;
+    }
+
+    pub fn observe(&mut self, p: macroquad::math::Vec3) {
+        if let Some((a, b)) = self.previous
+            && b.z > a.z
+            && b.z >= p.z
+        {
+            self.maxima.push_back(refine_peak(a, b, p));
+            if self.maxima.len() > MAX_MAXIMA {
+                self.maxima.pop_front();
+            }
+        }
+        self.previous = Some((self.previous.map_or(p, |(_, b)| b), p));
+    }
+
+    fn cobweb_maxima(&self) -> impl Iterator<Item = &macroquad::math::Vec3> {
+        let n = (self.cobweb_steps as usize + 1).min(self.maxima.len());
+        self.maxima.iter().skip(self.maxima.len() - n)
+    }
+
+    pub fn draw(&self) {
+        let panel = &self.panel;
+        panel.draw_frame("z(n)", "z(n+1)");
+        let Some(first) = self.maxima.front() else {
+            return;
+        };
+        let (lo, hi) = self
+            .maxima
+            .iter()
+            .fold((first.z, first.z), |(lo, hi), p| (lo.min(p.z), hi.max(p.z)));
+        let range = (lo, hi.max(lo + 1e-6));
+        let to_screen = |a: f32, b: f32| {
+            panel.to_screen(macroquad::math::vec2(
+                crate::panel::unlerp(range, a),
+                crate::panel::unlerp(range, b),
+            ))
+        };
+
+        let (a, b) = (to_screen(lo, lo), to_screen(hi, hi));
+        macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::DARKGRAY);
+        for (a, b) in self.maxima.iter().tuple_windows() {
+            let p = to_screen(a.z, b.z);
+            macroquad::shapes::draw_rectangle(
+                p.x - 1.0,
+                p.y - 1.0,
+                2.0,
+                2.0,
+                macroquad::color::WHITE,
+            );
+        }
+
+        if self.cobweb {
+            let maxima = self.cobweb_maxima().collect::<Vec<_>>();
+            for (a, b) in maxima.iter().tuple_windows() {
+                let diagonal = to_screen(a.z, a.z);
+                let vertical = to_screen(a.z, b.z);
+                let horizontal = to_screen(b.z, b.z);
+                for (from, to) in [(diagonal, vertical), (vertical, horizontal)] {
+                    macroquad::shapes::draw_line(
+                        from.x,
+                        from.y,
+                        to.x,
+                        to.y,
+                        1.0,
+                        macroquad::color::ORANGE,
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn draw_3d(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        if !self.cobweb {
+            return;
+        }
+        for (i, p) in self.cobweb_maxima().enumerate() {
+            let p = transform(*p);
+            macroquad::models::draw_sphere(p, 0.5, None, macroquad::color::ORANGE);
+            crate::labels::draw_label(
+                camera,
+                p + macroquad::math::vec3(0.0, 2.0, 0.0),
+                &(i + 1).to_string(),
+                macroquad::color::ORANGE,
+                16.0,
+                false,
+            );
+        }
+    }
+}
+
+/// The vertex of the parabola through three equally spaced samples around a maximum of `z`, so
+/// the peaks do not jitter with where the integration steps happen to fall.
+fn refine_peak(
+    a: macroquad::math::Vec3,
+    b: macroquad::math::Vec3,
+    c: macroquad::math::Vec3,
+) -> macroquad::math::Vec3 {
+    let curvature = a.z - 2.0 * b.z + c.z;
+    if curvature >= 0.0 {
+        return b;
+    }
+    let t = (0.5 * (a.z - c.z) / curvature).clamp(-0.5, 0.5);
+    let along = if t < 0.0 { b.lerp(a, -t) } else { b.lerp(c, t) };
+    macroquad::math::vec3(along.x, along.y, b.z - 0.25 * (a.z - c.z) * t)
+}
@@ -0,0 +1,40 @@
+pub struct Rng {
+    seed: u64,
+    generator: macroquad::rand::RandGenerator,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let generator = macroquad::rand::RandGenerator::new();
+        generator.srand(seed);
+        Self { seed, generator }
+    }
+
+    pub fn from_time() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self::new(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.generator.srand(seed);
+    }
+
+    pub fn range(&self, low: f32, high: f32) -> f32 {
+        self.generator.gen_range(low, high)
+    }
+
+    /// Standard normal sample (Box-Muller).
+    pub fn gaussian(&self) -> f32 {
+        let u = self.range(f32::EPSILON, 1.0);
+        let v = self.range(0.0, std::f32::consts::TAU);
+        (-2.0 * u.ln()).sqrt() * v.cos()
+    }
+}
@@ -0,0 +1,321 @@
+const DIRECTORY: &str = "systems";
+const EXTENSION: &str = "sys";
+const MAX_PARAMS: usize = 13;
+
+pub struct ScriptSystem {
+    pub path: std::path::PathBuf,
+    pub name: &'static str,
+    pub params: &'static [crate::system::Param],
+    pub start: macroquad::math::Vec3,
+    pub camera_distance: f32,
+    equations: [crate::expression::Expression; 3],
+    source: String,
+}
+
+impl crate::system::DynamicalSystem for ScriptSystem {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn params(&self) -> &[crate::system::Param] {
+        self.params
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start
+    }
+
+    fn camera_distance(&self) -> f32 {
+        self.camera_distance
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let mut variables = [0.0; 3 + MAX_PARAMS];
+        let variables = &mut variables[..3 + params.len()];
+        variables[..3].copy_from_slice(&p.to_array());
+        variables[3..].copy_from_slice(params);
+        macroquad::math::vec3(
+            self.equations[0].evaluate(variables),
+            self.equations[1].evaluate(variables),
+            self.equations[2].evaluate(variables),
+        )
+    }
+}
+
+/// Systems are handed out as `&'static` so that `System` stays `Copy` and worker threads can
+/// keep evaluating one while it is replaced, which means every edit that is reloaded or
+/// recompiled leaks the equations of the previous version. Names and parameter lists are
+/// interned and unchanged sources are not reloaded, so only actual edits cost memory.
+static SYSTEMS: std::sync::RwLock<Vec<&'static ScriptSystem>> = std::sync::RwLock::new(Vec::new());
+static LOADED: std::sync::Once = std::sync::Once::new();
+static NAMES: std::sync::Mutex<std::collections::BTreeSet<&'static str>> =
+    std::sync::Mutex::new(std::collections::BTreeSet::new());
+static PARAMS: std::sync::Mutex<Vec<&'static [crate::system::Param]>> =
+    std::sync::Mutex::new(Vec::new());
+
+fn intern(text: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap();
+    if let Some(name) = names.get(text) {
+        return name;
+    }
+    let name = Box::leak(text.to_owned().into_boxed_str());
+    names.insert(name);
+    name
+}
+
+fn intern_params(params: Vec<crate::system::Param>) -> &'static [crate::system::Param] {
+    let same = |a: &crate::system::Param, b: &crate::system::Param| {
+        (a.name, a.default, a.min, a.max) == (b.name, b.default, b.min, b.max)
+    };
+    let mut interned = PARAMS.lock().unwrap();
+    if let Some(existing) = interned
+        .iter()
+        .find(|p| p.len() == params.len() && p.iter().zip(&params).all(|(a, b)| same(a, b)))
+    {
+        return existing;
+    }
+    let params = Box::leak(params.into_boxed_slice());
+    interned.push(params);
+    params
+}
+
+pub fn systems() -> Vec<&'static ScriptSystem> {
+    LOADED.call_once(load);
+    SYSTEMS.read().unwrap().clone()
+}
+
+pub fn get(i: usize) -> &'static ScriptSystem {
+    LOADED.call_once(load);
+    SYSTEMS.read().unwrap()[i]
+}
+
+fn load() {
+    let Ok(entries) = std::fs::read_dir(DIRECTORY) else {
+        return;
+    };
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut systems = SYSTEMS.write().unwrap();
+    for path in paths {
+        match parse(&path) {
+            Ok(system) => systems.push(Box::leak(Box::new(system))),
+            Err(err) => eprintln!("systems: {}: {err}", path.display()),
+        }
+    }
+}
+
+fn modified(path: &std::path::Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub struct Watcher {
+    seen: Vec<Option<std::time::SystemTime>>,
+    last_poll: f64,
+}
+
+impl Watcher {
+    pub fn new() -> Self {
+        Self {
+            seen: systems().iter().map(|s| modified(&s.path)).collect(),
+            last_poll: 0.0,
+        }
+    }
+
+    pub fn poll(&mut self) -> Vec<(usize, &'static [crate::system::Param])> {
+        let now = macroquad::time::get_time();
+        if now - self.last_poll < 0.5 {
+            return Vec::new();
+        }
+        self.last_poll = now;
+        let mut changed = Vec::new();
+        for (i, system) in systems().into_iter().enumerate() {
+            let modified = modified(&system.path);
+            if i == self.seen.len() {
+                self.seen.push(modified);
+                continue;
+            }
+            if modified == self.seen[i] {
+                continue;
+            }
+            self.seen[i] = modified;
+            match parse(&system.path) {
+                Ok(reloaded) if reloaded.source == system.source => {}
+                Ok(reloaded) => {
+                    eprintln!("systems: reloaded {}", system.path.display());
+                    SYSTEMS.write().unwrap()[i] = Box::leak(Box::new(reloaded));
+                    changed.push((i, system.params));
+                }
+                Err(err) => eprintln!("systems: {}: {err}", system.path.display()),
+            }
+        }
+        changed
+    }
+}
+
+/// Compiles a system typed in the UI, replacing an earlier one of the same name. Returns its index.
+pub fn define(text: &str) -> Result<usize, String> {
+    LOADED.call_once(load);
+    let system = parse_text(text, "custom".to_owned(), std::path::Path::new(""))?;
+    let mut systems = SYSTEMS.write().unwrap();
+    let existing = systems
+        .iter()
+        .position(|s| s.path.as_os_str().is_empty() && s.name == system.name);
+    if let Some(i) = existing
+        && systems[i].source == system.source
+    {
+        return Ok(i);
+    }
+    let system = Box::leak(Box::new(system));
+    Ok(match existing {
+        Some(i) => {
+            systems[i] = system;
+            i
+        }
+        None => {
+            systems.push(system);
+            systems.len() - 1
+        }
+    })
+}
+
+fn parse(path: &std::path::Path) -> Result<ScriptSystem, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let name = path
+        .file_stem()
+        .map_or("script".to_owned(), |s| s.to_string_lossy().into_owned());
+    parse_text(&text, name, path)
+}
+
+fn parse_text(
+    text: &str,
+    mut name: String,
+    path: &std::path::Path,
+) -> Result<ScriptSystem, String> {
+    let mut params: Vec<(String, f32, f32, f32)> = Vec::new();
+    let mut start = macroquad::math::vec3(0.1, 0.0, 0.0);
+    let mut camera_distance = 50.0;
+    let mut equations = [None, None, None];
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("invalid line '{line}'"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let numbers = |text: &str| {
+            text.split([' ', ',', '[', ']'])
+                .filter(|s| !s.is_empty())
+                .map(str::parse::<f32>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("invalid numbers in '{line}'"))
+        };
+        match key.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["name"] => name = value.to_owned(),
+            ["start"] => match numbers(value)?.as_slice() {
+                &[x, y, z] => start = macroquad::math::vec3(x, y, z),
+                _ => return Err(format!("expected three numbers in '{line}'")),
+            },
+            ["distance"] => match numbers(value)?.as_slice() {
+                &[distance] => camera_distance = distance,
+                _ => return Err(format!("expected a number in '{line}'")),
+            },
+            ["param", param] => {
+                let &[default, min, max] = numbers(value)?.as_slice() else {
+                    return Err(format!("expected 'default [min, max]' in '{line}'"));
+                };
+                if params.len() == MAX_PARAMS {
+                    return Err(format!("at most {MAX_PARAMS} parameters are supported"));
+                }
+                let identifier = param.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && param.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if !identifier
+                    || ["x", "y", "z"].contains(param)
+                    || crate::expression::Function::is_name(param)
+                {
+                    return Err(format!("'{param}' cannot name a parameter in '{line}'"));
+                }
+                if params.iter().any(|(name, ..)| name == param) {
+                    return Err(format!("duplicate parameter in '{line}'"));
+                }
+                if min > max {
+                    return Err(format!("minimum above maximum in '{line}'"));
+                }
+                if !(min..=max).contains(&default) {
+                    return Err(format!("default outside [min, max] in '{line}'"));
+                }
+                params.push((param.to_string(), default, min, max));
+            }
+            [axis @ ("dx" | "dy" | "dz")] => {
+                let i = ["dx", "dy", "dz"].iter().position(|a| a == axis).unwrap();
+                equations[i] = Some(value.to_owned());
+            }
+            _ => return Err(format!("unknown key '{key}'")),
+        }
+    }
+    let variables = ["x", "y", "z"]
+        .into_iter()
+        .chain(params.iter().map(|(name, ..)| name.as_str()))
+        .collect::<Vec<_>>();
+    let [dx, dy, dz] = equations.map(|e| {
+        let e = e.ok_or("missing equation for dx, dy or dz".to_owned())?;
+        crate::expression::Expression::parse(&e, &variables)
+    });
+    let equations = [dx?, dy?, dz?];
+    // Interned names are never freed, so only a file that parsed completely gets any.
+    let params = params
+        .into_iter()
+        .map(|(name, default, min, max)| crate::system::Param {
+            name: intern(&name),
+            default,
+            min,
+            max,
+        })
+        .collect();
+    Ok(ScriptSystem {
+        path: path.to_owned(),
+        name: intern(&name),
+        params: intern_params(params),
+        start,
+        camera_distance,
+        equations,
+        source: text.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(params: &str) -> Result<ScriptSystem, String> {
+        let text = format!("{params}\ndx = -x\ndy = -y\ndz = -z\n");
+        parse_text(&text, "test".to_owned(), std::path::Path::new("test.txt"))
+    }
+
+    #[test]
+    fn params() {
+        let system = parse("param k = 1 [0, 2]\nparam k2 = 0 [0, 0]").unwrap();
+        assert_eq!(system.params.len(), 2);
+        assert_eq!(system.params[1].name, "k2");
+    }
+
+    #[test]
+    fn invalid_params() {
+        for (params, error) in [
+            ("param k = 3 [2, 1]", "minimum above maximum"),
+            ("param k = 3 [0, 1]", "default outside"),
+            ("param k = 1 [0, 2]\nparam k = 1 [0, 2]", "duplicate"),
+            ("param x = 1 [0, 2]", "cannot name"),
+            ("param sin = 1 [0, 2]", "cannot name"),
+            ("param 2k = 1 [0, 2]", "cannot name"),
+        ] {
+            let message = parse(params).err().unwrap();
+            assert!(message.contains(error), "{params}: {message}");
+            assert!(message.contains("param "), "{message}");
+        }
+    }
+}
@@ -0,0 +1,177 @@
+const SUBSTEPS: usize = 10;
+const ITERATIONS: usize = 12;
+
+/// A noisy pseudo-orbit next to the exact orbit from the same start and the true orbit that
+/// shadows it, found by min-norm Newton refinement of the whole segment.
+pub struct Shadowing {
+    pub visible: bool,
+    pub log_noise: f32,
+    pub samples: f32,
+    pub status: String,
+    pseudo: Vec<macroquad::math::Vec3>,
+    naive: Vec<macroquad::math::Vec3>,
+    shadow: Vec<macroquad::math::Vec3>,
+}
+
+impl Shadowing {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            log_noise: -2.0,
+            samples: 200.0,
+            status: String::new(),
+            pseudo: Vec::new(),
+            naive: Vec::new(),
+            shadow: Vec::new(),
+        }
+    }
+
+    pub fn compute(
+        &mut self,
+        system: crate::system::System,
+        params: &[f32],
+        integrator: crate::integrator::Integrator,
+        dt: f32,
+        start: macroquad::math::Vec3,
+        rng: &crate::rng::Rng,
+    ) {
+        let f = |p| system.derivative(p, params);
+        let segment = |mut p: macroquad::math::Vec3| {
+            let mut points = Vec::with_capacity(SUBSTEPS);
+            for _ in 0..SUBSTEPS {
+                p = integrator.step(f, p, dt);
+                points.push(p);
+            }
+            points
+        };
+        let map = |p| *segment(p).last().unwrap();
+        let noise = 10.0_f32.powf(self.log_noise);
+        let n = self.samples as usize;
+
+        let mut pseudo = vec![start];
+        for _ in 1..n {
+            let kick = macroquad::math::vec3(rng.gaussian(), rng.gaussian(), rng.gaussian());
+            pseudo.push(map(*pseudo.last().unwrap()) + kick * noise);
+        }
+        let Some(shadow) = refine(&pseudo, map) else {
+            self.status = "refinement did not converge, try less noise or a shorter orbit".into();
+            self.shadow.clear();
+            return;
+        };
+        let distance = pseudo
+            .iter()
+            .zip(shadow.iter())
+            .map(|(a, b)| a.distance(*b))
+            .fold(0.0, f32::max);
+        let mut naive = vec![start];
+        for _ in 1..n {
+            naive.push(map(*naive.last().unwrap()));
+        }
+        let divergence = naive
+            .iter()
+            .zip(pseudo.iter())
+            .map(|(a, b)| a.distance(*b))
+            .fold(0.0, f32::max);
+        self.status = format!(
+            "shadow within {distance:.3} (start moved {:.3}); exact orbit from the same start \
+             strays {divergence:.1}",
+            shadow[0].distance(pseudo[0])
+        );
+
+        let dense = |orbit: &[macroquad::math::Vec3], kicked: bool| {
+            let mut points = vec![orbit[0]];
+            for (i, p) in orbit.iter().enumerate().skip(1) {
+                let mut piece = segment(orbit[i - 1]);
+                if kicked {
+                    *piece.last_mut().unwrap() = *p;
+                }
+                points.extend(piece);
+            }
+            points
+        };
+        self.pseudo = dense(&pseudo, true);
+        self.naive = dense(&naive, false);
+        self.shadow = dense(&shadow, false);
+    }
+
+    pub fn draw(&self, transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        for (orbit, color) in [
+            (&self.naive, macroquad::color::GRAY),
+            (&self.pseudo, macroquad::color::RED),
+            (&self.shadow, macroquad::color::GREEN),
+        ] {
+            for (a, b) in orbit.iter().zip(orbit.iter().skip(1)) {
+                macroquad::models::draw_line_3d(transform(*a), transform(*b), color);
+            }
+        }
+    }
+}
+
+/// Newton iteration for x[n+1] = map(x[n]) taking the smallest correction to `orbit` each time.
+/// The normal equations of the underdetermined linearized system are block tridiagonal.
+fn refine(
+    orbit: &[macroquad::math::Vec3],
+    map: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+) -> Option<Vec<macroquad::math::Vec3>> {
+    let mut x = orbit.to_vec();
+    let m = x.len().checked_sub(1)?;
+    let jacobian = |p: macroquad::math::Vec3| {
+        let h = 1e-2;
+        let column = |e: macroquad::math::Vec3| (map(p + e * h) - map(p - e * h)) / (2.0 * h);
+        macroquad::math::Mat3::from_cols(
+            column(macroquad::math::Vec3::X),
+            column(macroquad::math::Vec3::Y),
+            column(macroquad::math::Vec3::Z),
+        )
+    };
+    let identity = macroquad::math::Mat3::IDENTITY;
+    for _ in 0..ITERATIONS {
+        let residuals = (0..m).map(|n| map(x[n]) - x[n + 1]).collect::<Vec<_>>();
+        let worst = residuals.iter().map(|r| r.length()).fold(0.0, f32::max);
+        if !worst.is_finite() {
+            return None;
+        }
+        if worst < 1e-4 {
+            return Some(x);
+        }
+        let j = x[..m].iter().map(|p| jacobian(*p)).collect::<Vec<_>>();
+
+        let mut upper = Vec::with_capacity(m);
+        let mut rhs = Vec::with_capacity(m);
+        for n in 0..m {
+            let diagonal = j[n] * j[n].transpose() + identity;
+            let b = -residuals[n];
+            let (diagonal, b) = if n == 0 {
+                (diagonal, b)
+            } else {
+                let lower = -j[n];
+                (diagonal - lower * upper[n - 1], b - lower * rhs[n - 1])
+            };
+            if diagonal.determinant().abs() < 1e-12 {
+                return None;
+            }
+            let inverse = diagonal.inverse();
+            upper.push(if n + 1 < m {
+                inverse * -j[n + 1].transpose()
+            } else {
+                macroquad::math::Mat3::ZERO
+            });
+            rhs.push(inverse * b);
+        }
+        let mut w = rhs;
+        for n in (0..m.saturating_sub(1)).rev() {
+            w[n] = w[n] - upper[n] * w[n + 1];
+        }
+        for n in 0..=m {
+            let mut correction = macroquad::math::Vec3::ZERO;
+            if n < m {
+                correction += j[n].transpose() * w[n];
+            }
+            if n > 0 {
+                correction -= w[n - 1];
+            }
+            x[n] += correction;
+        }
+    }
+    None
+}
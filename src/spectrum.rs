@@ -0,0 +1,162 @@
+const REFRESH_FRAMES: usize = 10;
+
+pub const SIZES: [usize; 4] = [256, 1024, 4096, 16384];
+pub const SIZE_NAMES: [&str; 4] = ["256", "1024", "4096", "16384"];
+
+/// Hann-windowed power spectrum of one coordinate over the most recent points of the first
+/// trail, resampled to even time steps.
+pub struct Spectrum {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub axis: usize,
+    pub size: usize,
+    power: Vec<f32>,
+    nyquist: f32,
+    frames: usize,
+}
+
+impl Spectrum {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "power spectrum",
+                macroquad::math::Rect::new(520.0, 630.0, 240.0, 160.0),
+            ),
+            axis: 2,
+            size: 1,
+            power: Vec::new(),
+            nyquist: 0.0,
+            frames: 0,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        points: &std::collections::VecDeque<macroquad::math::DVec3>,
+        times: &std::collections::VecDeque<f64>,
+    ) {
+        self.frames += 1;
+        if self.frames < REFRESH_FRAMES && !self.power.is_empty() {
+            return;
+        }
+        self.frames = 0;
+        let len = points.len().min(times.len());
+        let n = SIZES[self.size].min(len.next_power_of_two() / 2);
+        if n < 16 {
+            self.power.clear();
+            return;
+        }
+        let first = len - n;
+        let (t0, t1) = (times[first], times[len - 1]);
+        let spacing = (t1 - t0) / (n - 1) as f64;
+        if spacing <= 0.0 {
+            self.power.clear();
+            return;
+        }
+        let mut j = first;
+        let samples = (0..n)
+            .map(|i| {
+                let t = t0 + i as f64 * spacing;
+                while j + 2 < len && times[j + 1] <= t {
+                    j += 1;
+                }
+                let span = (times[j + 1] - times[j]).max(f64::EPSILON);
+                let u = ((t - times[j]) / span).clamp(0.0, 1.0);
+                points[j][self.axis] + (points[j + 1][self.axis] - points[j][self.axis]) * u
+            })
+            .collect::<Vec<_>>();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let mut re = samples
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let hann = 0.5 - 0.5 * (std::f64::consts::TAU * i as f64 / (n - 1) as f64).cos();
+                (x - mean) * hann
+            })
+            .collect::<Vec<_>>();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+        self.power = (1..n / 2)
+            .map(|k| (re[k] * re[k] + im[k] * im[k]).max(1e-30).log10() as f32)
+            .collect();
+        self.nyquist = (0.5 / spacing) as f32;
+    }
+
+    pub fn draw(&self) {
+        let panel = &self.panel;
+        panel.draw_frame("frequency", "log P");
+        let Some(first) = self.power.first() else {
+            return;
+        };
+        let (lo, hi) = self
+            .power
+            .iter()
+            .fold((*first, *first), |(lo, hi), p| (lo.min(*p), hi.max(*p)));
+        let points = self
+            .power
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                panel.to_screen(macroquad::math::vec2(
+                    i as f32 / (self.power.len() - 1).max(1) as f32,
+                    crate::panel::unlerp((lo, hi.max(lo + 1e-6)), *p),
+                ))
+            })
+            .collect::<Vec<_>>();
+        for (a, b) in points.iter().zip(points.iter().skip(1)) {
+            macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, macroquad::color::YELLOW);
+        }
+        let peak = self
+            .power
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(i, _)| i + 1);
+        let frequency = peak as f32 / (self.power.len() + 1) as f32 * self.nyquist;
+        macroquad::text::draw_text(
+            &format!(
+                "{} up to {:.1}, peak at {frequency:.3}",
+                crate::poincare::AXES[self.axis],
+                self.nyquist
+            ),
+            panel.rect.x + 6.0,
+            panel.rect.y + 16.0,
+            16.0,
+            macroquad::color::WHITE,
+        );
+    }
+}
+
+/// In-place iterative radix-2 FFT; the length must be a power of two.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut length = 2;
+    while length <= n {
+        let angle = -std::f64::consts::TAU / length as f64;
+        for start in (0..n).step_by(length) {
+            for k in 0..length / 2 {
+                let (wr, wi) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                let (a, b) = (start + k, start + k + length / 2);
+                let (tr, ti) = (re[b] * wr - im[b] * wi, re[b] * wi + im[b] * wr);
+                (re[b], im[b]) = (re[a] - tr, im[a] - ti);
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        length <<= 1;
+    }
+}
@@ -0,0 +1,1810 @@
+use crate::{
+    analysis, assimilation, bifurcation, bloom, bookmarks, butterfly, camera, camera_path,
+    comparison, config, console, correlation, coupling, curve, delay, density, diagnostics,
+    dissipation, ensemble, explain, explorer, exposure, fractional, ftle, ftle_field, fxaa, http,
+    image_file, integrator, labels, lighting, lyapunov, manifold, palette, periodic, poincare,
+    profile, profiler, recurrence, render, return_map, rng, script_system, shadowing, spectrum,
+    stereo, sync, system, tangent, theme, time_series, trajectory, volume, zero_one,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum NormalizationMode {
+    Off,
+    Auto,
+    Manual,
+}
+
+pub struct Normalization {
+    pub mode: NormalizationMode,
+    pub center: macroquad::math::Vec3,
+    pub scale: f32,
+}
+
+impl Normalization {
+    const EXTENT: f32 = 30.0;
+    const SMOOTHING: f32 = 0.05;
+
+    pub fn new() -> Self {
+        Self {
+            mode: NormalizationMode::Off,
+            center: macroquad::math::Vec3::ZERO,
+            scale: 1.0,
+        }
+    }
+
+    pub fn update(&mut self, mut points: impl Iterator<Item = macroquad::math::Vec3>) {
+        match self.mode {
+            NormalizationMode::Off => {
+                self.center = macroquad::math::Vec3::ZERO;
+                self.scale = 1.0;
+            }
+            NormalizationMode::Manual => {}
+            NormalizationMode::Auto => {
+                let Some(first) = points.next() else {
+                    return;
+                };
+                let (min, max) =
+                    points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+                let half_extent = ((max - min) / 2.0).max_element().max(1e-3);
+                let center = (min + max) / 2.0;
+                let scale = Self::EXTENT / half_extent;
+                self.center = self.center.lerp(center, Self::SMOOTHING);
+                self.scale += (scale - self.scale) * Self::SMOOTHING;
+            }
+        }
+    }
+
+    pub fn apply(&self, p: macroquad::math::Vec3) -> macroquad::math::Vec3 {
+        (p - self.center) * self.scale
+    }
+
+    pub fn invert(&self, p: macroquad::math::Vec3) -> macroquad::math::Vec3 {
+        p / self.scale + self.center
+    }
+}
+
+pub const BLOWUP_BOUND: f32 = 1e4;
+/// `speed` counts steps per frame at this frame time; other rates scale it so the simulation
+/// advances the same amount per real second.
+pub const REFERENCE_FRAME_TIME: f32 = 1.0 / 60.0;
+/// Longer frames, such as after a hitch, are not caught up beyond this.
+const MAX_FRAME_TIME: f32 = 0.1;
+
+pub struct Blowup {
+    pub trajectory: String,
+    pub time: f32,
+    pub params: Vec<f32>,
+    pub dt: f32,
+    pub integrator: integrator::Integrator,
+}
+
+/// What happens to a trail that leaves the `BLOWUP_BOUND` ball or turns non-finite.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlowupPolicy {
+    Pause,
+    Restart,
+    Clamp,
+}
+
+impl BlowupPolicy {
+    pub const ALL: [BlowupPolicy; 3] = [
+        BlowupPolicy::Pause,
+        BlowupPolicy::Restart,
+        BlowupPolicy::Clamp,
+    ];
+    pub const NAMES: [&'static str; 3] = ["pause and report", "restart trail", "clamp to bound"];
+}
+
+const NOTICE_SECONDS: f64 = 3.0;
+
+/// Where a ctrl-click in the 3D view places a new trajectory along the mouse ray.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpawnTarget {
+    ViewPlane,
+    Section,
+    Trail,
+}
+
+impl SpawnTarget {
+    pub const ALL: [SpawnTarget; 3] = [
+        SpawnTarget::ViewPlane,
+        SpawnTarget::Section,
+        SpawnTarget::Trail,
+    ];
+    pub const NAMES: [&'static str; 3] = [
+        "plane through camera target",
+        "poincare section plane",
+        "nearest point on trail 1",
+    ];
+}
+
+pub const CAMERA_PATH: &str = "camera_path.txt";
+
+/// Starting text of the custom system editor, in the `.sys` file format.
+const CUSTOM_SYSTEM: &str = "name = custom
+param sigma = 10 [0, 20]
+param rho = 28 [0, 100]
+param beta = 2.667 [0, 10]
+start = 0.1, 0, 0
+distance = 100
+dx = sigma*(y - x)
+dy = x*(rho - z) - y
+dz = x*y - beta*z
+";
+
+pub struct State {
+    pub system: system::System,
+    pub params: Vec<f32>,
+    pub profiles: std::collections::HashMap<system::System, profile::Profile>,
+    pub dt: f32,
+    pub integrator: integrator::Integrator,
+    pub log_tolerance: f32,
+    pub reversed: bool,
+    pub tail: f32,
+    pub fractional_memory: f32,
+    pub speed: f32,
+    /// Intensity of additive white noise, applied with Euler-Maruyama sqrt(h) scaling.
+    pub noise: f32,
+    pub step_accumulator: f32,
+    pub time: f32,
+    pub paused: bool,
+    pub run_for: f32,
+    pub rng: rng::Rng,
+    pub seed_text: String,
+    pub run_remaining: Option<f32>,
+    pub time_compression: f32,
+    pub start: macroquad::math::Vec3,
+    pub trajectories: Vec<trajectory::Trajectory>,
+    pub show_legend: bool,
+    pub spawn_target: SpawnTarget,
+    pub colormap: palette::Colormap,
+    pub trail_coloring: trajectory::Coloring,
+    pub ftle_window: f32,
+    pub projection: usize,
+    pub ftle_scale: f32,
+    pub subdivisions: f32,
+    pub point_cloud: bool,
+    pub point_size: f32,
+    pub line_width: f32,
+    /// Points drawn per trail before older parts are thinned out.
+    pub tail_detail: f32,
+    pub themes: Vec<theme::Theme>,
+    pub theme: usize,
+    pub show_labels: bool,
+    pub show_axes: bool,
+    pub show_shadows: bool,
+    pub show_equilibria: bool,
+    pub manifolds: manifold::Manifolds,
+    pub equilibria: Vec<analysis::FixedPoint>,
+    equilibria_of: Option<(system::System, Vec<f32>)>,
+    pub annotations: Vec<labels::Label>,
+    pub annotation_text: String,
+    pub custom_system: String,
+    pub custom_error: String,
+    pub normalization: Normalization,
+    pub parameter_plane: explorer::ParameterPlane,
+    pub bifurcation: bifurcation::Bifurcation,
+    pub param_morph: Option<(usize, f32)>,
+    pub return_map: return_map::ReturnMap,
+    pub poincare: poincare::Poincare,
+    pub recurrence: recurrence::Recurrence,
+    pub time_series: time_series::TimeSeries,
+    pub spectrum: spectrum::Spectrum,
+    pub correlation: correlation::CorrelationDimension,
+    pub density: density::Density,
+    pub volume: volume::Volume,
+    pub volume_monitor: dissipation::VolumeMonitor,
+    pub diagnostics: diagnostics::Diagnostics,
+    pub last_params: Vec<f32>,
+    pub blowup: Option<Blowup>,
+    pub blowup_policy: BlowupPolicy,
+    /// A short message and the time it was raised, shown at the bottom of the screen.
+    pub notice: Option<(String, f64)>,
+    pub camera_path: camera_path::CameraPath,
+    pub key_spacing: f32,
+    pub render_settings: render::Settings,
+    pub render: Option<render::OfflineRender>,
+    pub exit_after_render: bool,
+    pub resets: usize,
+    pub sync: Option<sync::Session>,
+    pub sync_address: String,
+    pub http: Option<http::Server>,
+    pub console: console::Console,
+    /// A text field has taken keyboard input since the last click, so keys go to it rather
+    /// than to the shortcuts.
+    pub typing: bool,
+    /// Camera follow settings as last written to the config file, and newer ones with the time
+    /// they last changed, so a dragged slider is stored once it settles.
+    pub stored_follow: (bool, f32),
+    pub pending_follow: Option<((bool, f32), f64)>,
+    pub script_watcher: script_system::Watcher,
+    pub profiler: profiler::Profiler,
+    pub ensemble: ensemble::Ensemble,
+    pub assimilation: assimilation::Assimilation,
+    pub comparison: comparison::Comparison,
+    pub shadowing: shadowing::Shadowing,
+    pub periodic: periodic::Library,
+    pub explain: explain::Explain,
+    pub ellipsoid: tangent::Ellipsoid,
+    pub zero_one: zero_one::ZeroOne,
+    pub lyapunov: lyapunov::Live,
+    pub coupling: coupling::Coupling,
+    pub butterfly: butterfly::Butterfly,
+    pub ftle_field: ftle_field::FtleField,
+    pub lighting: lighting::Lighting,
+    pub bloom: bloom::Bloom,
+    pub exposure: exposure::Exposure,
+    pub fxaa: fxaa::Fxaa,
+    pub bookmarks: bookmarks::Bookmarks,
+    pub anaglyph: stereo::Anaglyph,
+    pub alpha_curve: curve::Curve,
+    pub curve_editor: curve::CurveEditor,
+}
+
+impl State {
+    pub fn new(config: &config::Config) -> Self {
+        let system = system::System::Lorenz;
+        let start = system.start();
+        let tail = 10.0;
+        let rng = config
+            .get("seed")
+            .map(rng::Rng::new)
+            .unwrap_or_else(rng::Rng::from_time);
+        let themes = theme::load();
+        let theme = config
+            .get::<String>("theme")
+            .and_then(|name| themes.iter().position(|t| t.name == name))
+            .unwrap_or(0);
+        Self {
+            system,
+            params: system.defaults(),
+            profiles: std::collections::HashMap::new(),
+            dt: 0.005,
+            integrator: integrator::Integrator::Euler,
+            log_tolerance: -4.0,
+            reversed: false,
+            tail,
+            fractional_memory: 1_000.0,
+            speed: 10.0,
+            noise: 0.0,
+            step_accumulator: 0.0,
+            time: 0.0,
+            paused: false,
+            run_for: 10.0,
+            seed_text: rng.seed().to_string(),
+            rng,
+            run_remaining: None,
+            time_compression: 0.0,
+            start,
+            trajectories: vec![trajectory::Trajectory::new(
+                "trajectory 1".to_owned(),
+                start,
+                palette::categorical(0),
+                tail,
+            )],
+            show_legend: true,
+            spawn_target: SpawnTarget::ViewPlane,
+            colormap: themes[theme].colormap,
+            trail_coloring: trajectory::Coloring::Speed,
+            ftle_window: 1.0,
+            projection: 0,
+            ftle_scale: 5.0,
+            subdivisions: 1.0,
+            point_cloud: false,
+            point_size: 3.0,
+            line_width: 1.0,
+            tail_detail: 20_000.0,
+            themes,
+            theme,
+            show_labels: true,
+            show_axes: false,
+            show_shadows: false,
+            show_equilibria: false,
+            manifolds: manifold::Manifolds::new(),
+            equilibria: Vec::new(),
+            equilibria_of: None,
+            annotations: Vec::new(),
+            annotation_text: String::new(),
+            custom_system: CUSTOM_SYSTEM.to_owned(),
+            custom_error: String::new(),
+            normalization: Normalization::new(),
+            parameter_plane: explorer::ParameterPlane::new(),
+            bifurcation: bifurcation::Bifurcation::new(),
+            param_morph: None,
+            return_map: return_map::ReturnMap::new(),
+            poincare: poincare::Poincare::new(),
+            recurrence: recurrence::Recurrence::new(),
+            time_series: time_series::TimeSeries::new(),
+            spectrum: spectrum::Spectrum::new(),
+            correlation: correlation::CorrelationDimension::new(),
+            density: density::Density::new(),
+            volume: volume::Volume::new(),
+            volume_monitor: dissipation::VolumeMonitor::new(),
+            diagnostics: diagnostics::Diagnostics::new(),
+            last_params: Vec::new(),
+            blowup: None,
+            blowup_policy: BlowupPolicy::Pause,
+            notice: None,
+            camera_path: camera_path::CameraPath::new(),
+            key_spacing: 2.0,
+            render_settings: render::Settings::new(config),
+            render: None,
+            exit_after_render: false,
+            resets: 0,
+            sync: None,
+            sync_address: sync::DEFAULT_ADDRESS.to_owned(),
+            http: config.get::<String>("http").and_then(|address| {
+                match http::Server::bind(&address) {
+                    Ok(server) => Some(server),
+                    Err(err) => {
+                        eprintln!("http: {err}");
+                        None
+                    }
+                }
+            }),
+            console: console::Console::new(),
+            typing: false,
+            stored_follow: (false, 0.5),
+            pending_follow: None,
+            script_watcher: script_system::Watcher::new(),
+            profiler: profiler::Profiler::new(),
+            ensemble: ensemble::Ensemble::new(),
+            assimilation: assimilation::Assimilation::new(),
+            comparison: comparison::Comparison::new(),
+            shadowing: shadowing::Shadowing::new(),
+            periodic: periodic::Library::new(),
+            explain: explain::Explain::new(),
+            ellipsoid: tangent::Ellipsoid::new(),
+            zero_one: zero_one::ZeroOne::new(),
+            lyapunov: lyapunov::Live::new(),
+            coupling: coupling::Coupling::new(),
+            butterfly: butterfly::Butterfly::new(),
+            ftle_field: ftle_field::FtleField::new(),
+            lighting: lighting::Lighting::new(),
+            bloom: bloom::Bloom::new(),
+            exposure: exposure::Exposure::new(),
+            fxaa: fxaa::Fxaa::new(),
+            bookmarks: bookmarks::Bookmarks::new(config),
+            anaglyph: stereo::Anaglyph::new(),
+            alpha_curve: curve::Curve::new(curve::Preset::Linear),
+            curve_editor: curve::CurveEditor::new(),
+        }
+    }
+
+    pub fn add_trajectory(&mut self) {
+        let n = self.trajectories.len();
+        let offset = n as f32 * 0.01;
+        self.trajectories.push(trajectory::Trajectory::new(
+            format!("trajectory {}", n + 1),
+            self.start + macroquad::math::vec3(offset, 0.0, 0.0),
+            palette::categorical(n),
+            self.tail,
+        ));
+    }
+
+    /// Restarts the first two trajectories far apart with the coupling switched on, so the
+    /// distance plot shows them lock together.
+    pub fn start_synchronization_demo(&mut self) {
+        while self.trajectories.len() < 2 {
+            self.add_trajectory();
+        }
+        let r = self.system.camera_distance() / 10.0;
+        self.trajectories[1].start = self.start + macroquad::math::vec3(r, -r, r);
+        self.coupling.enabled = true;
+        self.reset_position();
+    }
+
+    /// Adds a trajectory where the ray from the mouse meets the spawn target, in the normalized
+    /// space the trails are drawn in.
+    pub fn spawn_at_ray(
+        &mut self,
+        origin: macroquad::math::Vec3,
+        direction: macroquad::math::Vec3,
+        camera_target: macroquad::math::Vec3,
+    ) {
+        let origin = self.normalization.invert(origin);
+        let along_plane = |normal: macroquad::math::Vec3, point: macroquad::math::Vec3| {
+            let denominator = direction.dot(normal);
+            let t = (point - origin).dot(normal) / denominator;
+            (denominator.abs() > 1e-6 && t > 0.0).then(|| origin + direction * t)
+        };
+        let found = match self.spawn_target {
+            SpawnTarget::ViewPlane => {
+                let target = self.normalization.invert(camera_target);
+                along_plane(direction, target)
+            }
+            SpawnTarget::Section => {
+                let mut normal = macroquad::math::Vec3::ZERO;
+                normal[self.poincare.axis] = 1.0;
+                along_plane(normal, normal * self.poincare.offset)
+            }
+            SpawnTarget::Trail => self.trajectories.first().and_then(|trail| {
+                trail
+                    .points
+                    .iter()
+                    .map(|p| p.as_vec3())
+                    .filter(|p| (*p - origin).dot(direction) > 0.0)
+                    .min_by(|a, b| {
+                        let distance = |p: macroquad::math::Vec3| (p - origin).cross(direction);
+                        distance(*a)
+                            .length_squared()
+                            .total_cmp(&distance(*b).length_squared())
+                    })
+            }),
+        };
+        if let Some(start) = found {
+            self.add_trajectory();
+            if let Some(trajectory) = self.trajectories.last_mut() {
+                trajectory.start = start;
+                trajectory.reset();
+            }
+        }
+    }
+
+    /// Restarts the first two trajectories `epsilon` apart in contrasting colors.
+    pub fn start_butterfly_demo(&mut self) {
+        while self.trajectories.len() < 2 {
+            self.add_trajectory();
+        }
+        let epsilon = self.butterfly.epsilon();
+        for (i, trajectory) in self.trajectories.iter_mut().take(2).enumerate() {
+            trajectory.start = self.start + macroquad::math::vec3(i as f32 * epsilon, 0.0, 0.0);
+            trajectory.color = palette::categorical(i);
+            trajectory.solid_color = true;
+            trajectory.visible = true;
+        }
+        self.coupling.enabled = false;
+        self.butterfly.enabled = true;
+        self.reset_position();
+    }
+
+    pub fn switch_system(&mut self, system: system::System, camera: &mut camera::OrbitCamera) {
+        if system == self.system {
+            return;
+        }
+        let current = profile::Profile {
+            params: std::mem::take(&mut self.params),
+            start: self.start,
+            colormap: self.colormap,
+            camera: camera.clone(),
+        };
+        self.profiles.insert(self.system, current);
+        let profile = self
+            .profiles
+            .remove(&system)
+            .unwrap_or_else(|| profile::Profile::default_for(system, self.colormap));
+        self.system = system;
+        self.parameter_plane.switch_system(system);
+        self.bifurcation.switch_system(system);
+        self.param_morph = None;
+        self.params = profile.params;
+        self.colormap = profile.colormap;
+        let previous = std::mem::replace(camera, profile.camera);
+        camera.keep_controls(&previous);
+        self.set_start(profile.start);
+    }
+
+    pub fn set_start(&mut self, start: macroquad::math::Vec3) {
+        self.start = start;
+        for (i, trajectory) in self.trajectories.iter_mut().enumerate() {
+            trajectory.start = self.start + macroquad::math::vec3(i as f32 * 0.01, 0.0, 0.0);
+        }
+        self.reset_position();
+    }
+
+    pub fn random_start(&mut self) {
+        let start = self.system.start();
+        let r = self.system.camera_distance() / 5.0;
+        self.set_start(
+            start
+                + macroquad::math::vec3(
+                    self.rng.range(-r, r),
+                    self.rng.range(-r, r),
+                    self.rng.range(-r, r),
+                ),
+        );
+    }
+
+    pub fn randomize_params(&mut self) {
+        for (value, param) in self.params.iter_mut().zip(self.system.params()) {
+            *value = (param.default * self.rng.range(0.5, 1.5)).clamp(param.min, param.max);
+        }
+    }
+
+    pub fn start_render(&mut self) {
+        if self.camera_path.keyframes.is_empty() {
+            eprintln!("render: camera path has no keyframes");
+            return;
+        }
+        match render::OfflineRender::new(self.render_settings.clone(), self.camera_path.duration())
+        {
+            Ok(render) => {
+                self.reset_position();
+                self.paused = false;
+                self.render = Some(render);
+            }
+            Err(err) => eprintln!("render: {err}"),
+        }
+    }
+
+    pub fn render_frame(
+        &mut self,
+        render: &mut render::OfflineRender,
+        camera: &mut camera::OrbitCamera,
+    ) -> bool {
+        self.camera_path.sample(render.time(), camera);
+        self.step(1.0 / render.settings.fps);
+        self.render_to_texture(camera, &render.target);
+        let done = render.finish_frame();
+
+        let scale = (macroquad::window::screen_width() / render.settings.width)
+            .min(macroquad::window::screen_height() / render.settings.height);
+        let size = macroquad::math::vec2(render.settings.width, render.settings.height) * scale;
+        macroquad::texture::draw_texture_ex(
+            &render.target.texture,
+            (macroquad::window::screen_width() - size.x) / 2.0,
+            (macroquad::window::screen_height() - size.y) / 2.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(size),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        done
+    }
+
+    /// Renders the scene into `target`, leaving the default camera active.
+    pub fn render_to_texture(
+        &self,
+        camera: &camera::OrbitCamera,
+        target: &macroquad::texture::RenderTarget,
+    ) {
+        let size = target.texture.size();
+        let camera_3d = macroquad::camera::Camera3D {
+            aspect: Some(size.x / size.y),
+            render_target: Some(target.clone()),
+            ..camera.get_camera()
+        };
+        macroquad::camera::set_camera(&camera_3d);
+        macroquad::window::clear_background(self.theme().background);
+        self.draw(&camera_3d);
+        macroquad::camera::set_default_camera();
+    }
+
+    /// Renders the scene into a new RGBA image with rows ordered top to bottom.
+    pub fn render_to_image(
+        &self,
+        camera: &camera::OrbitCamera,
+        width: u32,
+        height: u32,
+    ) -> macroquad::texture::Image {
+        let target = macroquad::texture::render_target_ex(
+            width,
+            height,
+            macroquad::texture::RenderTargetParams {
+                sample_count: self.render_settings.samples,
+                depth: true,
+            },
+        );
+        self.render_to_texture(camera, &target);
+        let mut image = target.texture.get_texture_data();
+        let row = width as usize * 4;
+        image.bytes = image.bytes.chunks(row).rev().flatten().copied().collect();
+        image
+    }
+
+    /// Searches the stored trail of the first trajectory for periodic orbits at once instead of
+    /// waiting for the live search to come across them.
+    pub fn scan_for_orbits(&mut self) {
+        if self.system.fractional_order(&self.params).is_some()
+            || self.system.discrete()
+            || self.system.delay(&self.params).is_some()
+            || self.system.dimension() != 3
+            || self.reversed
+        {
+            self.notice = Some((
+                "orbit scan needs a forward running 3D flow".to_owned(),
+                macroquad::time::get_time(),
+            ));
+            return;
+        }
+        let Some(trajectory) = self.trajectories.first() else {
+            return;
+        };
+        let trail = trajectory
+            .points
+            .iter()
+            .map(|p| p.as_vec3())
+            .collect::<Vec<_>>();
+        let found = self.periodic.scan(
+            &trail,
+            self.dt,
+            self.system,
+            &self.params,
+            self.integrator,
+            self.dt,
+        );
+        self.notice = Some((
+            format!("found {found} new periodic orbits"),
+            macroquad::time::get_time(),
+        ));
+    }
+
+    /// Restarts the first trajectory exactly on a point of a stored periodic orbit.
+    pub fn start_on_orbit(&mut self, i: usize) {
+        let Some(point) = self.periodic.orbits.get(i).map(|o| o.points[0]) else {
+            return;
+        };
+        if let Some(trajectory) = self.trajectories.first_mut() {
+            trajectory.start = point;
+            trajectory.reset();
+        }
+        self.periodic.clear_history();
+    }
+
+    pub fn reset_position(&mut self) {
+        for trajectory in self.trajectories.iter_mut() {
+            trajectory.reset();
+        }
+        self.return_map.clear();
+        self.poincare.clear();
+        self.density.clear();
+        self.volume.clear();
+        self.periodic.clear_history();
+        self.ellipsoid.reset();
+        self.zero_one.clear();
+        self.lyapunov.clear();
+        self.coupling.clear();
+        self.butterfly
+            .restart(self.system.camera_distance() / 100.0);
+        self.volume_monitor.reset();
+        self.diagnostics.reset();
+        if let Some(truth) = self.trajectories.first() {
+            self.assimilation.restart(truth.start, &self.rng);
+            self.comparison.restart(truth.start);
+        }
+        self.blowup = None;
+        self.time = 0.0;
+        self.resets += 1;
+    }
+
+    /// Drops what was accumulated in the old direction of time: FTLE windows, fractional
+    /// memory, the slow-motion look-ahead and the observers along the first trail. The trails
+    /// themselves are kept, so the head turns back over its own tail.
+    pub fn reverse_changed(&mut self) {
+        for trajectory in self.trajectories.iter_mut() {
+            trajectory.ftle = None;
+            trajectory.memory = None;
+            trajectory.next = None;
+            trajectory.step_size = 0.0;
+        }
+        self.return_map.clear();
+        self.poincare.clear();
+        self.periodic.clear_history();
+        self.ellipsoid.reset();
+        self.zero_one.clear();
+        self.lyapunov.clear();
+        self.volume_monitor.reset();
+        if let Some(truth) = self.trajectories.first() {
+            self.comparison.restart(truth.head());
+        }
+    }
+
+    pub fn reload_scripts(&mut self) {
+        for (i, previous) in self.script_watcher.poll() {
+            self.script_changed(i, previous);
+        }
+    }
+
+    pub fn define_custom_system(&mut self, camera: &mut camera::OrbitCamera) {
+        let previous = match self.system {
+            system::System::Script(i) => Some((i, self.system.params())),
+            _ => None,
+        };
+        match script_system::define(&self.custom_system) {
+            Ok(i) => {
+                self.custom_error.clear();
+                if let Some((active, params)) = previous
+                    && active == i
+                {
+                    self.script_changed(i, params);
+                } else {
+                    self.profiles.remove(&system::System::Script(i));
+                    self.switch_system(system::System::Script(i), camera);
+                }
+            }
+            Err(err) => self.custom_error = err,
+        }
+    }
+
+    fn script_changed(&mut self, i: usize, previous: &[system::Param]) {
+        let system = system::System::Script(i);
+        let remap = |values: &[f32]| {
+            system
+                .params()
+                .iter()
+                .map(|param| {
+                    previous
+                        .iter()
+                        .zip(values)
+                        .find(|(p, _)| p.name == param.name)
+                        .map_or(param.default, |(_, v)| *v)
+                })
+                .collect::<Vec<_>>()
+        };
+        if self.system == system {
+            self.params = remap(&self.params);
+            self.parameter_plane.switch_system(system);
+            self.bifurcation.switch_system(system);
+            self.param_morph = None;
+        } else if let Some(profile) = self.profiles.get_mut(&system) {
+            profile.params = remap(&profile.params);
+        }
+    }
+
+    pub fn run_script(
+        &mut self,
+        text: &str,
+        camera: &mut camera::OrbitCamera,
+        depth: usize,
+    ) -> Result<(), String> {
+        for command in console::parse(text)? {
+            self.execute(command, camera, depth)?;
+        }
+        Ok(())
+    }
+
+    pub fn execute(
+        &mut self,
+        command: console::Command,
+        camera: &mut camera::OrbitCamera,
+        depth: usize,
+    ) -> Result<(), String> {
+        match command {
+            console::Command::Set(name, value) => {
+                let i = self
+                    .system
+                    .params()
+                    .iter()
+                    .position(|p| p.name == name)
+                    .ok_or(format!("unknown parameter '{name}'"))?;
+                self.params[i] = value;
+            }
+            console::Command::Reset => self.reset_position(),
+            console::Command::Pause => self.paused = true,
+            console::Command::Resume => self.paused = false,
+            console::Command::System(name) => {
+                let system = system::System::all()
+                    .into_iter()
+                    .find(|s| s.name() == name)
+                    .ok_or(format!("unknown system '{name}'"))?;
+                self.switch_system(system, camera);
+            }
+            console::Command::View(yaw, pitch) => (camera.yaw, camera.pitch) = (yaw, pitch),
+            console::Command::Distance(distance) => camera.distance = distance.clamp(1.0, 200.0),
+            console::Command::Speed(speed) => self.speed = speed.clamp(0.1, 20.0),
+            console::Command::Seed(seed) => {
+                self.rng.reseed(seed);
+                self.seed_text = seed.to_string();
+            }
+            console::Command::Run(path) => {
+                if depth >= 8 {
+                    return Err(format!("{path}: scripts nested too deeply"));
+                }
+                let text =
+                    std::fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+                self.run_script(&text, camera, depth + 1)?;
+            }
+            console::Command::ExportAnalysis => self.export_analysis()?,
+            console::Command::Help => self.console.print(console::HELP.to_owned()),
+        }
+        Ok(())
+    }
+
+    pub fn serve_requests(&mut self) {
+        let Some(server) = &self.http else {
+            return;
+        };
+        for request in server.poll() {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/state") => {
+                    let body = self.state_json();
+                    request.respond("200 OK", "application/json", body.as_bytes());
+                }
+                ("GET", "/screenshot.png") => {
+                    let image = macroquad::texture::get_screen_data();
+                    match image_file::encode_png(&image) {
+                        Ok(png) => request.respond("200 OK", "image/png", &png),
+                        Err(err) => request.text("500 Internal Server Error", &format!("{err}\n")),
+                    }
+                }
+                ("POST", "/reset") => {
+                    self.reset_position();
+                    request.text("200 OK", "ok\n");
+                }
+                ("POST", "/params") => {
+                    let mut params = self.params.clone();
+                    let mut error = None;
+                    for (name, value) in request.form() {
+                        let index = self.system.params().iter().position(|p| p.name == name);
+                        match (index, value.parse::<f32>()) {
+                            (Some(i), Ok(value)) => params[i] = value,
+                            _ => error = Some(format!("invalid parameter '{name}={value}'\n")),
+                        }
+                    }
+                    match error {
+                        Some(error) => request.text("400 Bad Request", &error),
+                        None => {
+                            self.params = params;
+                            request.text("200 OK", "ok\n");
+                        }
+                    }
+                }
+                _ => request.text("404 Not Found", "not found\n"),
+            }
+        }
+    }
+
+    pub fn export_analysis(&self) -> Result<(), String> {
+        let trail = self
+            .trajectories
+            .first()
+            .map(|t| t.points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let report =
+            analysis::Report::new(self.system, &self.params, self.integrator, self.dt, &trail);
+        std::fs::write(analysis::ANALYSIS_PATH, report.json())
+            .map_err(|err| format!("{}: {err}", analysis::ANALYSIS_PATH))
+    }
+
+    pub fn state_json(&self) -> String {
+        let params = self
+            .system
+            .params()
+            .iter()
+            .zip(self.params.iter())
+            .map(|(param, value)| format!("\"{}\": {value}", param.name))
+            .collect::<Vec<_>>();
+        let head = self
+            .trajectories
+            .first()
+            .map_or(macroquad::math::Vec3::ZERO, |t| t.head());
+        format!(
+            "{{\"system\": \"{}\", \"time\": {}, \"paused\": {}, \"params\": {{{}}}, \"head\": [{}, {}, {}]}}\n",
+            self.system.name(),
+            self.time,
+            self.paused,
+            params.join(", "),
+            head.x,
+            head.y,
+            head.z
+        )
+    }
+
+    pub fn synchronize(&mut self, camera: &mut camera::OrbitCamera) {
+        let snapshot = sync::Snapshot {
+            system: self.system.name().to_owned(),
+            params: self.params.clone(),
+            resets: self.resets,
+            paused: self.paused,
+        };
+        let Some(session) = &mut self.sync else {
+            return;
+        };
+        session.broadcast(&snapshot);
+        let Some(messages) = session.receive() else {
+            eprintln!("sync: disconnected from host");
+            self.sync = None;
+            return;
+        };
+        for message in messages {
+            match message {
+                sync::Message::System(name) => {
+                    match system::System::all().into_iter().find(|s| s.name() == name) {
+                        Some(system) => self.switch_system(system, camera),
+                        None => eprintln!("sync: host system '{name}' is not available here"),
+                    }
+                }
+                sync::Message::Params(params) => {
+                    if params.len() == self.params.len() {
+                        self.params = params;
+                    }
+                }
+                sync::Message::Reset => self.reset_position(),
+                sync::Message::Paused(paused) => self.paused = paused,
+            }
+        }
+    }
+
+    pub fn step(&mut self, frame_time: f32) {
+        let _scope = profiler::scope("simulation");
+        if let Some((i, target)) = self.param_morph {
+            self.params[i] += (target - self.params[i]) * 0.1;
+            if (target - self.params[i]).abs() < 1e-3 {
+                self.params[i] = target;
+                self.param_morph = None;
+            }
+        }
+        let fractional_order = self.system.fractional_order(&self.params);
+        let discrete = self.system.discrete();
+        let delay = self.system.delay(&self.params);
+        let coupled = self.coupling.enabled
+            && self.trajectories.len() >= 2
+            && fractional_order.is_none()
+            && !discrete
+            && delay.is_none()
+            && self.system.dimension() == 3;
+        let adaptive = self.integrator.is_adaptive()
+            && fractional_order.is_none()
+            && !discrete
+            && delay.is_none()
+            && !coupled;
+        let mut steps = 0;
+        let mut budget = 0.0;
+        if !self.paused {
+            let frames = frame_time.clamp(0.0, MAX_FRAME_TIME) / REFERENCE_FRAME_TIME;
+            if adaptive {
+                budget = self.speed * self.dt * frames;
+            } else {
+                self.step_accumulator += self.speed * frames;
+                steps = self.step_accumulator as usize;
+                self.step_accumulator -= steps as f32;
+            }
+        }
+        if let Some(remaining) = self.run_remaining {
+            if adaptive {
+                if remaining <= budget {
+                    budget = remaining.max(0.0);
+                    self.paused = true;
+                    self.run_remaining = None;
+                } else {
+                    self.run_remaining = Some(remaining - budget);
+                }
+            } else {
+                let remaining_steps = (remaining / self.dt).ceil().max(0.0) as usize;
+                if remaining_steps <= steps {
+                    steps = remaining_steps;
+                    self.paused = true;
+                    self.run_remaining = None;
+                } else {
+                    self.run_remaining = Some(remaining - steps as f32 * self.dt);
+                }
+            }
+        }
+        if !adaptive {
+            budget = steps as f32 * self.dt;
+        }
+        if self.params != self.last_params {
+            self.last_params = self.params.clone();
+            self.return_map.clear();
+            self.poincare.clear();
+            self.volume.clear();
+            self.periodic.clear_history();
+            self.periodic.orbits.clear();
+            self.volume_monitor.reset();
+        }
+        if self.show_equilibria || self.show_labels {
+            self.update_equilibria();
+        }
+        if self.show_equilibria && self.manifolds.visible {
+            self.manifolds.update(
+                self.system,
+                &self.params,
+                &self.equilibria,
+                self.integrator,
+                self.dt,
+            );
+        }
+        let (system, params, dt) = (self.system, &self.params, self.dt);
+        let (integrator, reversed) = (self.integrator, self.reversed);
+        let flow = |p: macroquad::math::Vec3| system.derivative(p, params);
+        let advance = |p: &macroquad::math::Vec3| {
+            if discrete {
+                system.iterate(*p, params)
+            } else if reversed {
+                integrator.step(integrator::reversed(flow), *p, dt)
+            } else {
+                integrator.step(flow, *p, dt)
+            }
+        };
+        let flow64 = |p: integrator::Precise| integrator::Precise(system.derivative64(p.0, params));
+        let tolerance = 10.0_f32.powf(self.log_tolerance);
+        let (h_min, h_max) = (dt * 1e-3, dt * 4.0);
+        let memory = self.fractional_memory as usize;
+        let hyper = system.dimension() == 4;
+        let (projection, time) = (self.projection, self.time);
+        let slow_motion = self.speed < 1.0
+            && fractional_order.is_none()
+            && !adaptive
+            && !hyper
+            && !discrete
+            && delay.is_none()
+            && !coupled;
+        let direction = if reversed { -1.0 } else { 1.0 };
+        let (noise, rng) = (self.noise, &self.rng);
+        let kick = |h: f32| {
+            if noise > 0.0 {
+                macroquad::math::vec3(rng.gaussian(), rng.gaussian(), rng.gaussian())
+                    * (noise * h.abs().sqrt())
+            } else {
+                macroquad::math::Vec3::ZERO
+            }
+        };
+        let mut pair = [Vec::new(), Vec::new()];
+        if coupled {
+            let directed = |p: macroquad::math::Vec3| flow(p) * direction;
+            let (mut a, mut b) = (self.trajectories[0].head(), self.trajectories[1].head());
+            for _ in 0..steps {
+                (a, b) = self.coupling.step(directed, a, b, dt);
+                (a, b) = (a + kick(dt), b + kick(dt));
+                pair[0].push(a);
+                pair[1].push(b);
+            }
+        }
+        let extent = system.camera_distance();
+        self.assimilation.model.extent = extent;
+        self.comparison.model.extent = extent;
+        for (i, trajectory) in self.trajectories.iter_mut().enumerate() {
+            trajectory.extent = extent;
+            let (mut taken, mut elapsed) = (0, 0.0);
+            while if adaptive {
+                elapsed < budget - h_min
+            } else {
+                taken < steps
+            } {
+                taken += 1;
+                let mut h = dt;
+                if i == 0
+                    && self.volume_monitor.visible
+                    && fractional_order.is_none()
+                    && !adaptive
+                    && !discrete
+                {
+                    let p = trajectory.head();
+                    let divergence = direction * system.divergence(p, params);
+                    self.volume_monitor.step(p, advance, divergence, dt);
+                }
+                match fractional_order {
+                    Some(order) => {
+                        let head = trajectory.head();
+                        let gl = trajectory.memory.get_or_insert_with(|| {
+                            fractional::GrunwaldLetnikov::new(head, order, memory)
+                        });
+                        gl.configure(order, memory);
+                        let next = if reversed {
+                            gl.step(integrator::reversed(flow), dt)
+                        } else {
+                            gl.step(flow, dt)
+                        };
+                        trajectory.push(next);
+                        self.diagnostics.record(1, 0);
+                    }
+                    None if delay.is_some() => {
+                        let tau = delay.unwrap_or_default();
+                        let start = trajectory.start.x;
+                        let line = trajectory
+                            .delay
+                            .get_or_insert_with(|| delay::DelayLine::new(start, dt));
+                        let f = |x, lagged| system.delayed_derivative(x, lagged, params);
+                        line.step(f, tau, 2.0 * tau, dt, integrator);
+                        line.perturb(kick(dt).x);
+                        let p =
+                            macroquad::math::vec3(line.current(), line.at(tau), line.at(2.0 * tau));
+                        trajectory.push(p);
+                        self.diagnostics.record(integrator.evaluations().min(4), 0);
+                    }
+                    None if coupled && i < 2 => {
+                        trajectory.push(pair[i][taken - 1]);
+                        self.diagnostics.record(4, 0);
+                    }
+                    None if hyper => {
+                        let state = match trajectory.state4 {
+                            Some(state) => state,
+                            None => {
+                                let w = if system.driven() {
+                                    time
+                                } else {
+                                    system.start4().w
+                                };
+                                let start = trajectory.start.extend(w);
+                                let p = system.display_point(start, params, projection);
+                                trajectory.restart4(start, p);
+                                start
+                            }
+                        };
+                        let flow4 = |p| system.derivative4(p, params) * direction;
+                        let next = if adaptive {
+                            let h_try = trajectory
+                                .step_size
+                                .clamp(h_min, h_max)
+                                .min(budget - elapsed);
+                            let step =
+                                integrator.adaptive_step(flow4, state, h_try, tolerance, h_min);
+                            trajectory.step_size = step.next_h.clamp(h_min, h_max);
+                            elapsed += step.h;
+                            h = step.h;
+                            self.diagnostics.record(
+                                integrator.evaluations() * (step.rejected + 1),
+                                step.rejected,
+                            );
+                            step.p
+                        } else {
+                            self.diagnostics.record(integrator.evaluations(), 0);
+                            integrator.step(flow4, state, dt)
+                        };
+                        let next = next + kick(h).extend(0.0);
+                        trajectory.state4 = Some(next);
+                        trajectory.push(system.display_point(next, params, projection));
+                        trajectory.set_head_value(next.w);
+                    }
+                    None if adaptive => {
+                        let h_try = trajectory
+                            .step_size
+                            .clamp(h_min, h_max)
+                            .min(budget - elapsed);
+                        let head = integrator::Precise(trajectory.head64());
+                        let step = if reversed {
+                            integrator.adaptive_step(
+                                integrator::reversed(flow64),
+                                head,
+                                h_try,
+                                tolerance,
+                                h_min,
+                            )
+                        } else {
+                            integrator.adaptive_step(flow64, head, h_try, tolerance, h_min)
+                        };
+                        trajectory.push64(step.p.0 + kick(step.h).as_dvec3());
+                        trajectory.step_size = step.next_h.clamp(h_min, h_max);
+                        elapsed += step.h;
+                        h = step.h;
+                        self.diagnostics.record(
+                            integrator.evaluations() * (step.rejected + 1),
+                            step.rejected,
+                        );
+                        if i == 0 {
+                            self.diagnostics.step_size = step.h;
+                            self.diagnostics.error = Some(step.error);
+                        }
+                    }
+                    None if discrete => {
+                        trajectory.push(advance(&trajectory.head()) + kick(1.0));
+                        self.diagnostics.record(integrator.evaluations(), 0);
+                    }
+                    None => {
+                        let head = integrator::Precise(trajectory.head64());
+                        let next = if reversed {
+                            integrator.step(integrator::reversed(flow64), head, dt)
+                        } else {
+                            integrator.step(flow64, head, dt)
+                        };
+                        trajectory.push64(next.0 + kick(dt).as_dvec3());
+                        self.diagnostics.record(integrator.evaluations(), 0);
+                    }
+                }
+                trajectory.advance_time(h);
+                let head = trajectory.head();
+                if !head.is_finite() || head.length() > BLOWUP_BOUND {
+                    trajectory.points.pop_back();
+                    trajectory.values.pop_back();
+                    trajectory.times.pop_back();
+                    trajectory.memory = None;
+                    let action = match self.blowup_policy {
+                        BlowupPolicy::Pause => {
+                            self.blowup = Some(Blowup {
+                                trajectory: trajectory.name.clone(),
+                                time: self.time,
+                                params: params.clone(),
+                                dt,
+                                integrator,
+                            });
+                            break;
+                        }
+                        BlowupPolicy::Clamp
+                            if head.is_finite()
+                                && trajectory.state4.is_none()
+                                && trajectory.delay.is_none() =>
+                        {
+                            trajectory.push(head.clamp_length_max(BLOWUP_BOUND));
+                            "clamped"
+                        }
+                        _ => {
+                            trajectory.reset();
+                            "restarted"
+                        }
+                    };
+                    let message = format!("'{}' diverged and was {action}", trajectory.name);
+                    self.notice = Some((message, macroquad::time::get_time()));
+                    continue;
+                }
+                if self.trail_coloring == trajectory::Coloring::Ftle
+                    && fractional_order.is_none()
+                    && !hyper
+                {
+                    let jacobian = system.jacobian(head, params) * direction;
+                    let ftle = trajectory.ftle.get_or_insert_with(ftle::Ftle::new);
+                    let value = ftle.step(jacobian, h, self.ftle_window);
+                    trajectory.set_head_value(value);
+                }
+                if i == 0 {
+                    self.return_map.observe(head);
+                    self.poincare.observe(head);
+                    if self.density.visible {
+                        self.density.observe(head);
+                    }
+                    if self.volume.visible {
+                        self.volume.observe(head);
+                    }
+                    if self.zero_one.enabled {
+                        self.zero_one.observe(head, h);
+                    }
+                    if self.lyapunov.enabled
+                        && fractional_order.is_none()
+                        && delay.is_none()
+                        && !hyper
+                        && !discrete
+                    {
+                        let directed = |p: macroquad::math::Vec3| flow(p) * direction;
+                        self.lyapunov
+                            .observe(head, h, |p, h| integrator.step(directed, p, h));
+                    }
+                    if self.ellipsoid.enabled && fractional_order.is_none() {
+                        self.ellipsoid
+                            .step(system.jacobian(head, params) * direction, h);
+                    }
+                    if self.periodic.searching && fractional_order.is_none() && !reversed {
+                        self.periodic
+                            .observe(head, h, system, params, integrator, dt);
+                    }
+                    if self.assimilation.enabled && fractional_order.is_none() {
+                        let directed = |p: macroquad::math::Vec3| flow(p) * direction;
+                        self.assimilation.step(
+                            head,
+                            h,
+                            |p, h| integrator.step(directed, p, h),
+                            &self.rng,
+                        );
+                    }
+                    if self.comparison.enabled
+                        && fractional_order.is_none()
+                        && delay.is_none()
+                        && !hyper
+                        && !discrete
+                        && !coupled
+                    {
+                        let other = self.comparison.integrator;
+                        let directed = |p| flow64(p) * direction;
+                        self.comparison
+                            .step(head, |p| other.step(directed, integrator::Precise(p), h).0);
+                    }
+                }
+            }
+            if fractional_order.is_none() {
+                trajectory.memory = None;
+            }
+            if delay.is_none() {
+                trajectory.delay = None;
+            }
+            if slow_motion {
+                trajectory.look_ahead(advance);
+            } else {
+                trajectory.next = None;
+            }
+        }
+        if coupled && steps > 0 {
+            let distance = self.trajectories[0]
+                .head()
+                .distance(self.trajectories[1].head());
+            self.coupling.record(budget, distance);
+        }
+        if self.butterfly.enabled
+            && budget > 0.0
+            && let [first, second, ..] = self.trajectories.as_slice()
+        {
+            let separation = first.head64().distance(second.head64());
+            self.butterfly.observe(separation, budget);
+        }
+        if self.ensemble.visible {
+            let steps = (budget / dt).round() as usize;
+            if !self.ensemble.step_gpu(self.system, &self.params, dt, steps) {
+                for _ in 0..steps {
+                    self.ensemble.step(|p| advance(p) + kick(dt));
+                }
+            }
+            if steps > 0 {
+                self.ensemble.record(steps as f32 * dt);
+            }
+        }
+        if self.blowup.is_some() {
+            self.paused = true;
+            self.run_remaining = None;
+        }
+        if !adaptive {
+            self.diagnostics.step_size = dt;
+            self.diagnostics.error = None;
+            if self.diagnostics.visible
+                && fractional_order.is_none()
+                && let Some(trajectory) = self.trajectories.first()
+            {
+                let directed = |p: macroquad::math::Vec3| flow(p) * direction;
+                let head = trajectory.head();
+                let full = integrator.step(directed, head, dt);
+                let half = integrator.step(directed, head, dt / 2.0);
+                let half = integrator.step(directed, half, dt / 2.0);
+                self.diagnostics.error = Some((full - half).length());
+            }
+        }
+        self.diagnostics.end_frame(frame_time);
+        self.time += direction * budget;
+        if frame_time > 0.0 {
+            let compression = budget / frame_time;
+            self.time_compression += (compression - self.time_compression) * 0.1;
+        }
+        self.normalization.update(
+            self.trajectories
+                .iter()
+                .filter(|t| t.visible)
+                .flat_map(|t| t.points.iter().map(|p| p.as_vec3())),
+        );
+    }
+
+    pub fn theme(&self) -> &theme::Theme {
+        &self.themes[self.theme]
+    }
+
+    pub fn draw(&self, camera: &macroquad::camera::Camera3D) {
+        let theme = self.theme();
+        let trails = profiler::scope("trails");
+        let mut style = trajectory::Style {
+            scatter: self.point_cloud || self.system.discrete(),
+            point_size: self.point_size,
+            line_width: self.line_width,
+            coloring: self.trail_coloring,
+            ftle_scale: self.ftle_scale,
+            colormap: self.colormap,
+            glow: theme.glow,
+            subdivisions: self.subdivisions as usize,
+            lighting: self.lighting.enabled.then_some(&self.lighting),
+            alpha: &self.alpha_curve,
+            index: 0.0,
+            max_points: self.tail_detail as usize,
+        };
+        let last = self.trajectories.len().saturating_sub(1).max(1) as f32;
+        for (i, trajectory) in self.trajectories.iter().enumerate() {
+            style.index = i as f32 / last;
+            trajectory.draw(camera, &style, self.step_accumulator, |p| {
+                self.normalization.apply(p)
+            });
+        }
+        if self.assimilation.enabled
+            && let Some(truth) = self.trajectories.first()
+        {
+            self.assimilation.draw(camera, &style, truth.head(), |p| {
+                self.normalization.apply(p)
+            });
+        }
+        if self.comparison.enabled
+            && let Some(truth) = self.trajectories.first()
+        {
+            self.comparison.draw(camera, &style, truth.head(), |p| {
+                self.normalization.apply(p)
+            });
+        }
+        self.draw_joints();
+        if self.shadowing.visible {
+            self.shadowing.draw(|p| self.normalization.apply(p));
+        }
+        if self.poincare.in_scene {
+            self.poincare.draw_scene(|p| self.normalization.apply(p));
+        }
+        self.periodic.draw(|p| self.normalization.apply(p));
+        if self.ellipsoid.enabled
+            && let Some(trajectory) = self.trajectories.first()
+        {
+            self.ellipsoid
+                .draw(trajectory.head(), |p| self.normalization.apply(p));
+        }
+        if let Some(head) = self.explained_head() {
+            let derivative = self.system.derivative(head, &self.params);
+            self.explain
+                .draw_3d(camera, head, derivative, |p| self.normalization.apply(p));
+        }
+        if self.ftle_field.visible {
+            self.ftle_field.draw(|p| self.normalization.apply(p));
+        }
+        if self.ensemble.visible {
+            self.ensemble
+                .draw(camera, self.colormap, |p| self.normalization.apply(p));
+        }
+        drop(trails);
+        let _scene = profiler::scope("scene");
+        macroquad::models::draw_grid(
+            theme.grid_slices,
+            theme.grid_spacing,
+            theme.grid,
+            theme.grid,
+        );
+        if self.show_equilibria {
+            self.draw_equilibria(camera);
+            if self.manifolds.visible {
+                self.manifolds.draw(|p| self.normalization.apply(p));
+            }
+        }
+        if self.show_labels {
+            self.draw_labels(camera);
+        }
+        if self.show_axes {
+            self.draw_axes(camera);
+        }
+        if self.show_shadows {
+            self.draw_shadows();
+        }
+        if self.return_map.visible {
+            self.return_map
+                .draw_3d(camera, |p| self.normalization.apply(p));
+        }
+        // Last, so the translucent slices blend over everything else in the scene.
+        if self.volume.visible {
+            self.volume.draw(camera, |p| self.normalization.apply(p));
+        }
+    }
+
+    fn draw_joints(&self) {
+        for trajectory in self.trajectories.iter().filter(|t| t.visible) {
+            let Some(joints) = trajectory
+                .state4
+                .and_then(|p| self.system.joints(p, &self.params))
+            else {
+                continue;
+            };
+            let joints = joints.map(|p| self.normalization.apply(p));
+            for arm in joints.windows(2) {
+                macroquad::models::draw_line_3d(arm[0], arm[1], macroquad::color::LIGHTGRAY);
+            }
+            for (i, joint) in joints.iter().enumerate() {
+                let radius = if i == 0 { 0.15 } else { 0.4 };
+                macroquad::models::draw_sphere(
+                    *joint,
+                    radius * self.normalization.scale,
+                    None,
+                    trajectory.color,
+                );
+            }
+        }
+    }
+
+    /// Swatch, name and initial condition of every visible trajectory, bottom left.
+    fn draw_legend(&self) {
+        let visible = self.trajectories.iter().filter(|t| t.visible);
+        let mut y = macroquad::window::screen_height() - 20.0 * visible.clone().count() as f32;
+        for trajectory in visible {
+            let swatch = if trajectory.solid_color {
+                trajectory.color
+            } else {
+                self.colormap.sample(0.5)
+            };
+            macroquad::shapes::draw_rectangle(10.0, y - 12.0, 14.0, 14.0, swatch);
+            let start = trajectory.start;
+            let mut text = format!(
+                "{}  ({:.2}, {:.2}, {:.2})",
+                trajectory.name, start.x, start.y, start.z
+            );
+            if !trajectory.solid_color {
+                text.push_str("  colored by trail");
+            }
+            macroquad::text::draw_text(&text, 30.0, y, 18.0, macroquad::color::WHITE);
+            y += 20.0;
+        }
+    }
+
+    fn draw_zero_one(&self) {
+        let text = match self.zero_one.k {
+            Some(k) => format!(
+                "0-1 test K = {k:.2} ({})",
+                if k > 0.5 { "chaotic" } else { "regular" }
+            ),
+            None => format!(
+                "0-1 test collecting samples {:.0}%",
+                self.zero_one.progress() * 100.0
+            ),
+        };
+        let width = macroquad::text::measure_text(&text, None, 24, 1.0).width;
+        macroquad::text::draw_text(
+            &text,
+            (macroquad::window::screen_width() - width) / 2.0,
+            30.0,
+            24.0,
+            macroquad::color::WHITE,
+        );
+    }
+
+    fn explained_head(&self) -> Option<macroquad::math::Vec3> {
+        let explaining = self.explain.enabled && (self.paused || self.run_remaining.is_some());
+        explaining
+            .then(|| self.trajectories.first().map(|t| t.head()))
+            .flatten()
+    }
+
+    /// Where the tip of the first visible trajectory is drawn, for the camera to follow.
+    pub fn followed_head(&self) -> Option<macroquad::math::Vec3> {
+        self.trajectories
+            .iter()
+            .find(|t| t.visible)
+            .map(|t| self.normalization.apply(t.head()))
+    }
+
+    pub fn mouse_over_panels(&self, mouse: macroquad::math::Vec2) -> bool {
+        self.parameter_plane.visible && self.parameter_plane.panel.contains(mouse)
+            || self.bifurcation.visible && self.bifurcation.panel.contains(mouse)
+            || self.return_map.visible && self.return_map.panel.contains(mouse)
+            || self.poincare.visible && self.poincare.panel.contains(mouse)
+            || self.recurrence.visible && self.recurrence.panel.contains(mouse)
+            || self.diagnostics.visible && self.diagnostics.panel.contains(mouse)
+            || self.profiler.visible && self.profiler.panel.contains(mouse)
+            || self.curve_editor.visible && self.curve_editor.panel.contains(mouse)
+            || self.ensemble.show_spread && self.ensemble.spread_panel.contains(mouse)
+            || self.coupling.enabled && self.coupling.panel.contains(mouse)
+            || self.time_series.visible && self.time_series.panel.contains(mouse)
+            || self.spectrum.visible && self.spectrum.panel.contains(mouse)
+            || self.correlation.visible && self.correlation.panel.contains(mouse)
+            || self.density.visible && self.density.panel.contains(mouse)
+    }
+
+    pub fn draw_panels(&mut self) {
+        let _scope = profiler::scope("panels");
+        if self.parameter_plane.visible {
+            self.parameter_plane.update_texture(self.colormap);
+            self.parameter_plane.draw(self.system, &mut self.params);
+        }
+        if self.ftle_field.visible {
+            self.ftle_field.update_texture(self.colormap);
+        }
+        if self.bifurcation.visible
+            && let Some(value) = self.bifurcation.draw(&self.params)
+        {
+            self.param_morph = Some((self.bifurcation.param, value));
+        }
+        if self.return_map.visible {
+            self.return_map.draw();
+        }
+        if self.poincare.visible {
+            self.poincare.draw();
+        }
+        self.poincare.record(self.theme().background);
+        if self.recurrence.visible {
+            self.recurrence
+                .update(&self.trajectories[0].points, self.colormap);
+            self.recurrence.draw();
+        }
+        if self.time_series.visible {
+            self.time_series.draw(&self.trajectories[0].points);
+        }
+        if self.spectrum.visible {
+            let first = &self.trajectories[0];
+            self.spectrum.update(&first.points, &first.times);
+            self.spectrum.draw();
+        }
+        if self.correlation.visible {
+            self.correlation.draw();
+        }
+        if self.volume.visible {
+            self.volume
+                .update(&self.trajectories[0].points, self.colormap);
+        }
+        if self.density.visible {
+            self.density
+                .update(&self.trajectories[0].points, self.colormap);
+            self.density.draw();
+        }
+        if self.diagnostics.visible {
+            self.diagnostics.draw();
+        }
+        if self.profiler.visible {
+            self.profiler.draw();
+        }
+        if self.curve_editor.visible {
+            self.curve_editor.draw(&mut self.alpha_curve);
+        }
+        if self.ensemble.show_spread {
+            self.ensemble.draw_spread();
+        }
+        if self.coupling.enabled {
+            self.coupling.draw();
+        }
+        if self.zero_one.enabled {
+            self.draw_zero_one();
+        }
+        if self.butterfly.enabled {
+            self.butterfly.draw();
+        }
+        if self.show_legend && self.trajectories.len() > 1 {
+            self.draw_legend();
+        }
+        if let Some((message, raised)) = &self.notice {
+            if macroquad::time::get_time() - raised < NOTICE_SECONDS {
+                let size = macroquad::text::measure_text(message, None, 20, 1.0);
+                macroquad::text::draw_text(
+                    message,
+                    (macroquad::window::screen_width() - size.width) / 2.0,
+                    macroquad::window::screen_height() - 30.0,
+                    20.0,
+                    macroquad::color::ORANGE,
+                );
+            } else {
+                self.notice = None;
+            }
+        }
+        if let Some(head) = self.explained_head() {
+            let derivative = self.system.derivative(head, &self.params);
+            self.explain
+                .draw_equations(self.system, &self.params, head, derivative);
+        }
+    }
+
+    /// Recomputes the equilibria when the system or its parameters changed, seeding Newton's
+    /// method across the first trail and a box scaled to the system.
+    fn update_equilibria(&mut self) {
+        let key = (self.system, self.params.clone());
+        if self.equilibria_of.as_ref() == Some(&key) {
+            return;
+        }
+        self.equilibria_of = Some(key);
+        self.equilibria.clear();
+        if self.system.dimension() != 3
+            || self.system.discrete()
+            || self.system.delay(&self.params).is_some()
+        {
+            return;
+        }
+        let trail = self
+            .trajectories
+            .first()
+            .map(|t| t.points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let (min, max) = analysis::bounds(&trail);
+        let r = macroquad::math::Vec3::splat(self.system.camera_distance() / 4.0);
+        self.equilibria =
+            analysis::fixed_points(self.system, &self.params, (min.min(-r), max.max(r)));
+    }
+
+    fn draw_equilibria(&self, camera: &macroquad::camera::Camera3D) {
+        for point in self.equilibria.iter() {
+            let unstable = point.unstable_directions();
+            let (kind, color) = match unstable {
+                0 => ("stable", macroquad::color::GREEN),
+                3 => ("repeller", macroquad::color::RED),
+                _ => ("saddle", macroquad::color::ORANGE),
+            };
+            let position = self.normalization.apply(point.position);
+            macroquad::models::draw_sphere(position, 0.5, None, color);
+            let p = point.position;
+            let text = format!("{kind} ({:.1}, {:.1}, {:.1})", p.x, p.y, p.z);
+            let below = position - camera.up * 2.0;
+            labels::draw_label(camera, below, &text, color, 16.0, false);
+        }
+    }
+
+    /// Axes along three edges of the first trail's bounding box, with ticks labelled in the
+    /// system's own coordinates at 1, 2 or 5 times a power of ten.
+    fn draw_axes(&self, camera: &macroquad::camera::Camera3D) {
+        let trail = self
+            .trajectories
+            .first()
+            .map(|t| t.points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let (min, max) = analysis::bounds(&trail);
+        let color = self.theme().grid;
+        let line = |a: macroquad::math::Vec3, b: macroquad::math::Vec3| {
+            let (a, b) = (self.normalization.apply(a), self.normalization.apply(b));
+            macroquad::models::draw_line_3d(a, b, color);
+        };
+        for (axis, name) in poincare::AXES.iter().enumerate() {
+            let range = max[axis] - min[axis];
+            if range <= 0.0 {
+                continue;
+            }
+            let magnitude = 10.0_f32.powf((range / 5.0).log10().floor());
+            let step = [1.0, 2.0, 5.0, 10.0]
+                .into_iter()
+                .map(|m| m * magnitude)
+                .find(|step| range / step <= 6.0)
+                .unwrap();
+            let decimals = (-step.log10().floor()).max(0.0) as usize;
+            // Ticks point away from the box along the next axis.
+            let across = (axis + 1) % 3;
+            let mut tick = macroquad::math::Vec3::ZERO;
+            tick[across] = -0.03 * (max[across] - min[across]).max(range);
+            let mut end = min;
+            end[axis] = max[axis];
+            line(min, end);
+            let mut value = (min[axis] / step).ceil() * step;
+            while value <= max[axis] {
+                let mut p = min;
+                p[axis] = value;
+                line(p, p + tick);
+                let text = format!("{:.*}", decimals, value + 0.0);
+                let position = self.normalization.apply(p + tick * 2.5);
+                labels::draw_label(camera, position, &text, color, 16.0, false);
+                value += step;
+            }
+            let position = self.normalization.apply(end + tick * 4.0);
+            labels::draw_label(camera, position, name, color, 24.0, false);
+        }
+    }
+
+    /// Flattened copies of the visible trails on the three walls at the low end of their
+    /// bounding box, facing x-y, x-z and y-z.
+    fn draw_shadows(&self) {
+        const MAX_SEGMENTS: usize = 4000;
+        let trails = self
+            .trajectories
+            .iter()
+            .filter(|t| t.visible && t.points.len() > 1)
+            .map(|t| t.points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        if trails.is_empty() {
+            return;
+        }
+        let (min, max) = analysis::bounds(&trails.concat());
+        let walls = min - (max - min) * 0.1;
+        let color = self.theme().grid.with_alpha(0.6);
+        for trail in &trails {
+            let step = trail.len().div_ceil(MAX_SEGMENTS).max(1);
+            let points = trail.iter().step_by(step).collect::<Vec<_>>();
+            for axis in 0..3 {
+                let flatten = |p: &macroquad::math::Vec3| {
+                    let mut p = *p;
+                    p[axis] = walls[axis];
+                    self.normalization.apply(p)
+                };
+                for pair in points.windows(2) {
+                    macroquad::models::draw_line_3d(flatten(pair[0]), flatten(pair[1]), color);
+                }
+            }
+        }
+    }
+
+    pub fn draw_labels(&self, camera: &macroquad::camera::Camera3D) {
+        let color = self.theme().grid;
+        // Beyond the attractor along each axis of the system's own coordinates, so the labels
+        // keep their place whatever its size and the normalization.
+        let reach = 0.6 * self.system.camera_distance();
+        for (axis, text) in [
+            (macroquad::math::Vec3::X, "x"),
+            (macroquad::math::Vec3::Y, "y"),
+            (macroquad::math::Vec3::Z, "z"),
+        ] {
+            let position = self.normalization.apply(axis * reach);
+            labels::draw_label(camera, position, text, color, 24.0, true);
+        }
+        // The origin is O and the other equilibria C1, C2, ... from the largest x down, which
+        // makes C1 and C2 the C+ and C- of the Lorenz system.
+        let mut equilibria = self
+            .equilibria
+            .iter()
+            .map(|point| point.position)
+            .collect::<Vec<_>>();
+        equilibria.sort_by(|a, b| b.x.total_cmp(&a.x));
+        let mut index = 0;
+        for position in equilibria {
+            let text = if position.length() < 1e-3 {
+                "O".to_string()
+            } else {
+                index += 1;
+                format!("C{index}")
+            };
+            labels::draw_label(
+                camera,
+                self.normalization.apply(position),
+                &text,
+                macroquad::color::WHITE,
+                20.0,
+                false,
+            );
+        }
+        for annotation in self.annotations.iter() {
+            annotation.draw(camera, |p| self.normalization.apply(p));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::miniquad;
+
+const ANAGLYPH: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform sampler2D Right;
+void main() {
+    vec3 left = texture2D(Texture, uv).rgb;
+    vec3 right = texture2D(Right, uv).rgb;
+    float red = dot(left, vec3(0.299, 0.587, 0.114));
+    gl_FragColor = vec4(red, right.g, right.b, 1.0);
+}
+"#;
+
+struct Eyes {
+    material: macroquad::material::Material,
+    left: macroquad::texture::RenderTarget,
+    right: macroquad::texture::RenderTarget,
+    size: (u32, u32),
+}
+
+/// Red/cyan anaglyph: the scene is rendered for two eyes `separation` camera distances apart,
+/// the left one in shades of red and the right one in cyan.
+pub struct Anaglyph {
+    pub enabled: bool,
+    pub separation: f32,
+    eyes: Option<Eyes>,
+}
+
+impl Anaglyph {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            separation: 0.03,
+            eyes: None,
+        }
+    }
+
+    /// Cameras for the left and right eye, both looking at the target of `camera`.
+    pub fn eye_cameras(
+        &mut self,
+        camera: &crate::camera::OrbitCamera,
+    ) -> Vec<macroquad::camera::Camera3D> {
+        let size = (
+            macroquad::window::screen_width() as u32,
+            macroquad::window::screen_height() as u32,
+        );
+        if self.eyes.as_ref().is_none_or(|e| e.size != size) {
+            self.eyes = eyes(size);
+        }
+        let Some(eyes) = &self.eyes else {
+            return vec![camera.get_camera()];
+        };
+        let camera_3d = camera.get_camera();
+        let forward = camera_3d.target - camera_3d.position;
+        let right = forward.cross(camera_3d.up).normalize_or_zero();
+        let offset = right * forward.length() * self.separation / 2.0;
+        [(&eyes.left, -offset), (&eyes.right, offset)]
+            .into_iter()
+            .map(|(target, offset)| macroquad::camera::Camera3D {
+                position: camera_3d.position + offset,
+                render_target: Some(target.clone()),
+                ..camera.get_camera()
+            })
+            .collect()
+    }
+
+    /// Draws both eyes combined to the screen; expects the default camera.
+    pub fn composite(&self) {
+        let Some(eyes) = &self.eyes else {
+            return;
+        };
+        eyes.material
+            .set_texture("Right", eyes.right.texture.clone());
+        macroquad::material::gl_use_material(&eyes.material);
+        macroquad::texture::draw_texture_ex(
+            &eyes.left.texture,
+            0.0,
+            0.0,
+            macroquad::color::WHITE,
+            macroquad::texture::DrawTextureParams {
+                dest_size: Some(macroquad::math::vec2(
+                    eyes.size.0 as f32,
+                    eyes.size.1 as f32,
+                )),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        macroquad::material::gl_use_default_material();
+    }
+}
+
+fn eyes((width, height): (u32, u32)) -> Option<Eyes> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let material = macroquad::material::load_material(
+        miniquad::ShaderSource::Glsl {
+            vertex: crate::bloom::VERTEX,
+            fragment: ANAGLYPH,
+        },
+        macroquad::material::MaterialParams {
+            textures: vec!["Right".to_owned()],
+            ..Default::default()
+        },
+    )
+    .map_err(|err| eprintln!("material: {err}"))
+    .ok()?;
+    Some(Eyes {
+        material,
+        left: crate::bloom::target(width, height, true),
+        right: crate::bloom::target(width, height, true),
+        size: (width, height),
+    })
+}
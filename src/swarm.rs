@@ -0,0 +1,529 @@
+use macroquad::miniquad;
+
+/// Integration steps taken per texel in one pass; more are split over several passes.
+const MAX_STEPS: usize = 32;
+
+const QUAD_VERTEX: &str = r#"#version 100
+attribute vec2 corner;
+varying highp vec2 uv;
+void main() {
+    gl_Position = vec4(corner, 0.0, 1.0);
+    uv = corner * 0.5 + 0.5;
+}
+"#;
+
+/// Fourth order Runge-Kutta over every texel, `DERIVATIVE` being replaced by the system's
+/// field. A coordinate rests in two half floats, `positions` holding it rounded and `residuals`
+/// what the rounding lost, since one half float alone moves Lorenz points by around 0.02. The
+/// rounded pass writes the new point and the speed per step, for coloring; the residual pass
+/// repeats the same steps and subtracts what the rounded pass stored in `rounded`.
+const STEP_FRAGMENT: &str = r#"#version 100
+precision highp float;
+varying highp vec2 uv;
+uniform sampler2D positions;
+uniform sampler2D residuals;
+uniform sampler2D rounded;
+uniform float params[8];
+uniform float dt;
+uniform float steps;
+uniform float residual;
+vec3 derivative(vec3 p) {
+    return DERIVATIVE;
+}
+void main() {
+    vec4 state = texture2D(positions, uv);
+    vec3 start = state.xyz + texture2D(residuals, uv).xyz;
+    vec3 p = start;
+    for (int i = 0; i < MAX_STEPS; i++) {
+        if (float(i) >= steps) {
+            break;
+        }
+        vec3 k1 = derivative(p);
+        vec3 k2 = derivative(p + 0.5 * dt * k1);
+        vec3 k3 = derivative(p + 0.5 * dt * k2);
+        vec3 k4 = derivative(p + dt * k3);
+        p += dt / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4);
+    }
+    if (!all(lessThan(abs(p), vec3(1e4)))) {
+        p = start;
+    }
+    if (residual > 0.5) {
+        gl_FragColor = vec4(p - texture2D(rounded, uv).xyz, 0.0);
+    } else {
+        gl_FragColor = vec4(p, length(p - start) / max(steps, 1.0));
+    }
+}
+"#;
+
+const SEED_VERTEX: &str = r#"#version 100
+attribute vec2 texel;
+attribute vec3 position;
+varying highp vec3 value;
+varying highp vec2 uv;
+void main() {
+    gl_Position = vec4(texel, 0.0, 1.0);
+    gl_PointSize = 1.0;
+    value = position;
+    uv = texel * 0.5 + 0.5;
+}
+"#;
+
+/// Writes the seeded point, or in the residual pass what storing it in `rounded` lost.
+const SEED_FRAGMENT: &str = r#"#version 100
+precision highp float;
+varying highp vec3 value;
+varying highp vec2 uv;
+uniform sampler2D rounded;
+uniform float residual;
+void main() {
+    gl_FragColor = residual > 0.5 ? vec4(value - texture2D(rounded, uv).xyz, 0.0) : vec4(value, 0.0);
+}
+"#;
+
+/// Like the instanced spheres, but each particle is looked up in the state texture.
+const DRAW_VERTEX: &str = r#"#version 100
+attribute vec2 corner;
+attribute vec2 texel;
+uniform mat4 view_projection;
+uniform vec3 right;
+uniform vec3 up;
+uniform vec3 origin;
+uniform float scale;
+uniform float radius;
+uniform sampler2D positions;
+varying mediump vec2 uv;
+varying lowp float speed;
+void main() {
+    vec4 state = texture2D(positions, texel);
+    vec3 world = origin + state.xyz * scale + (right * corner.x + up * corner.y) * radius;
+    gl_Position = view_projection * vec4(world, 1.0);
+    uv = corner;
+    speed = clamp(state.w / 2.0, 0.0, 1.0);
+}
+"#;
+
+const DRAW_FRAGMENT: &str = r#"#version 100
+precision mediump float;
+varying mediump vec2 uv;
+varying lowp float speed;
+uniform sampler2D colormap;
+void main() {
+    float r2 = dot(uv, uv);
+    if (r2 > 1.0) {
+        discard;
+    }
+    vec3 normal = vec3(uv, sqrt(1.0 - r2));
+    float light = 0.3 + 0.7 * max(dot(normal, normalize(vec3(0.4, 0.6, 0.7))), 0.0);
+    gl_FragColor = vec4(texture2D(colormap, vec2(speed, 0.5)).rgb * light, 1.0);
+}
+"#;
+
+#[repr(C)]
+struct StepUniforms {
+    params: [f32; 8],
+    dt: f32,
+    steps: f32,
+    residual: f32,
+}
+
+#[repr(C)]
+struct SeedUniforms {
+    residual: f32,
+}
+
+#[repr(C)]
+struct DrawUniforms {
+    view_projection: macroquad::math::Mat4,
+    right: [f32; 3],
+    up: [f32; 3],
+    origin: [f32; 3],
+    scale: f32,
+    radius: f32,
+}
+
+/// An ensemble whose members live in two pairs of half float textures, rounded positions and
+/// residuals, and are advanced by a fragment shader, ping-ponging between the pairs, so their
+/// positions never return to the CPU.
+/// Only systems with a `glsl` field can be integrated this way.
+pub struct Swarm {
+    pub system: crate::system::System,
+    count: usize,
+    side: u32,
+    current: usize,
+    states: [miniquad::TextureId; 2],
+    residuals: [miniquad::TextureId; 2],
+    passes: [miniquad::RenderPass; 2],
+    residual_passes: [miniquad::RenderPass; 2],
+    step_pipeline: miniquad::Pipeline,
+    step_bindings: [miniquad::Bindings; 2],
+    residual_bindings: [miniquad::Bindings; 2],
+    seed_pipeline: miniquad::Pipeline,
+    draw_pipeline: miniquad::Pipeline,
+    draw_bindings: miniquad::Bindings,
+    colormap: Option<crate::palette::Colormap>,
+}
+
+impl Swarm {
+    /// State textures for `count` members of `system`; none when the system has no GLSL field
+    /// or the shaders do not compile here.
+    pub fn new(system: crate::system::System, count: usize) -> Option<Self> {
+        let derivative = system.glsl()?;
+        let ctx = unsafe { macroquad::window::get_internal_gl() }.quad_context;
+        let side = (count.max(1) as f32).sqrt().ceil() as u32;
+        let step_fragment = STEP_FRAGMENT
+            .replace("DERIVATIVE", derivative)
+            .replace("MAX_STEPS", &MAX_STEPS.to_string());
+        let step_shader = shader(
+            ctx,
+            QUAD_VERTEX,
+            &step_fragment,
+            &["positions", "residuals", "rounded"],
+            &[
+                miniquad::UniformDesc::new("params", miniquad::UniformType::Float1).array(8),
+                miniquad::UniformDesc::new("dt", miniquad::UniformType::Float1),
+                miniquad::UniformDesc::new("steps", miniquad::UniformType::Float1),
+                miniquad::UniformDesc::new("residual", miniquad::UniformType::Float1),
+            ],
+        )?;
+        let seed_shader = shader(
+            ctx,
+            SEED_VERTEX,
+            SEED_FRAGMENT,
+            &["rounded"],
+            &[miniquad::UniformDesc::new(
+                "residual",
+                miniquad::UniformType::Float1,
+            )],
+        )?;
+        let draw_shader = shader(
+            ctx,
+            DRAW_VERTEX,
+            DRAW_FRAGMENT,
+            &["positions", "colormap"],
+            &[
+                miniquad::UniformDesc::new("view_projection", miniquad::UniformType::Mat4),
+                miniquad::UniformDesc::new("right", miniquad::UniformType::Float3),
+                miniquad::UniformDesc::new("up", miniquad::UniformType::Float3),
+                miniquad::UniformDesc::new("origin", miniquad::UniformType::Float3),
+                miniquad::UniformDesc::new("scale", miniquad::UniformType::Float1),
+                miniquad::UniformDesc::new("radius", miniquad::UniformType::Float1),
+            ],
+        )?;
+
+        let [states, residuals] = [0, 1].map(|_| {
+            [0, 1].map(|_| {
+                ctx.new_render_texture(miniquad::TextureParams {
+                    width: side,
+                    height: side,
+                    format: miniquad::TextureFormat::RGBA16F,
+                    min_filter: miniquad::FilterMode::Nearest,
+                    mag_filter: miniquad::FilterMode::Nearest,
+                    ..Default::default()
+                })
+            })
+        });
+        let passes = states.map(|texture| ctx.new_render_pass(texture, None));
+        let residual_passes = residuals.map(|texture| ctx.new_render_pass(texture, None));
+        let colormap = ctx.new_texture_from_rgba8(256, 1, &[0; 256 * 4]);
+
+        let corners: [[f32; 2]; 4] = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+        let quad_indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+        let corner_buffer = ctx.new_buffer(
+            miniquad::BufferType::VertexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&corners),
+        );
+        let quad_index_buffer = ctx.new_buffer(
+            miniquad::BufferType::IndexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&quad_indices),
+        );
+        let texels = (0..count)
+            .map(|i| texel(i, side).map(|c| c * 0.5 + 0.5))
+            .collect::<Vec<_>>();
+        let texel_buffer = ctx.new_buffer(
+            miniquad::BufferType::VertexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&texels),
+        );
+
+        let step_pipeline = ctx.new_pipeline(
+            &[miniquad::BufferLayout::default()],
+            &[miniquad::VertexAttribute::new(
+                "corner",
+                miniquad::VertexFormat::Float2,
+            )],
+            step_shader,
+            miniquad::PipelineParams::default(),
+        );
+        // The rounded pass has nothing to subtract, so its `rounded` slot holds a texture it
+        // does not render to.
+        let step_bindings = [0, 1].map(|i| miniquad::Bindings {
+            vertex_buffers: vec![corner_buffer],
+            index_buffer: quad_index_buffer,
+            images: vec![states[i], residuals[i], residuals[1 - i]],
+        });
+        let residual_bindings = [0, 1].map(|i| miniquad::Bindings {
+            vertex_buffers: vec![corner_buffer],
+            index_buffer: quad_index_buffer,
+            images: vec![states[i], residuals[i], states[1 - i]],
+        });
+        let seed_pipeline = ctx.new_pipeline(
+            &[miniquad::BufferLayout::default()],
+            &[
+                miniquad::VertexAttribute::new("texel", miniquad::VertexFormat::Float2),
+                miniquad::VertexAttribute::new("position", miniquad::VertexFormat::Float3),
+            ],
+            seed_shader,
+            miniquad::PipelineParams {
+                primitive_type: miniquad::PrimitiveType::Points,
+                ..Default::default()
+            },
+        );
+        let draw_pipeline = ctx.new_pipeline(
+            &[
+                miniquad::BufferLayout::default(),
+                miniquad::BufferLayout {
+                    step_func: miniquad::VertexStep::PerInstance,
+                    ..Default::default()
+                },
+            ],
+            &[
+                miniquad::VertexAttribute::with_buffer("corner", miniquad::VertexFormat::Float2, 0),
+                miniquad::VertexAttribute::with_buffer("texel", miniquad::VertexFormat::Float2, 1),
+            ],
+            draw_shader,
+            miniquad::PipelineParams {
+                depth_test: miniquad::Comparison::LessOrEqual,
+                depth_write: true,
+                ..Default::default()
+            },
+        );
+        let draw_bindings = miniquad::Bindings {
+            vertex_buffers: vec![corner_buffer, texel_buffer],
+            index_buffer: quad_index_buffer,
+            images: vec![states[0], colormap],
+        };
+        Some(Self {
+            system,
+            count,
+            side,
+            current: 0,
+            states,
+            residuals,
+            passes,
+            residual_passes,
+            step_pipeline,
+            step_bindings,
+            residual_bindings,
+            seed_pipeline,
+            draw_pipeline,
+            draw_bindings,
+            colormap: None,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes `points` into the current state textures, one point primitive per member, the
+    /// residuals after the rounded positions they correct.
+    pub fn seed(&mut self, points: &[macroquad::math::Vec3]) {
+        let mut gl = unsafe { macroquad::window::get_internal_gl() };
+        gl.flush();
+        let ctx = gl.quad_context;
+        let vertices = points
+            .iter()
+            .take(self.count)
+            .enumerate()
+            .map(|(i, p)| {
+                let [u, v] = texel(i, self.side);
+                [u, v, p.x, p.y, p.z]
+            })
+            .collect::<Vec<_>>();
+        let indices = (0..vertices.len() as u32).collect::<Vec<_>>();
+        let vertex_buffer = ctx.new_buffer(
+            miniquad::BufferType::VertexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&vertices),
+        );
+        let index_buffer = ctx.new_buffer(
+            miniquad::BufferType::IndexBuffer,
+            miniquad::BufferUsage::Immutable,
+            miniquad::BufferSource::slice(&indices),
+        );
+        let current = self.current;
+        for (pass, rounded, residual) in [
+            (self.passes[current], self.residuals[current], 0.0),
+            (self.residual_passes[current], self.states[current], 1.0),
+        ] {
+            ctx.begin_pass(
+                Some(pass),
+                miniquad::PassAction::clear_color(0.0, 0.0, 0.0, 0.0),
+            );
+            ctx.apply_viewport(0, 0, self.side as i32, self.side as i32);
+            ctx.apply_pipeline(&self.seed_pipeline);
+            ctx.apply_bindings(&miniquad::Bindings {
+                vertex_buffers: vec![vertex_buffer],
+                index_buffer,
+                images: vec![rounded],
+            });
+            ctx.apply_uniforms(miniquad::UniformsSource::table(&SeedUniforms { residual }));
+            ctx.draw(0, indices.len() as i32, 1);
+            ctx.end_render_pass();
+        }
+        ctx.delete_buffer(vertex_buffer);
+        ctx.delete_buffer(index_buffer);
+    }
+
+    /// Advances every member by `steps` steps of `dt`.
+    pub fn step(&mut self, params: &[f32], dt: f32, steps: usize) {
+        let mut gl = unsafe { macroquad::window::get_internal_gl() };
+        gl.flush();
+        let ctx = gl.quad_context;
+        let mut padded = [0.0; 8];
+        for (slot, value) in padded.iter_mut().zip(params) {
+            *slot = *value;
+        }
+        let mut remaining = steps;
+        while remaining > 0 {
+            let batch = remaining.min(MAX_STEPS);
+            remaining -= batch;
+            let next = 1 - self.current;
+            for (pass, bindings, residual) in [
+                (self.passes[next], &self.step_bindings[self.current], 0.0),
+                (
+                    self.residual_passes[next],
+                    &self.residual_bindings[self.current],
+                    1.0,
+                ),
+            ] {
+                ctx.begin_pass(Some(pass), miniquad::PassAction::Nothing);
+                ctx.apply_viewport(0, 0, self.side as i32, self.side as i32);
+                ctx.apply_pipeline(&self.step_pipeline);
+                ctx.apply_bindings(bindings);
+                ctx.apply_uniforms(miniquad::UniformsSource::table(&StepUniforms {
+                    params: padded,
+                    dt,
+                    steps: batch as f32,
+                    residual,
+                }));
+                ctx.draw(0, 6, 1);
+                ctx.end_render_pass();
+            }
+            self.current = next;
+        }
+    }
+
+    /// Draws the members as lit spheres colored by speed; `origin + p * scale` is where a point
+    /// `p` lands in the scene.
+    pub fn draw(
+        &mut self,
+        camera: &macroquad::camera::Camera3D,
+        colormap: crate::palette::Colormap,
+        origin: macroquad::math::Vec3,
+        scale: f32,
+        radius: f32,
+    ) {
+        let mut gl = unsafe { macroquad::window::get_internal_gl() };
+        gl.flush();
+        let ctx = gl.quad_context;
+        if self.colormap != Some(colormap) {
+            let bytes = (0..256)
+                .flat_map(|i| {
+                    let color = colormap.sample(i as f32 / 255.0);
+                    [color.r, color.g, color.b, 1.0].map(|c| (c * 255.0) as u8)
+                })
+                .collect::<Vec<_>>();
+            ctx.texture_update(self.draw_bindings.images[1], &bytes);
+            self.colormap = Some(colormap);
+        }
+        self.draw_bindings.images[0] = self.states[self.current];
+
+        let forward = (camera.target - camera.position).normalize();
+        let right = forward.cross(camera.up).normalize();
+        let uniforms = DrawUniforms {
+            view_projection: macroquad::camera::Camera::matrix(camera),
+            right: right.to_array(),
+            up: right.cross(forward).to_array(),
+            origin: origin.to_array(),
+            scale,
+            radius,
+        };
+        let pass = macroquad::camera::Camera::render_pass(camera);
+        let (width, height) = match &pass {
+            Some(pass) => {
+                let texture = ctx.render_pass_texture(pass.raw_miniquad_id());
+                ctx.texture_size(texture)
+            }
+            None => {
+                let (w, h) = miniquad::window::screen_size();
+                (w as u32, h as u32)
+            }
+        };
+        match &pass {
+            Some(pass) => {
+                ctx.begin_pass(Some(pass.raw_miniquad_id()), miniquad::PassAction::Nothing)
+            }
+            None => ctx.begin_default_pass(miniquad::PassAction::Nothing),
+        }
+        ctx.apply_viewport(0, 0, width as i32, height as i32);
+        ctx.apply_pipeline(&self.draw_pipeline);
+        ctx.apply_bindings(&self.draw_bindings);
+        ctx.apply_uniforms(miniquad::UniformsSource::table(&uniforms));
+        ctx.draw(0, 6, self.count as i32);
+        ctx.end_render_pass();
+    }
+}
+
+impl Drop for Swarm {
+    fn drop(&mut self) {
+        let ctx = unsafe { macroquad::window::get_internal_gl() }.quad_context;
+        for pass in self.passes.into_iter().chain(self.residual_passes) {
+            ctx.delete_render_pass(pass);
+        }
+        for texture in self
+            .states
+            .into_iter()
+            .chain(self.residuals)
+            .chain([self.draw_bindings.images[1]])
+        {
+            ctx.delete_texture(texture);
+        }
+        for buffer in &self.draw_bindings.vertex_buffers {
+            ctx.delete_buffer(*buffer);
+        }
+        ctx.delete_buffer(self.draw_bindings.index_buffer);
+        for pipeline in [self.step_pipeline, self.seed_pipeline, self.draw_pipeline] {
+            ctx.delete_pipeline(pipeline);
+        }
+    }
+}
+
+/// Clip space center of the texel holding member `i`.
+fn texel(i: usize, side: u32) -> [f32; 2] {
+    let side = side as usize;
+    let (x, y) = (i % side, i / side);
+    [x, y].map(|c| (c as f32 + 0.5) / side as f32 * 2.0 - 1.0)
+}
+
+fn shader(
+    ctx: &mut dyn miniquad::RenderingBackend,
+    vertex: &str,
+    fragment: &str,
+    images: &[&str],
+    uniforms: &[miniquad::UniformDesc],
+) -> Option<miniquad::ShaderId> {
+    ctx.new_shader(
+        miniquad::ShaderSource::Glsl { vertex, fragment },
+        miniquad::ShaderMeta {
+            images: images.iter().map(|name| name.to_string()).collect(),
+            uniforms: miniquad::UniformBlockLayout {
+                uniforms: uniforms.to_vec(),
+            },
+        },
+    )
+    .map_err(|err| eprintln!("swarm: {err}"))
+    .ok()
+}
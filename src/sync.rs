@@ -0,0 +1,231 @@
+use std::io::{Read, Write};
+
+pub const DEFAULT_ADDRESS: &str = "0.0.0.0:7878";
+
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    /// By name, since the position in `System::all` depends on each machine's plugins and
+    /// scripts.
+    pub system: String,
+    pub params: Vec<f32>,
+    pub resets: usize,
+    pub paused: bool,
+}
+
+pub enum Message {
+    System(String),
+    Params(Vec<f32>),
+    Reset,
+    Paused(bool),
+}
+
+impl Message {
+    fn encode(&self) -> String {
+        match self {
+            Message::System(system) => format!("system {system}\n"),
+            Message::Params(params) => {
+                let values = params.iter().map(f32::to_string).collect::<Vec<_>>();
+                format!("params {}\n", values.join(" "))
+            }
+            Message::Reset => "reset\n".to_owned(),
+            Message::Paused(paused) => format!("paused {paused}\n"),
+        }
+    }
+
+    fn decode(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        match words.next()? {
+            "system" => {
+                let name = words.collect::<Vec<_>>().join(" ");
+                (!name.is_empty()).then_some(Message::System(name))
+            }
+            "params" => Some(Message::Params(
+                words.map(str::parse).collect::<Result<_, _>>().ok()?,
+            )),
+            "reset" => Some(Message::Reset),
+            "paused" => Some(Message::Paused(words.next()?.parse().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+pub enum Session {
+    Host {
+        listener: std::net::TcpListener,
+        clients: Vec<std::net::TcpStream>,
+        last: Option<Snapshot>,
+    },
+    Client {
+        stream: std::net::TcpStream,
+        buffer: Vec<u8>,
+    },
+}
+
+impl Session {
+    pub fn host(address: &str) -> std::io::Result<Self> {
+        let listener = std::net::TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Session::Host {
+            listener,
+            clients: Vec::new(),
+            last: None,
+        })
+    }
+
+    pub fn connect(address: &str) -> std::io::Result<Self> {
+        let address = std::net::ToSocketAddrs::to_socket_addrs(address)?
+            .next()
+            .ok_or_else(|| std::io::Error::other("address did not resolve"))?;
+        let stream =
+            std::net::TcpStream::connect_timeout(&address, std::time::Duration::from_secs(2))?;
+        stream.set_nonblocking(true)?;
+        Ok(Session::Client {
+            stream,
+            buffer: Vec::new(),
+        })
+    }
+
+    pub fn status(&self) -> String {
+        match self {
+            Session::Host { clients, .. } => format!("hosting, {} clients", clients.len()),
+            Session::Client { .. } => "connected to host".to_owned(),
+        }
+    }
+
+    pub fn broadcast(&mut self, snapshot: &Snapshot) {
+        let Session::Host {
+            listener,
+            clients,
+            last,
+        } = self
+        else {
+            return;
+        };
+        let mut joined = Vec::new();
+        while let Ok((stream, address)) = listener.accept() {
+            eprintln!("sync: client {address} connected");
+            // A client that stops reading must not stall the render loop; it is dropped once
+            // its buffer fills up instead.
+            if let Err(err) = stream.set_nonblocking(true) {
+                eprintln!("sync: {address}: {err}");
+                continue;
+            }
+            let _ = stream.set_nodelay(true);
+            joined.push(stream);
+        }
+
+        let mut messages = Vec::new();
+        let previous = last.as_ref();
+        if previous.is_none_or(|p| p.system != snapshot.system) {
+            messages.push(Message::System(snapshot.system.clone()));
+        }
+        if previous.is_none_or(|p| p.params != snapshot.params) {
+            messages.push(Message::Params(snapshot.params.clone()));
+        }
+        if previous.is_some_and(|p| p.resets != snapshot.resets) {
+            messages.push(Message::Reset);
+        }
+        if previous.is_none_or(|p| p.paused != snapshot.paused) {
+            messages.push(Message::Paused(snapshot.paused));
+        }
+        let update = messages.iter().map(Message::encode).collect::<String>();
+        let full = [
+            Message::System(snapshot.system.clone()),
+            Message::Params(snapshot.params.clone()),
+            Message::Reset,
+            Message::Paused(snapshot.paused),
+        ]
+        .iter()
+        .map(Message::encode)
+        .collect::<String>();
+        *last = Some(snapshot.clone());
+
+        let send = |client: &mut std::net::TcpStream, text: &str| {
+            let result = client.write_all(text.as_bytes());
+            if let Err(err) = &result {
+                let address = client
+                    .peer_addr()
+                    .map(|a| a.to_string())
+                    .unwrap_or_default();
+                eprintln!("sync: dropping client {address}: {err}");
+            }
+            result.is_ok()
+        };
+        clients.retain_mut(|client| update.is_empty() || send(client, &update));
+        joined.retain_mut(|client| send(client, &full));
+        clients.append(&mut joined);
+    }
+
+    pub fn receive(&mut self) -> Option<Vec<Message>> {
+        let Session::Client { stream, buffer } = self else {
+            return Some(Vec::new());
+        };
+        let mut chunk = [0; 4096];
+        loop {
+            match stream.read(&mut chunk) {
+                Ok(0) => return None,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(_) => return None,
+            }
+        }
+        let mut messages = Vec::new();
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line = buffer.drain(..=end).collect::<Vec<_>>();
+            match Message::decode(&String::from_utf8_lossy(&line)) {
+                Some(message) => messages.push(message),
+                None => eprintln!("sync: ignoring '{}'", String::from_utf8_lossy(&line).trim()),
+            }
+        }
+        Some(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(message: Message) -> Message {
+        let line = message.encode();
+        assert!(line.ends_with('\n'));
+        Message::decode(&line).unwrap()
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        assert!(matches!(
+            round_trip(Message::System("double pendulum".to_owned())),
+            Message::System(name) if name == "double pendulum"
+        ));
+        assert!(matches!(
+            round_trip(Message::Params(vec![10.0, 28.0, 2.6666667])),
+            Message::Params(params) if params == [10.0, 28.0, 2.6666667]
+        ));
+        assert!(matches!(
+            round_trip(Message::Params(Vec::new())),
+            Message::Params(params) if params.is_empty()
+        ));
+        assert!(matches!(round_trip(Message::Reset), Message::Reset));
+        assert!(matches!(
+            round_trip(Message::Paused(true)),
+            Message::Paused(true)
+        ));
+    }
+
+    #[test]
+    fn malformed_messages() {
+        for line in [
+            "",
+            "system",
+            "params 1 x 3",
+            "paused maybe",
+            "paused",
+            "teleport 1 2 3",
+        ] {
+            assert!(
+                Message::decode(line).is_none(),
+                "'{line}' should not decode"
+            );
+        }
+    }
+}
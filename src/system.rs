@@ -0,0 +1,1371 @@
+#[derive(Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+const LORENZ_PARAMS: [Param; 3] = [
+    Param {
+        name: "sigma",
+        default: 10.0,
+        min: -20.0,
+        max: 20.0,
+    },
+    Param {
+        name: "beta",
+        default: 8.0 / 3.0,
+        min: -20.0,
+        max: 20.0,
+    },
+    Param {
+        name: "rho",
+        default: 28.0,
+        min: -20.0,
+        max: 40.0,
+    },
+];
+
+const FRACTIONAL_LORENZ_PARAMS: [Param; 4] = [
+    LORENZ_PARAMS[0],
+    LORENZ_PARAMS[1],
+    LORENZ_PARAMS[2],
+    Param {
+        name: "q",
+        default: 0.995,
+        min: 0.8,
+        max: 1.0,
+    },
+];
+
+const ROSSLER_PARAMS: [Param; 3] = [
+    Param {
+        name: "a",
+        default: 0.2,
+        min: -1.0,
+        max: 1.0,
+    },
+    Param {
+        name: "b",
+        default: 0.2,
+        min: -1.0,
+        max: 2.0,
+    },
+    Param {
+        name: "c",
+        default: 5.7,
+        min: -10.0,
+        max: 20.0,
+    },
+];
+
+const CHEN_PARAMS: [Param; 3] = [
+    Param {
+        name: "a",
+        default: 35.0,
+        min: 0.0,
+        max: 50.0,
+    },
+    Param {
+        name: "b",
+        default: 3.0,
+        min: 0.0,
+        max: 10.0,
+    },
+    Param {
+        name: "c",
+        default: 28.0,
+        min: 0.0,
+        max: 40.0,
+    },
+];
+
+const THOMAS_PARAMS: [Param; 1] = [Param {
+    name: "b",
+    default: 0.208186,
+    min: 0.0,
+    max: 1.0,
+}];
+
+const AIZAWA_PARAMS: [Param; 6] = [
+    Param {
+        name: "a",
+        default: 0.95,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "b",
+        default: 0.7,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "c",
+        default: 0.6,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "d",
+        default: 3.5,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "e",
+        default: 0.25,
+        min: 0.0,
+        max: 1.0,
+    },
+    Param {
+        name: "f",
+        default: 0.1,
+        min: 0.0,
+        max: 1.0,
+    },
+];
+
+const HALVORSEN_PARAMS: [Param; 1] = [Param {
+    name: "a",
+    default: 1.89,
+    min: 0.5,
+    max: 3.0,
+}];
+
+const DADRAS_PARAMS: [Param; 5] = [
+    Param {
+        name: "a",
+        default: 3.0,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "b",
+        default: 2.7,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "c",
+        default: 1.7,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "d",
+        default: 2.0,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "e",
+        default: 9.0,
+        min: 0.0,
+        max: 15.0,
+    },
+];
+
+const HYPER_ROSSLER_PARAMS: [Param; 4] = [
+    Param {
+        name: "a",
+        default: 0.25,
+        min: 0.0,
+        max: 1.0,
+    },
+    Param {
+        name: "b",
+        default: 3.0,
+        min: 0.0,
+        max: 5.0,
+    },
+    Param {
+        name: "c",
+        default: 0.5,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "d",
+        default: 0.05,
+        min: -0.5,
+        max: 0.5,
+    },
+];
+
+const HYPER_LORENZ_PARAMS: [Param; 4] = [
+    Param {
+        name: "a",
+        default: 10.0,
+        min: 0.0,
+        max: 20.0,
+    },
+    Param {
+        name: "b",
+        default: 2.667,
+        min: 0.0,
+        max: 10.0,
+    },
+    Param {
+        name: "c",
+        default: 28.0,
+        min: 0.0,
+        max: 50.0,
+    },
+    Param {
+        name: "r",
+        default: -1.0,
+        min: -3.0,
+        max: 1.0,
+    },
+];
+
+const DOUBLE_PENDULUM_PARAMS: [Param; 5] = [
+    Param {
+        name: "l1",
+        default: 10.0,
+        min: 1.0,
+        max: 20.0,
+    },
+    Param {
+        name: "l2",
+        default: 10.0,
+        min: 1.0,
+        max: 20.0,
+    },
+    Param {
+        name: "m1",
+        default: 1.0,
+        min: 0.1,
+        max: 5.0,
+    },
+    Param {
+        name: "m2",
+        default: 1.0,
+        min: 0.1,
+        max: 5.0,
+    },
+    Param {
+        name: "g",
+        default: 9.81,
+        min: 0.0,
+        max: 30.0,
+    },
+];
+
+const DUFFING_PARAMS: [Param; 5] = [
+    Param {
+        name: "delta",
+        default: 0.3,
+        min: 0.0,
+        max: 1.0,
+    },
+    Param {
+        name: "alpha",
+        default: -1.0,
+        min: -2.0,
+        max: 2.0,
+    },
+    Param {
+        name: "beta",
+        default: 1.0,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "amplitude",
+        default: 0.5,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "frequency",
+        default: 1.2,
+        min: 0.1,
+        max: 3.0,
+    },
+];
+
+const FORCED_LORENZ_PARAMS: [Param; 5] = [
+    LORENZ_PARAMS[0],
+    LORENZ_PARAMS[1],
+    LORENZ_PARAMS[2],
+    Param {
+        name: "amplitude",
+        default: 10.0,
+        min: 0.0,
+        max: 50.0,
+    },
+    Param {
+        name: "frequency",
+        default: 1.0,
+        min: 0.0,
+        max: 10.0,
+    },
+];
+
+const HENON_PARAMS: [Param; 2] = [
+    Param {
+        name: "a",
+        default: 1.4,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "b",
+        default: 0.3,
+        min: -1.0,
+        max: 1.0,
+    },
+];
+
+const LOZI_PARAMS: [Param; 2] = [
+    Param {
+        name: "a",
+        default: 1.7,
+        min: 0.0,
+        max: 2.0,
+    },
+    Param {
+        name: "b",
+        default: 0.5,
+        min: -1.0,
+        max: 1.0,
+    },
+];
+
+const IKEDA_PARAMS: [Param; 1] = [Param {
+    name: "u",
+    default: 0.9,
+    min: 0.0,
+    max: 1.0,
+}];
+
+const MACKEY_GLASS_PARAMS: [Param; 4] = [
+    Param {
+        name: "beta",
+        default: 0.2,
+        min: 0.0,
+        max: 1.0,
+    },
+    Param {
+        name: "gamma",
+        default: 0.1,
+        min: 0.0,
+        max: 1.0,
+    },
+    Param {
+        name: "n",
+        default: 10.0,
+        min: 1.0,
+        max: 20.0,
+    },
+    Param {
+        name: "tau",
+        default: 17.0,
+        min: 1.0,
+        max: 50.0,
+    },
+];
+
+/// Which three of the four coordinates of a hyperchaotic system are shown.
+pub const PROJECTIONS: [[usize; 3]; 4] = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+pub const PROJECTION_NAMES: [&str; 4] = ["x y z", "x y w", "x z w", "y z w"];
+
+pub fn project(p: macroquad::math::Vec4, projection: usize) -> macroquad::math::Vec3 {
+    let [a, b, c] = PROJECTIONS[projection];
+    macroquad::math::vec3(p[a], p[b], p[c])
+}
+
+/// An ODE x' = f(x; params) together with what the UI needs to present it. The integrators,
+/// analyses and drawing code only go through this, so a new system is an implementation plus a
+/// `System` variant that resolves to it.
+pub trait DynamicalSystem {
+    fn name(&self) -> &'static str;
+    fn params(&self) -> &[Param];
+    fn start(&self) -> macroquad::math::Vec3;
+    fn camera_distance(&self) -> f32;
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3;
+
+    /// The field in double precision, used to advance the trails; by default `derivative`
+    /// evaluated at the rounded point.
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        self.derivative(p.as_vec3(), params).as_dvec3()
+    }
+
+    /// 4 for hyperchaotic systems, which also implement `derivative4` and `start4`; their
+    /// `derivative` is the slice w = 0 seen by the three-dimensional analyses.
+    fn dimension(&self) -> usize {
+        3
+    }
+
+    fn start4(&self) -> macroquad::math::Vec4 {
+        self.start().extend(0.0)
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        self.derivative(p.truncate(), params).extend(0.0)
+    }
+
+    /// Iterated maps replace integration with `iterate`; their `derivative` is the displacement
+    /// per iteration.
+    fn discrete(&self) -> bool {
+        false
+    }
+
+    fn iterate(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        p + self.derivative(p, params)
+    }
+
+    /// Delay `tau` of a scalar delay differential equation x' = `delayed_derivative`(x, x(t - tau)),
+    /// drawn in the delay embedding (x(t), x(t - tau), x(t - 2 tau)).
+    fn delay(&self, _params: &[f32]) -> Option<f32> {
+        None
+    }
+
+    fn delayed_derivative(&self, _x: f32, _lagged: f32, _params: &[f32]) -> f32 {
+        0.0
+    }
+
+    /// Order of a fractional derivative replacing d/dt, integrated by the memory integrator in
+    /// `fractional` instead of the usual steppers.
+    fn fractional_order(&self, _params: &[f32]) -> Option<f32> {
+        None
+    }
+
+    /// The field as a GLSL expression in `p` and `params[i]`, for systems whose ensembles can
+    /// be integrated on the GPU.
+    fn glsl(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Periodically forced systems carry their own time as w, so `derivative4` sees t = p.w.
+    fn driven(&self) -> bool {
+        false
+    }
+
+    /// Joint positions of a mechanical system, from the fixed pivot to the tip whose path is
+    /// traced instead of a projection of the state.
+    fn joints(
+        &self,
+        _p: macroquad::math::Vec4,
+        _params: &[f32],
+    ) -> Option<[macroquad::math::Vec3; 3]> {
+        None
+    }
+}
+
+pub struct Lorenz;
+
+impl DynamicalSystem for Lorenz {
+    fn name(&self) -> &'static str {
+        "lorenz"
+    }
+
+    fn params(&self) -> &[Param] {
+        &LORENZ_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(0.0, 1.0, 1.05)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        100.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (sigma, beta, rho) = (params[0], params[1], params[2]);
+        macroquad::math::vec3(
+            sigma * (p.y - p.x),
+            p.x * (rho - p.z) - p.y,
+            p.x * p.y - beta * p.z,
+        )
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let (sigma, beta, rho) = (params[0] as f64, params[1] as f64, params[2] as f64);
+        macroquad::math::dvec3(
+            sigma * (p.y - p.x),
+            p.x * (rho - p.z) - p.y,
+            p.x * p.y - beta * p.z,
+        )
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some(
+            "vec3(
+                params[0] * (p.y - p.x),
+                p.x * (params[2] - p.z) - p.y,
+                p.x * p.y - params[1] * p.z
+            )",
+        )
+    }
+}
+
+/// The Lorenz field with an extra order parameter; the memory integrator in `fractional` handles
+/// the rest.
+pub struct FractionalLorenz;
+
+impl DynamicalSystem for FractionalLorenz {
+    fn name(&self) -> &'static str {
+        "fractional lorenz"
+    }
+
+    fn params(&self) -> &[Param] {
+        &FRACTIONAL_LORENZ_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        Lorenz.start()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        Lorenz.camera_distance()
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        Lorenz.derivative(p, params)
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        Lorenz.derivative64(p, params)
+    }
+
+    fn fractional_order(&self, params: &[f32]) -> Option<f32> {
+        Some(params[3])
+    }
+}
+
+pub struct Rossler;
+
+impl DynamicalSystem for Rossler {
+    fn name(&self) -> &'static str {
+        "rossler"
+    }
+
+    fn params(&self) -> &[Param] {
+        &ROSSLER_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(1.0, 1.0, 0.0)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        50.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b, c) = (params[0], params[1], params[2]);
+        macroquad::math::vec3(-p.y - p.z, p.x + a * p.y, b + p.z * (p.x - c))
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let (a, b, c) = (params[0] as f64, params[1] as f64, params[2] as f64);
+        macroquad::math::dvec3(-p.y - p.z, p.x + a * p.y, b + p.z * (p.x - c))
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some("vec3(-p.y - p.z, p.x + params[0] * p.y, params[1] + p.z * (p.x - params[2]))")
+    }
+}
+
+pub struct Chen;
+
+impl DynamicalSystem for Chen {
+    fn name(&self) -> &'static str {
+        "chen"
+    }
+
+    fn params(&self) -> &[Param] {
+        &CHEN_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(-3.0, 2.0, 20.0)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        100.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b, c) = (params[0], params[1], params[2]);
+        macroquad::math::vec3(
+            a * (p.y - p.x),
+            (c - a) * p.x - p.x * p.z + c * p.y,
+            p.x * p.y - b * p.z,
+        )
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let (a, b, c) = (params[0] as f64, params[1] as f64, params[2] as f64);
+        macroquad::math::dvec3(
+            a * (p.y - p.x),
+            (c - a) * p.x - p.x * p.z + c * p.y,
+            p.x * p.y - b * p.z,
+        )
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some(
+            "vec3(
+                params[0] * (p.y - p.x),
+                (params[2] - params[0]) * p.x - p.x * p.z + params[2] * p.y,
+                p.x * p.y - params[1] * p.z
+            )",
+        )
+    }
+}
+
+/// Cyclically symmetric attractor of a particle in a damped periodic lattice.
+pub struct Thomas;
+
+impl DynamicalSystem for Thomas {
+    fn name(&self) -> &'static str {
+        "thomas"
+    }
+
+    fn params(&self) -> &[Param] {
+        &THOMAS_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(1.1, 1.1, -0.01)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        12.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let b = params[0];
+        macroquad::math::vec3(
+            p.y.sin() - b * p.x,
+            p.z.sin() - b * p.y,
+            p.x.sin() - b * p.z,
+        )
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let b = params[0] as f64;
+        macroquad::math::dvec3(
+            p.y.sin() - b * p.x,
+            p.z.sin() - b * p.y,
+            p.x.sin() - b * p.z,
+        )
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some("sin(p.yzx) - params[0] * p")
+    }
+}
+
+pub struct Aizawa;
+
+impl DynamicalSystem for Aizawa {
+    fn name(&self) -> &'static str {
+        "aizawa"
+    }
+
+    fn params(&self) -> &[Param] {
+        &AIZAWA_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(0.1, 0.0, 0.0)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        5.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b, c, d, e, f) = (
+            params[0], params[1], params[2], params[3], params[4], params[5],
+        );
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::vec3(
+            (z - b) * x - d * y,
+            d * x + (z - b) * y,
+            c + a * z - z * z * z / 3.0 - (x * x + y * y) * (1.0 + e * z) + f * z * x * x * x,
+        )
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let [a, b, c, d, e, f] = [0, 1, 2, 3, 4, 5].map(|i| params[i] as f64);
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::dvec3(
+            (z - b) * x - d * y,
+            d * x + (z - b) * y,
+            c + a * z - z * z * z / 3.0 - (x * x + y * y) * (1.0 + e * z) + f * z * x * x * x,
+        )
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some(
+            "vec3(
+                (p.z - params[1]) * p.x - params[3] * p.y,
+                params[3] * p.x + (p.z - params[1]) * p.y,
+                params[2] + params[0] * p.z - p.z * p.z * p.z / 3.0
+                    - (p.x * p.x + p.y * p.y) * (1.0 + params[4] * p.z)
+                    + params[5] * p.z * p.x * p.x * p.x
+            )",
+        )
+    }
+}
+
+pub struct Halvorsen;
+
+impl DynamicalSystem for Halvorsen {
+    fn name(&self) -> &'static str {
+        "halvorsen"
+    }
+
+    fn params(&self) -> &[Param] {
+        &HALVORSEN_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(-1.48, -1.51, 2.04)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        30.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let a = params[0];
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::vec3(
+            -a * x - 4.0 * y - 4.0 * z - y * y,
+            -a * y - 4.0 * z - 4.0 * x - z * z,
+            -a * z - 4.0 * x - 4.0 * y - x * x,
+        )
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let a = params[0] as f64;
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::dvec3(
+            -a * x - 4.0 * y - 4.0 * z - y * y,
+            -a * y - 4.0 * z - 4.0 * x - z * z,
+            -a * z - 4.0 * x - 4.0 * y - x * x,
+        )
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some("-params[0] * p - 4.0 * (p.yzx + p.zxy) - p.yzx * p.yzx")
+    }
+}
+
+pub struct Dadras;
+
+impl DynamicalSystem for Dadras {
+    fn name(&self) -> &'static str {
+        "dadras"
+    }
+
+    fn params(&self) -> &[Param] {
+        &DADRAS_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(1.1, 2.1, -2.0)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        30.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b, c, d, e) = (params[0], params[1], params[2], params[3], params[4]);
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::vec3(y - a * x + b * y * z, c * y - x * z + z, d * x * y - e * z)
+    }
+
+    fn derivative64(&self, p: macroquad::math::DVec3, params: &[f32]) -> macroquad::math::DVec3 {
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|i| params[i] as f64);
+        let (x, y, z) = (p.x, p.y, p.z);
+        macroquad::math::dvec3(y - a * x + b * y * z, c * y - x * z + z, d * x * y - e * z)
+    }
+
+    fn glsl(&self) -> Option<&'static str> {
+        Some(
+            "vec3(
+                p.y - params[0] * p.x + params[1] * p.y * p.z,
+                params[2] * p.y - p.x * p.z + p.z,
+                params[3] * p.x * p.y - params[4] * p.z
+            )",
+        )
+    }
+}
+
+/// Rössler's 1979 hyperchaotic extension with two positive Lyapunov exponents.
+pub struct HyperRossler;
+
+impl DynamicalSystem for HyperRossler {
+    fn name(&self) -> &'static str {
+        "hyperchaotic rossler"
+    }
+
+    fn params(&self) -> &[Param] {
+        &HYPER_ROSSLER_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start4().truncate()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        250.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.derivative4(p.extend(0.0), params).truncate()
+    }
+
+    fn dimension(&self) -> usize {
+        4
+    }
+
+    fn start4(&self) -> macroquad::math::Vec4 {
+        macroquad::math::vec4(-10.0, -6.0, 0.0, 10.0)
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        let (a, b, c, d) = (params[0], params[1], params[2], params[3]);
+        macroquad::math::vec4(
+            -p.y - p.z,
+            p.x + a * p.y + p.w,
+            b + p.x * p.z,
+            -c * p.z + d * p.w,
+        )
+    }
+}
+
+/// Lorenz system with a linear feedback controller w, as in Wang and Wang (2008).
+pub struct HyperLorenz;
+
+impl DynamicalSystem for HyperLorenz {
+    fn name(&self) -> &'static str {
+        "hyperchaotic lorenz"
+    }
+
+    fn params(&self) -> &[Param] {
+        &HYPER_LORENZ_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start4().truncate()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        100.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.derivative4(p.extend(0.0), params).truncate()
+    }
+
+    fn dimension(&self) -> usize {
+        4
+    }
+
+    fn start4(&self) -> macroquad::math::Vec4 {
+        macroquad::math::vec4(1.0, 1.0, 1.0, 1.0)
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        let (a, b, c, r) = (params[0], params[1], params[2], params[3]);
+        macroquad::math::vec4(
+            a * (p.y - p.x) + p.w,
+            c * p.x - p.y - p.x * p.z,
+            p.x * p.y - b * p.z,
+            -p.y * p.z + r * p.w,
+        )
+    }
+}
+
+/// Two point masses on rigid massless arms; the state is (theta1, theta2, omega1, omega2).
+pub struct DoublePendulum;
+
+impl DynamicalSystem for DoublePendulum {
+    fn name(&self) -> &'static str {
+        "double pendulum"
+    }
+
+    fn params(&self) -> &[Param] {
+        &DOUBLE_PENDULUM_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start4().truncate()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        60.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.derivative4(p.extend(0.0), params).truncate()
+    }
+
+    fn dimension(&self) -> usize {
+        4
+    }
+
+    fn start4(&self) -> macroquad::math::Vec4 {
+        macroquad::math::vec4(2.0, 2.5, 0.0, 0.0)
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        let (l1, l2, m1, m2, g) = (params[0], params[1], params[2], params[3], params[4]);
+        let (theta1, theta2, omega1, omega2) = (p.x, p.y, p.z, p.w);
+        let delta = theta1 - theta2;
+        let denominator = 2.0 * m1 + m2 - m2 * (2.0 * delta).cos();
+        let alpha1 = (-g * (2.0 * m1 + m2) * theta1.sin()
+            - m2 * g * (theta1 - 2.0 * theta2).sin()
+            - 2.0 * delta.sin() * m2 * (omega2 * omega2 * l2 + omega1 * omega1 * l1 * delta.cos()))
+            / (l1 * denominator);
+        let alpha2 = 2.0
+            * delta.sin()
+            * (omega1 * omega1 * l1 * (m1 + m2)
+                + g * (m1 + m2) * theta1.cos()
+                + omega2 * omega2 * l2 * m2 * delta.cos())
+            / (l2 * denominator);
+        macroquad::math::vec4(omega1, omega2, alpha1, alpha2)
+    }
+
+    fn joints(
+        &self,
+        p: macroquad::math::Vec4,
+        params: &[f32],
+    ) -> Option<[macroquad::math::Vec3; 3]> {
+        let (l1, l2) = (params[0], params[1]);
+        let elbow = macroquad::math::vec3(p.x.sin(), -p.x.cos(), 0.0) * l1;
+        let tip = elbow + macroquad::math::vec3(p.y.sin(), -p.y.cos(), 0.0) * l2;
+        Some([macroquad::math::Vec3::ZERO, elbow, tip])
+    }
+}
+
+/// Forced Duffing oscillator x'' + delta x' + alpha x + beta x^3 = amplitude cos(frequency t),
+/// drawn as (x, x', cos(frequency t)).
+pub struct Duffing;
+
+impl DynamicalSystem for Duffing {
+    fn name(&self) -> &'static str {
+        "duffing"
+    }
+
+    fn params(&self) -> &[Param] {
+        &DUFFING_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        self.start4().truncate()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        10.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.derivative4(p.extend(0.0), params).truncate()
+    }
+
+    fn dimension(&self) -> usize {
+        4
+    }
+
+    fn driven(&self) -> bool {
+        true
+    }
+
+    fn start4(&self) -> macroquad::math::Vec4 {
+        macroquad::math::vec4(1.0, 0.0, 1.0, 0.0)
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        let (delta, alpha, beta) = (params[0], params[1], params[2]);
+        let (amplitude, frequency) = (params[3], params[4]);
+        let phase = frequency * p.w;
+        macroquad::math::vec4(
+            p.y,
+            amplitude * phase.cos() - delta * p.y - alpha * p.x - beta * p.x * p.x * p.x,
+            -frequency * phase.sin(),
+            1.0,
+        )
+    }
+}
+
+/// Lorenz system with a sinusoidal force amplitude cos(frequency t) added to dx.
+pub struct ForcedLorenz;
+
+impl DynamicalSystem for ForcedLorenz {
+    fn name(&self) -> &'static str {
+        "forced lorenz"
+    }
+
+    fn params(&self) -> &[Param] {
+        &FORCED_LORENZ_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        Lorenz.start()
+    }
+
+    fn camera_distance(&self) -> f32 {
+        Lorenz.camera_distance()
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.derivative4(p.extend(0.0), params).truncate()
+    }
+
+    fn dimension(&self) -> usize {
+        4
+    }
+
+    fn driven(&self) -> bool {
+        true
+    }
+
+    fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        let (amplitude, frequency) = (params[3], params[4]);
+        let force = macroquad::math::Vec3::X * amplitude * (frequency * p.w).cos();
+        (Lorenz.derivative(p.truncate(), params) + force).extend(1.0)
+    }
+}
+
+pub struct Henon;
+
+impl DynamicalSystem for Henon {
+    fn name(&self) -> &'static str {
+        "henon map"
+    }
+
+    fn params(&self) -> &[Param] {
+        &HENON_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::Vec3::ZERO
+    }
+
+    fn camera_distance(&self) -> f32 {
+        4.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.iterate(p, params) - p
+    }
+
+    fn discrete(&self) -> bool {
+        true
+    }
+
+    fn iterate(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b) = (params[0], params[1]);
+        macroquad::math::vec3(1.0 - a * p.x * p.x + p.y, b * p.x, 0.0)
+    }
+}
+
+/// Piecewise linear variant of the Hénon map.
+pub struct Lozi;
+
+impl DynamicalSystem for Lozi {
+    fn name(&self) -> &'static str {
+        "lozi map"
+    }
+
+    fn params(&self) -> &[Param] {
+        &LOZI_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::Vec3::ZERO
+    }
+
+    fn camera_distance(&self) -> f32 {
+        4.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.iterate(p, params) - p
+    }
+
+    fn discrete(&self) -> bool {
+        true
+    }
+
+    fn iterate(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let (a, b) = (params[0], params[1]);
+        macroquad::math::vec3(1.0 - a * p.x.abs() + p.y, b * p.x, 0.0)
+    }
+}
+
+/// Light circulating in a nonlinear optical ring cavity.
+pub struct Ikeda;
+
+impl DynamicalSystem for Ikeda {
+    fn name(&self) -> &'static str {
+        "ikeda map"
+    }
+
+    fn params(&self) -> &[Param] {
+        &IKEDA_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(0.1, 0.1, 0.0)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        4.0
+    }
+
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.iterate(p, params) - p
+    }
+
+    fn discrete(&self) -> bool {
+        true
+    }
+
+    fn iterate(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        let u = params[0];
+        let t = 0.4 - 6.0 / (1.0 + p.x * p.x + p.y * p.y);
+        macroquad::math::vec3(
+            1.0 + u * (p.x * t.cos() - p.y * t.sin()),
+            u * (p.x * t.sin() + p.y * t.cos()),
+            0.0,
+        )
+    }
+}
+
+/// Mackey-Glass model of blood cell production, chaotic for tau above about 16.8.
+pub struct MackeyGlass;
+
+impl DynamicalSystem for MackeyGlass {
+    fn name(&self) -> &'static str {
+        "mackey-glass"
+    }
+
+    fn params(&self) -> &[Param] {
+        &MACKEY_GLASS_PARAMS
+    }
+
+    fn start(&self) -> macroquad::math::Vec3 {
+        macroquad::math::Vec3::splat(1.2)
+    }
+
+    fn camera_distance(&self) -> f32 {
+        4.0
+    }
+
+    /// Along the embedding the first two components are exact; x(t - 3 tau) is not known here.
+    fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        macroquad::math::vec3(
+            self.delayed_derivative(p.x, p.y, params),
+            self.delayed_derivative(p.y, p.z, params),
+            0.0,
+        )
+    }
+
+    fn delay(&self, params: &[f32]) -> Option<f32> {
+        Some(params[3])
+    }
+
+    fn delayed_derivative(&self, x: f32, lagged: f32, params: &[f32]) -> f32 {
+        let (beta, gamma, n) = (params[0], params[1], params[2]);
+        beta * lagged / (1.0 + lagged.abs().powf(n)) - gamma * x
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum System {
+    Lorenz,
+    FractionalLorenz,
+    Rossler,
+    Chen,
+    Thomas,
+    Aizawa,
+    Halvorsen,
+    Dadras,
+    HyperRossler,
+    HyperLorenz,
+    DoublePendulum,
+    Duffing,
+    ForcedLorenz,
+    Henon,
+    Lozi,
+    Ikeda,
+    MackeyGlass,
+    Plugin(usize),
+    Script(usize),
+}
+
+impl System {
+    pub const ALL: [System; 17] = [
+        System::Lorenz,
+        System::FractionalLorenz,
+        System::Rossler,
+        System::Chen,
+        System::Thomas,
+        System::Aizawa,
+        System::Halvorsen,
+        System::Dadras,
+        System::HyperRossler,
+        System::HyperLorenz,
+        System::DoublePendulum,
+        System::Duffing,
+        System::ForcedLorenz,
+        System::Henon,
+        System::Lozi,
+        System::Ikeda,
+        System::MackeyGlass,
+    ];
+
+    pub fn all() -> Vec<System> {
+        Self::ALL
+            .into_iter()
+            .chain((0..crate::plugin::systems().len()).map(System::Plugin))
+            .chain((0..crate::script_system::systems().len()).map(System::Script))
+            .collect()
+    }
+
+    pub fn definition(&self) -> &'static dyn DynamicalSystem {
+        match self {
+            System::Lorenz => &Lorenz,
+            System::FractionalLorenz => &FractionalLorenz,
+            System::Rossler => &Rossler,
+            System::Chen => &Chen,
+            System::Thomas => &Thomas,
+            System::Aizawa => &Aizawa,
+            System::Halvorsen => &Halvorsen,
+            System::Dadras => &Dadras,
+            System::HyperRossler => &HyperRossler,
+            System::HyperLorenz => &HyperLorenz,
+            System::DoublePendulum => &DoublePendulum,
+            System::Duffing => &Duffing,
+            System::ForcedLorenz => &ForcedLorenz,
+            System::Henon => &Henon,
+            System::Lozi => &Lozi,
+            System::Ikeda => &Ikeda,
+            System::MackeyGlass => &MackeyGlass,
+            System::Plugin(i) => &crate::plugin::systems()[*i],
+            System::Script(i) => crate::script_system::get(*i),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.definition().name()
+    }
+
+    pub fn params(&self) -> &'static [Param] {
+        self.definition().params()
+    }
+
+    pub fn defaults(&self) -> Vec<f32> {
+        self.params().iter().map(|p| p.default).collect()
+    }
+
+    pub fn start(&self) -> macroquad::math::Vec3 {
+        self.definition().start()
+    }
+
+    pub fn camera_distance(&self) -> f32 {
+        self.definition().camera_distance()
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.definition().dimension()
+    }
+
+    pub fn start4(&self) -> macroquad::math::Vec4 {
+        self.definition().start4()
+    }
+
+    pub fn derivative4(&self, p: macroquad::math::Vec4, params: &[f32]) -> macroquad::math::Vec4 {
+        self.definition().derivative4(p, params)
+    }
+
+    pub fn discrete(&self) -> bool {
+        self.definition().discrete()
+    }
+
+    pub fn iterate(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.definition().iterate(p, params)
+    }
+
+    pub fn delay(&self, params: &[f32]) -> Option<f32> {
+        self.definition().delay(params)
+    }
+
+    pub fn delayed_derivative(&self, x: f32, lagged: f32, params: &[f32]) -> f32 {
+        self.definition().delayed_derivative(x, lagged, params)
+    }
+
+    pub fn driven(&self) -> bool {
+        self.definition().driven()
+    }
+
+    pub fn glsl(&self) -> Option<&'static str> {
+        self.definition().glsl()
+    }
+
+    pub fn joints(
+        &self,
+        p: macroquad::math::Vec4,
+        params: &[f32],
+    ) -> Option<[macroquad::math::Vec3; 3]> {
+        self.definition().joints(p, params)
+    }
+
+    /// Where a four-dimensional state is drawn: the tip of a mechanism, or else the projection.
+    pub fn display_point(
+        &self,
+        p: macroquad::math::Vec4,
+        params: &[f32],
+        projection: usize,
+    ) -> macroquad::math::Vec3 {
+        self.joints(p, params)
+            .map_or_else(|| project(p, projection), |joints| joints[2])
+    }
+
+    pub fn fractional_order(&self, params: &[f32]) -> Option<f32> {
+        self.definition().fractional_order(params)
+    }
+
+    pub fn derivative(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Vec3 {
+        self.definition().derivative(p, params)
+    }
+
+    pub fn derivative64(
+        &self,
+        p: macroquad::math::DVec3,
+        params: &[f32],
+    ) -> macroquad::math::DVec3 {
+        self.definition().derivative64(p, params)
+    }
+
+    pub fn jacobian(&self, p: macroquad::math::Vec3, params: &[f32]) -> macroquad::math::Mat3 {
+        let h = 1e-3;
+        let column = |e: macroquad::math::Vec3| {
+            (self.derivative(p + e * h, params) - self.derivative(p - e * h, params)) / (2.0 * h)
+        };
+        macroquad::math::Mat3::from_cols(
+            column(macroquad::math::Vec3::X),
+            column(macroquad::math::Vec3::Y),
+            column(macroquad::math::Vec3::Z),
+        )
+    }
+
+    pub fn divergence(&self, p: macroquad::math::Vec3, params: &[f32]) -> f32 {
+        let j = self.jacobian(p, params);
+        j.x_axis.x + j.y_axis.y + j.z_axis.z
+    }
+}
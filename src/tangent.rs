@@ -0,0 +1,89 @@
+const LATITUDES: usize = 10;
+const LONGITUDES: usize = 20;
+
+/// A sphere of infinitesimal perturbations carried along with the head of the first trajectory;
+/// `deformation` maps the initial unit sphere to the current ellipsoid.
+pub struct Ellipsoid {
+    pub enabled: bool,
+    pub radius: f32,
+    pub window: f32,
+    pub normalize: bool,
+    pub deformation: macroquad::math::Mat3,
+    pub age: f32,
+}
+
+impl Ellipsoid {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            radius: 3.0,
+            window: 1.0,
+            normalize: true,
+            deformation: macroquad::math::Mat3::IDENTITY,
+            age: 0.0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.deformation = macroquad::math::Mat3::IDENTITY;
+        self.age = 0.0;
+    }
+
+    pub fn step(&mut self, jacobian: macroquad::math::Mat3, h: f32) {
+        let j = jacobian * h;
+        self.deformation = (macroquad::math::Mat3::IDENTITY + j + j * j * 0.5) * self.deformation;
+        self.age += h;
+        if self.age >= self.window || !self.deformation.is_finite() {
+            self.reset();
+        }
+    }
+
+    /// Semi-axes of the ellipsoid, largest first.
+    pub fn semi_axes(&self) -> [f32; 3] {
+        let gram = self.deformation.transpose() * self.deformation;
+        crate::analysis::eigenvalues(gram).map(|(re, _)| re.max(0.0).sqrt())
+    }
+
+    pub fn draw(
+        &self,
+        head: macroquad::math::Vec3,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        let axes = self.semi_axes();
+        let scale = if self.normalize {
+            self.radius / axes[0].max(1e-6)
+        } else {
+            self.radius
+        };
+        let color = macroquad::color::Color::new(1.0, 0.8, 0.2, 0.8);
+        draw_wireframe(head, self.deformation * scale, color, transform);
+    }
+}
+
+/// Wireframe of the image of the unit sphere under `shape`, centred on `center`.
+pub fn draw_wireframe(
+    center: macroquad::math::Vec3,
+    shape: macroquad::math::Mat3,
+    color: macroquad::color::Color,
+    transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+) {
+    let point = |latitude: usize, longitude: usize| {
+        let theta = std::f32::consts::PI * latitude as f32 / LATITUDES as f32;
+        let phi = std::f32::consts::TAU * longitude as f32 / LONGITUDES as f32;
+        let v = macroquad::math::vec3(
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            theta.cos(),
+        );
+        transform(center + shape * v)
+    };
+    for latitude in 0..=LATITUDES {
+        for longitude in 0..LONGITUDES {
+            let a = point(latitude, longitude);
+            macroquad::models::draw_line_3d(a, point(latitude, longitude + 1), color);
+            if latitude < LATITUDES {
+                macroquad::models::draw_line_3d(a, point(latitude + 1, longitude), color);
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+const BUILTIN: &str = include_str!("themes.ini");
+const USER_THEMES: &str = "themes.ini";
+
+#[derive(Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: macroquad::color::Color,
+    pub grid: macroquad::color::Color,
+    pub grid_slices: u32,
+    pub grid_spacing: f32,
+    pub ui_background: Option<macroquad::color::Color>,
+    pub ui_text: Option<macroquad::color::Color>,
+    pub colormap: crate::palette::Colormap,
+    pub glow: f32,
+}
+
+impl Theme {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            background: macroquad::color::BLACK,
+            grid: macroquad::color::DARKGRAY,
+            grid_slices: 12,
+            grid_spacing: 10.0,
+            ui_background: None,
+            ui_text: None,
+            colormap: crate::palette::Colormap::Rainbow,
+            glow: 0.0,
+        }
+    }
+}
+
+/// Replaces the UI skin with the one of `theme`, or macroquad's default when the theme has no
+/// UI colors.
+pub fn apply_skin(theme: &Theme) {
+    let mut ui = macroquad::ui::root_ui();
+    ui.pop_skin();
+    let (Some(background), Some(text)) = (theme.ui_background, theme.ui_text) else {
+        return;
+    };
+    let mix =
+        |t: f32| macroquad::color::Color::from_vec(background.to_vec().lerp(text.to_vec(), t));
+    let (widget, border) = (mix(0.15), mix(0.4));
+    let framed = |width: u16, height: u16, fill: macroquad::color::Color| {
+        let mut image = macroquad::texture::Image::gen_image_color(width, height, border);
+        for y in 1..height as u32 - 1 {
+            for x in 1..width as u32 - 1 {
+                image.set_pixel(x, y, fill);
+            }
+        }
+        image
+    };
+    let mut combobox = framed(16, 30, widget);
+    for y in 12..18 {
+        let half = 17 - y;
+        for x in 8 - half..=8 + half {
+            combobox.set_pixel(x, y, text);
+        }
+    }
+    let margin = macroquad::math::RectOffset::new(2.0, 2.0, 2.0, 2.0);
+    let frame = macroquad::math::RectOffset::new(1.0, 1.0, 1.0, 1.0);
+    let mut skin = ui.default_skin();
+    skin.window_style = ui
+        .style_builder()
+        .background(framed(3, 3, background))
+        .background_margin(frame)
+        .color_inactive(background.with_alpha(0.5))
+        .text_color(text)
+        .build();
+    skin.window_titlebar_style = ui
+        .style_builder()
+        .color(border)
+        .color_inactive(border.with_alpha(0.5))
+        .text_color(text)
+        .build();
+    skin.label_style = ui
+        .style_builder()
+        .margin(margin)
+        .text_color(text)
+        .color_inactive(text.with_alpha(0.5))
+        .build();
+    skin.button_style = ui
+        .style_builder()
+        .margin(margin)
+        .color(widget)
+        .color_hovered(mix(0.25))
+        .color_clicked(mix(0.35))
+        .text_color(text)
+        .build();
+    skin.combobox_style = ui
+        .style_builder()
+        .background(combobox)
+        .background_margin(macroquad::math::RectOffset::new(1.0, 14.0, 1.0, 1.0))
+        .color_inactive(widget.with_alpha(0.5))
+        .text_color(text)
+        .build();
+    skin.checkbox_style = ui
+        .style_builder()
+        .font_size(16)
+        .color(widget)
+        .color_hovered(mix(0.25))
+        .color_clicked(mix(0.5))
+        .color_selected(mix(0.6))
+        .color_selected_hovered(mix(0.7))
+        .text_color(text)
+        .build();
+    skin.editbox_style = ui
+        .style_builder()
+        .color(widget)
+        .color_selected(mix(0.4))
+        .text_color(text)
+        .build();
+    ui.push_skin(&skin);
+}
+
+pub fn load() -> Vec<Theme> {
+    let mut themes = parse(BUILTIN);
+    if let Ok(text) = std::fs::read_to_string(USER_THEMES) {
+        for theme in parse(&text) {
+            match themes.iter_mut().find(|t| t.name == theme.name) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
+            }
+        }
+    }
+    themes
+}
+
+fn parse(text: &str) -> Vec<Theme> {
+    let mut themes: Vec<Theme> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            themes.push(Theme::new(name.trim().to_owned()));
+            continue;
+        }
+        let (Some(theme), Some((key, value))) = (themes.last_mut(), line.split_once('=')) else {
+            eprintln!("themes: ignoring line '{line}'");
+            continue;
+        };
+        let value = value.trim();
+        let ok = match key.trim() {
+            "background" => parse_color(value).map(|c| theme.background = c),
+            "grid" => parse_color(value).map(|c| theme.grid = c),
+            "grid-slices" => value.parse().ok().map(|n| theme.grid_slices = n),
+            "grid-spacing" => value.parse().ok().map(|s| theme.grid_spacing = s),
+            "ui-background" => parse_color(value).map(|c| theme.ui_background = Some(c)),
+            "ui-text" => parse_color(value).map(|c| theme.ui_text = Some(c)),
+            "colormap" => crate::palette::Colormap::from_key(value).map(|c| theme.colormap = c),
+            "glow" => value.parse().ok().map(|g| theme.glow = g),
+            _ => None,
+        };
+        if ok.is_none() {
+            eprintln!("themes: invalid entry '{line}' in theme '{}'", theme.name);
+        }
+    }
+    themes
+}
+
+pub fn parse_color(value: &str) -> Option<macroquad::color::Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .map(macroquad::color::Color::from_hex)
+}
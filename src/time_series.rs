@@ -0,0 +1,68 @@
+const COLORS: [macroquad::color::Color; 3] = [
+    macroquad::color::RED,
+    macroquad::color::GREEN,
+    macroquad::color::SKYBLUE,
+];
+
+/// Strip chart of the recent points of the first trail, one lane per coordinate, docked to the
+/// bottom right corner.
+pub struct TimeSeries {
+    pub visible: bool,
+    pub panel: crate::panel::Panel,
+    pub window: f32,
+}
+
+impl TimeSeries {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            panel: crate::panel::Panel::new(
+                "x(t), y(t), z(t)",
+                macroquad::math::Rect::new(0.0, 0.0, 480.0, 180.0),
+            ),
+            window: 2_000.0,
+        }
+    }
+
+    pub fn draw(&mut self, points: &std::collections::VecDeque<macroquad::math::DVec3>) {
+        self.panel.rect.x = macroquad::window::screen_width() - self.panel.rect.w - 20.0;
+        self.panel.rect.y = macroquad::window::screen_height() - self.panel.rect.h - 30.0;
+        let panel = &self.panel;
+        panel.draw_frame("steps", "");
+        let n = points.len().min(self.window as usize);
+        if n < 2 {
+            return;
+        }
+        let recent = points.range(points.len() - n..);
+        let lane = 1.0 / 3.0;
+        for (axis, (name, color)) in crate::poincare::AXES.iter().zip(COLORS).enumerate() {
+            let (lo, hi) = recent.clone().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+                (lo.min(p[axis]), hi.max(p[axis]))
+            });
+            let range = (hi - lo).max(1e-6);
+            let bottom = 1.0 - (axis + 1) as f32 * lane;
+            let samples = recent
+                .clone()
+                .enumerate()
+                .map(|(i, p)| {
+                    let v = ((p[axis] - lo) / range) as f32;
+                    panel.to_screen(macroquad::math::vec2(
+                        i as f32 / (n - 1) as f32,
+                        bottom + lane * (0.05 + 0.9 * v),
+                    ))
+                })
+                .collect::<Vec<_>>();
+            for (a, b) in samples.iter().zip(samples.iter().skip(1)) {
+                macroquad::shapes::draw_line(a.x, a.y, b.x, b.y, 1.0, color);
+            }
+            let top = panel.to_screen(macroquad::math::vec2(0.0, bottom + lane));
+            macroquad::text::draw_text(
+                &format!("{name} {lo:.1}..{hi:.1}"),
+                top.x + 4.0,
+                top.y + 14.0,
+                16.0,
+                color,
+            );
+        }
+    }
+}
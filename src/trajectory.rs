@@ -0,0 +1,513 @@
+const MAX_POINTS: usize = 100_000;
+// Kept below macroquad's default per draw call capacity of 10000 vertices and 5000 indices,
+// beyond which a submitted mesh is silently clamped.
+const BATCH_VERTICES: usize = 9_000;
+const BATCH_INDICES: usize = 4_800;
+const TUBE_SIDES: u16 = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Taper {
+    None,
+    Linear,
+    EaseIn,
+    EaseOut,
+    Smooth,
+}
+
+impl Taper {
+    pub const ALL: [Taper; 5] = [
+        Taper::None,
+        Taper::Linear,
+        Taper::EaseIn,
+        Taper::EaseOut,
+        Taper::Smooth,
+    ];
+    pub const NAMES: [&'static str; 5] = ["none", "linear", "ease in", "ease out", "smooth"];
+
+    pub fn factor(&self, t: f32) -> f32 {
+        match self {
+            Taper::None => 1.0,
+            Taper::Linear => t,
+            Taper::EaseIn => t * t,
+            Taper::EaseOut => t.sqrt(),
+            Taper::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Coloring {
+    Speed,
+    Ftle,
+    Fourth,
+    Height,
+    Age,
+    Index,
+}
+
+impl Coloring {
+    pub const ALL: [Coloring; 6] = [
+        Coloring::Speed,
+        Coloring::Ftle,
+        Coloring::Fourth,
+        Coloring::Height,
+        Coloring::Age,
+        Coloring::Index,
+    ];
+    pub const NAMES: [&'static str; 6] = [
+        "speed",
+        "finite-time lyapunov",
+        "fourth coordinate",
+        "z height",
+        "time along trail",
+        "trajectory index",
+    ];
+}
+
+pub struct Style<'a> {
+    /// Draw each point as a dot instead of joining them, for iterated maps and point clouds.
+    pub scatter: bool,
+    /// Dot size in pixels per unit of trail thickness.
+    pub point_size: f32,
+    /// Scales the thickness of every trail, e.g. for high density displays.
+    pub line_width: f32,
+    pub coloring: Coloring,
+    /// FTLE values mapped onto the colormap from -ftle_scale to ftle_scale.
+    pub ftle_scale: f32,
+    pub colormap: crate::palette::Colormap,
+    pub glow: f32,
+    /// Pieces each stored segment is split into along a cubic Hermite curve.
+    pub subdivisions: usize,
+    pub lighting: Option<&'a crate::lighting::Lighting>,
+    pub alpha: &'a crate::curve::Curve,
+    /// Position of the trail being drawn among all trails, from 0 to 1, for `Coloring::Index`.
+    pub index: f32,
+    /// Stored points drawn per trail at most; longer trails keep the newest half of the budget
+    /// at full detail and thin out everything older to every k-th point.
+    pub max_points: usize,
+}
+
+pub struct Trajectory {
+    pub name: String,
+    pub start: macroquad::math::Vec3,
+    /// The trail in double precision, rounded to f32 only for drawing and analysis.
+    pub points: std::collections::VecDeque<macroquad::math::DVec3>,
+    /// Per-point finite-time Lyapunov exponent or fourth coordinate, parallel to `points`.
+    pub values: std::collections::VecDeque<f32>,
+    /// Integration time at each point, parallel to `points`; adaptive steps and merged points
+    /// leave them unevenly spaced.
+    pub times: std::collections::VecDeque<f64>,
+    pub length: f32,
+    pub color: macroquad::color::Color,
+    pub solid_color: bool,
+    pub thickness: f32,
+    pub taper: Taper,
+    pub visible: bool,
+    /// Trail length in multiples of `extent`, the system's camera distance, so one setting
+    /// covers the same share of any attractor.
+    pub tail: f32,
+    pub extent: f32,
+    pub next: Option<macroquad::math::Vec3>,
+    pub memory: Option<crate::fractional::GrunwaldLetnikov>,
+    pub delay: Option<crate::delay::DelayLine>,
+    pub ftle: Option<crate::ftle::Ftle>,
+    pub step_size: f32,
+    /// Full state of a four-dimensional system; `points` holds its projection.
+    pub state4: Option<macroquad::math::Vec4>,
+}
+
+impl Trajectory {
+    pub fn new(
+        name: String,
+        start: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+        tail: f32,
+    ) -> Self {
+        Self {
+            name,
+            start,
+            points: std::collections::VecDeque::from([start.as_dvec3()]),
+            values: std::collections::VecDeque::from([0.0]),
+            times: std::collections::VecDeque::from([0.0]),
+            length: 0.0,
+            color,
+            solid_color: false,
+            thickness: 1.0,
+            taper: Taper::None,
+            visible: true,
+            tail,
+            extent: 1.0,
+            next: None,
+            memory: None,
+            delay: None,
+            ftle: None,
+            step_size: 0.0,
+            state4: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.points.clear();
+        self.points.push_back(self.start.as_dvec3());
+        self.values.clear();
+        self.values.push_back(0.0);
+        self.times.clear();
+        self.times.push_back(0.0);
+        self.length = 0.0;
+        self.next = None;
+        self.step_size = 0.0;
+        self.memory = None;
+        self.delay = None;
+        self.ftle = None;
+        self.state4 = None;
+    }
+
+    /// Restarts the trail from `start` projected to `p`.
+    pub fn restart4(&mut self, start: macroquad::math::Vec4, p: macroquad::math::Vec3) {
+        self.reset();
+        self.points[0] = p.as_dvec3();
+        self.state4 = Some(start);
+    }
+
+    pub fn head(&self) -> macroquad::math::Vec3 {
+        self.head64().as_vec3()
+    }
+
+    pub fn head64(&self) -> macroquad::math::DVec3 {
+        *self.points.back().unwrap()
+    }
+
+    pub fn push(&mut self, p: macroquad::math::Vec3) {
+        self.push64(p.as_dvec3());
+    }
+
+    pub fn push64(&mut self, p: macroquad::math::DVec3) {
+        let tail = self.tail * self.extent;
+        let spacing = (tail / MAX_POINTS as f32) as f64;
+        let n = self.points.len();
+        if n >= 2 && self.points[n - 1].distance(self.points[n - 2]) < spacing {
+            let previous = self.points[n - 2];
+            self.length += (p.distance(previous) - self.points[n - 1].distance(previous)) as f32;
+            self.points[n - 1] = p;
+        } else {
+            self.length += p.distance(self.head64()) as f32;
+            self.points.push_back(p);
+            self.values
+                .push_back(self.values.back().copied().unwrap_or_default());
+            self.times
+                .push_back(self.times.back().copied().unwrap_or_default());
+        }
+        while self.points.len() > 2 {
+            let first = self.points[0].distance(self.points[1]) as f32;
+            if self.length - first < tail && self.points.len() <= MAX_POINTS {
+                break;
+            }
+            self.length -= first;
+            self.points.pop_front();
+            self.values.pop_front();
+            self.times.pop_front();
+        }
+    }
+
+    /// Moves the time of the newest point `h` past the one it was stepped from.
+    pub fn advance_time(&mut self, h: f32) {
+        if let Some(last) = self.times.back_mut() {
+            *last += h.abs() as f64;
+        }
+    }
+
+    pub fn set_head_value(&mut self, value: f32) {
+        if let Some(last) = self.values.back_mut() {
+            *last = value;
+        }
+    }
+
+    pub fn look_ahead(&mut self, f: impl Fn(&macroquad::math::Vec3) -> macroquad::math::Vec3) {
+        self.next = Some(f(&self.head()));
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        style: &Style,
+        head_fraction: f32,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let pixel_size = 2.0 * (camera.fovy / 2.0).tan() / macroquad::window::screen_height();
+        let mut batch = Batch::new();
+        let head = self
+            .next
+            .map(|next| (self.head(), self.head().lerp(next, head_fraction)));
+        let total = self.length.max(f32::EPSILON);
+        let mut travelled = 0.0;
+        let head_value = self.values.back().copied().unwrap_or_default();
+        let points = self.points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>();
+        let range = |values: &mut dyn Iterator<Item = f32>| {
+            values.fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+        };
+        let range = match style.coloring {
+            Coloring::Fourth => range(&mut self.values.iter().copied()),
+            Coloring::Height => range(&mut points.iter().map(|p| p.z)),
+            _ => (0.0, 1.0),
+        };
+        let normalized = |v: f32| ((v - range.0) / (range.1 - range.0).max(1e-6)).clamp(0.0, 1.0);
+        let subdivisions = if style.scatter {
+            1
+        } else {
+            style.subdivisions.max(1)
+        };
+        let kept = decimate(points.len().min(self.values.len()), style.max_points);
+        (1..kept.len())
+            .flat_map(|k| {
+                let (i, j) = (kept[k - 1], kept[k]);
+                let (start, end) = (points[i], points[j]);
+                let before = points[kept[k.saturating_sub(2)]];
+                let after = kept.get(k + 1).map_or(end, |&n| points[n]);
+                let curve = move |t: usize| {
+                    hermite(before, start, end, after, t as f32 / subdivisions as f32)
+                };
+                // The chord per stored step, so speed colors do not change where thinned out.
+                let chord = (end - start) / (j - i) as f32;
+                let value = self.values[j];
+                (0..subdivisions).map(move |t| (curve(t), curve(t + 1), chord, value))
+            })
+            .chain(head.map(|(start, end)| (start, end, end - start, head_value)))
+            .for_each(|(start, end, chord, value)| {
+                let age = (travelled / total).min(1.0);
+                travelled += start.distance(end);
+                let alpha = style.alpha.sample(age);
+                let taper = self.taper.factor(age);
+                let color = if self.solid_color {
+                    self.color.with_alpha(self.color.a * alpha)
+                } else {
+                    let t = match style.coloring {
+                        Coloring::Speed => chord.length().clamp(0.0, 2.0) / 2.0,
+                        Coloring::Ftle => (value / style.ftle_scale * 0.5 + 0.5).clamp(0.0, 1.0),
+                        Coloring::Fourth => normalized(value),
+                        Coloring::Height => normalized(end.z),
+                        Coloring::Age => age,
+                        Coloring::Index => style.index,
+                    };
+                    style.colormap.sample(t).with_alpha(alpha)
+                };
+                let start = transform(start);
+                let end = transform(end);
+                if style.scatter {
+                    batch.dot(
+                        end,
+                        color,
+                        self.thickness * style.line_width * style.point_size,
+                        camera,
+                        pixel_size,
+                    );
+                    return;
+                }
+                if style.glow > 0.0 {
+                    batch.segment(
+                        start,
+                        end,
+                        color.with_alpha(color.a * style.glow * 0.15),
+                        (self.thickness * style.line_width + 6.0) * taper,
+                        camera,
+                        pixel_size,
+                    );
+                }
+                let thickness = self.thickness * style.line_width * taper;
+                match style.lighting {
+                    Some(lighting) if lighting.shape == crate::lighting::Shape::Tube => batch
+                        .round_tube(start, end, color, lighting.radius * taper, camera, lighting),
+                    Some(lighting) if thickness > 1.0 => {
+                        batch.tube(start, end, color, thickness, camera, pixel_size, lighting)
+                    }
+                    _ => batch.segment(start, end, color, thickness, camera, pixel_size),
+                }
+            });
+        batch.flush();
+    }
+}
+
+/// Indices of the `len` stored points to draw: all of them when within `budget`, otherwise the
+/// newest half of the budget and every k-th older point, with k chosen to fit the other half.
+fn decimate(len: usize, budget: usize) -> Vec<usize> {
+    if len <= budget || budget < 4 {
+        return (0..len).collect();
+    }
+    let recent = budget / 2;
+    let older = len - recent;
+    let stride = older.div_ceil(budget - recent);
+    (0..older).step_by(stride).chain(older..len).collect()
+}
+
+/// Point at `t` on the segment from `p1` to `p2`, with Catmull-Rom tangents from the neighbours
+/// `p0` and `p3`.
+fn hermite(
+    p0: macroquad::math::Vec3,
+    p1: macroquad::math::Vec3,
+    p2: macroquad::math::Vec3,
+    p3: macroquad::math::Vec3,
+    t: f32,
+) -> macroquad::math::Vec3 {
+    let (m1, m2) = ((p2 - p0) / 2.0, (p3 - p1) / 2.0);
+    let (t2, t3) = (t * t, t * t * t);
+    p1 * (2.0 * t3 - 3.0 * t2 + 1.0)
+        + m1 * (t3 - 2.0 * t2 + t)
+        + p2 * (-2.0 * t3 + 3.0 * t2)
+        + m2 * (t3 - t2)
+}
+
+/// Every segment, dot and tube of one trail collected into as few meshes as possible, instead
+/// of a draw call per segment. Lines are camera facing quads at least a pixel wide.
+struct Batch {
+    mesh: macroquad::models::Mesh,
+}
+
+impl Batch {
+    fn new() -> Self {
+        Self {
+            mesh: macroquad::models::Mesh {
+                vertices: Vec::new(),
+                indices: Vec::new(),
+                texture: None,
+            },
+        }
+    }
+
+    fn reserve(&mut self, vertices: usize, indices: usize) -> u16 {
+        if self.mesh.vertices.len() + vertices > BATCH_VERTICES
+            || self.mesh.indices.len() + indices > BATCH_INDICES
+        {
+            self.flush();
+        }
+        self.mesh.vertices.len() as u16
+    }
+
+    fn vertex(&mut self, p: macroquad::math::Vec3, color: macroquad::color::Color) {
+        self.mesh.vertices.push(macroquad::models::Vertex::new2(
+            p,
+            macroquad::math::Vec2::ZERO,
+            color,
+        ));
+    }
+
+    fn parallelogram(
+        &mut self,
+        offset: macroquad::math::Vec3,
+        e1: macroquad::math::Vec3,
+        e2: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+    ) {
+        let i = self.reserve(4, 6);
+        for p in [offset, offset + e1, offset + e1 + e2, offset + e2] {
+            self.vertex(p, color);
+        }
+        self.mesh
+            .indices
+            .extend_from_slice(&[i, i + 1, i + 2, i, i + 2, i + 3]);
+    }
+
+    fn segment(
+        &mut self,
+        start: macroquad::math::Vec3,
+        end: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+        thickness: f32,
+        camera: &macroquad::camera::Camera3D,
+        pixel_size: f32,
+    ) {
+        let middle = (start + end) / 2.0;
+        let to_camera = camera.position - middle;
+        let width = thickness.max(1.0) * to_camera.length() * pixel_size;
+        let side = (end - start).cross(to_camera).normalize_or_zero() * width;
+        self.parallelogram(start - side / 2.0, end - start, side, color);
+    }
+
+    fn dot(
+        &mut self,
+        p: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+        size: f32,
+        camera: &macroquad::camera::Camera3D,
+        pixel_size: f32,
+    ) {
+        let to_camera = camera.position - p;
+        let width = size * to_camera.length() * pixel_size;
+        let right = to_camera.cross(camera.up).normalize_or_zero() * width;
+        let up = right.cross(to_camera).normalize_or_zero() * width;
+        self.parallelogram(p - (right + up) / 2.0, right, up, color);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tube(
+        &mut self,
+        start: macroquad::math::Vec3,
+        end: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+        thickness: f32,
+        camera: &macroquad::camera::Camera3D,
+        pixel_size: f32,
+        lighting: &crate::lighting::Lighting,
+    ) {
+        let base = self.reserve(6, 12);
+        let middle = (start + end) / 2.0;
+        let to_camera = camera.position - middle;
+        let width = thickness * to_camera.length() * pixel_size;
+        let view = to_camera.normalize_or_zero();
+        let side = (end - start).cross(to_camera).normalize_or_zero();
+        for (offset, normal) in [(-0.5, -side), (0.0, view), (0.5, side)] {
+            let color = lighting.shade(color, normal, view);
+            for p in [start, end] {
+                self.vertex(p + side * width * offset, color);
+            }
+        }
+        for quad in [0, 2] {
+            let i = base + quad;
+            self.mesh
+                .indices
+                .extend_from_slice(&[i, i + 1, i + 3, i, i + 3, i + 2]);
+        }
+    }
+
+    fn round_tube(
+        &mut self,
+        start: macroquad::math::Vec3,
+        end: macroquad::math::Vec3,
+        color: macroquad::color::Color,
+        radius: f32,
+        camera: &macroquad::camera::Camera3D,
+        lighting: &crate::lighting::Lighting,
+    ) {
+        let axis = (end - start).normalize_or_zero();
+        if axis == macroquad::math::Vec3::ZERO {
+            return;
+        }
+        let sides = TUBE_SIDES as usize;
+        let base = self.reserve(2 * sides, 6 * sides);
+        let (u, w) = axis.any_orthonormal_pair();
+        let view = (camera.position - (start + end) / 2.0).normalize_or_zero();
+        for k in 0..sides {
+            let angle = std::f32::consts::TAU * k as f32 / sides as f32;
+            let normal = u * angle.cos() + w * angle.sin();
+            let color = lighting.shade(color, normal, view);
+            for p in [start, end] {
+                self.vertex(p + normal * radius, color);
+            }
+        }
+        for k in 0..TUBE_SIDES {
+            let (i, j) = (base + 2 * k, base + 2 * ((k + 1) % TUBE_SIDES));
+            self.mesh
+                .indices
+                .extend_from_slice(&[i, i + 1, j + 1, i, j + 1, j]);
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.mesh.indices.is_empty() {
+            macroquad::models::draw_mesh(&self.mesh);
+        }
+        self.mesh.vertices.clear();
+        self.mesh.indices.clear();
+    }
+}
@@ -0,0 +1,168 @@
+const RESOLUTION: usize = 48;
+const REFRESH_FRAMES: usize = 15;
+const MARGIN: f32 = 0.1;
+const MAX_OUTSIDE: f32 = 0.2;
+
+/// Three dimensional histogram of where the first trajectory has been since the last reset,
+/// drawn as a stack of translucent slices across the axis closest to the view direction, so
+/// the invariant measure shows rather than just the recent tail.
+pub struct Volume {
+    pub visible: bool,
+    pub opacity: f32,
+    bins: Vec<f32>,
+    outside: f32,
+    bounds: Option<(macroquad::math::Vec3, macroquad::math::Vec3)>,
+    frames: usize,
+    slices: Vec<[macroquad::texture::Texture2D; 3]>,
+}
+
+impl Volume {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            opacity: 0.15,
+            bins: vec![0.0; RESOLUTION * RESOLUTION * RESOLUTION],
+            outside: 0.0,
+            bounds: None,
+            frames: 0,
+            slices: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bins.fill(0.0);
+        self.outside = 0.0;
+        self.bounds = None;
+    }
+
+    fn index(i: usize, j: usize, k: usize) -> usize {
+        (k * RESOLUTION + j) * RESOLUTION + i
+    }
+
+    pub fn observe(&mut self, p: macroquad::math::Vec3) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        let cell = (p - min) / (max - min) * RESOLUTION as f32;
+        if cell.min_element() < 0.0 || cell.max_element() >= RESOLUTION as f32 {
+            self.outside += 1.0;
+            return;
+        }
+        self.bins[Self::index(cell.x as usize, cell.y as usize, cell.z as usize)] += 1.0;
+    }
+
+    /// Refits the grid to `points` when needed and periodically redraws the slice textures.
+    pub fn update(
+        &mut self,
+        points: &std::collections::VecDeque<macroquad::math::DVec3>,
+        colormap: crate::palette::Colormap,
+    ) {
+        let total = self.bins.iter().sum::<f32>();
+        if self.bounds.is_none() || self.outside > MAX_OUTSIDE * (total + self.outside) {
+            self.clear();
+            let trail = points.iter().map(|p| p.as_vec3()).collect::<Vec<_>>();
+            if trail.len() < 2 {
+                return;
+            }
+            let (min, max) = crate::analysis::bounds(&trail);
+            let margin = (max - min).max(macroquad::math::Vec3::splat(1e-3)) * MARGIN;
+            self.bounds = Some((min - margin, max + margin));
+        }
+        self.frames += 1;
+        if self.frames < REFRESH_FRAMES && !self.slices.is_empty() {
+            return;
+        }
+        self.frames = 0;
+        if self.slices.is_empty() {
+            let texture = || {
+                let texture = macroquad::texture::Texture2D::from_image(
+                    &macroquad::texture::Image::gen_image_color(
+                        RESOLUTION as u16,
+                        RESOLUTION as u16,
+                        macroquad::color::BLANK,
+                    ),
+                );
+                texture.set_filter(macroquad::texture::FilterMode::Linear);
+                texture
+            };
+            self.slices = (0..RESOLUTION)
+                .map(|_| [texture(), texture(), texture()])
+                .collect();
+        }
+        let peak = self.bins.iter().copied().fold(0.0, f32::max).ln_1p();
+        let mut image = macroquad::texture::Image::gen_image_color(
+            RESOLUTION as u16,
+            RESOLUTION as u16,
+            macroquad::color::BLANK,
+        );
+        for axis in 0..3 {
+            for s in 0..RESOLUTION {
+                for v in 0..RESOLUTION {
+                    for u in 0..RESOLUTION {
+                        let index = match axis {
+                            0 => Self::index(s, u, v),
+                            1 => Self::index(u, s, v),
+                            _ => Self::index(u, v, s),
+                        };
+                        let t = if peak > 0.0 {
+                            self.bins[index].ln_1p() / peak
+                        } else {
+                            0.0
+                        };
+                        let color = colormap.sample(t).with_alpha(t);
+                        image.set_pixel(u as u32, v as u32, color);
+                    }
+                }
+                self.slices[s][axis].update(&image);
+            }
+        }
+    }
+
+    pub fn draw(
+        &self,
+        camera: &macroquad::camera::Camera3D,
+        transform: impl Fn(macroquad::math::Vec3) -> macroquad::math::Vec3,
+    ) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        if self.slices.is_empty() {
+            return;
+        }
+        let forward = camera.target - camera.position;
+        let axis = (0..3)
+            .max_by(|a, b| forward[*a].abs().total_cmp(&forward[*b].abs()))
+            .unwrap();
+        let (a, b) = match axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+        let color = macroquad::color::Color::new(1.0, 1.0, 1.0, self.opacity);
+        let back_to_front = if forward[axis] > 0.0 {
+            (0..RESOLUTION).rev().collect::<Vec<_>>()
+        } else {
+            (0..RESOLUTION).collect()
+        };
+        for s in back_to_front {
+            let mut corner = min;
+            corner[axis] += (max[axis] - min[axis]) * (s as f32 + 0.5) / RESOLUTION as f32;
+            let vertex = |du: f32, dv: f32| {
+                let mut p = corner;
+                p[a] += (max[a] - min[a]) * du;
+                p[b] += (max[b] - min[b]) * dv;
+                macroquad::models::Vertex::new2(transform(p), macroquad::math::vec2(du, dv), color)
+            };
+            macroquad::models::draw_mesh(&macroquad::models::Mesh {
+                vertices: vec![
+                    vertex(0.0, 0.0),
+                    vertex(1.0, 0.0),
+                    vertex(1.0, 1.0),
+                    vertex(0.0, 1.0),
+                ],
+                indices: vec![0, 1, 2, 0, 2, 3],
+                texture: Some(self.slices[s][axis].clone()),
+            });
+        }
+    }
+}
@@ -0,0 +1,110 @@
+const WINDOW: usize = 1000;
+const MIN_SAMPLES: usize = WINDOW / 2;
+const RECOMPUTE: usize = 25;
+const FREQUENCIES: usize = 32;
+
+/// Gottwald-Melbourne 0-1 test for chaos on the x coordinate of the first trajectory, sampled
+/// every `interval`. K tends to 1 for chaotic and to 0 for regular dynamics. The window slides
+/// across parameter changes, so K follows the sliders with a lag.
+pub struct ZeroOne {
+    pub enabled: bool,
+    pub interval: f32,
+    pub k: Option<f32>,
+    samples: std::collections::VecDeque<f32>,
+    since: f32,
+    pending: usize,
+}
+
+impl ZeroOne {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            interval: 0.2,
+            k: None,
+            samples: std::collections::VecDeque::new(),
+            since: 0.0,
+            pending: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.since = 0.0;
+        self.pending = 0;
+        self.k = None;
+    }
+
+    /// Fraction of the samples needed before the first estimate.
+    pub fn progress(&self) -> f32 {
+        (self.samples.len() as f32 / MIN_SAMPLES as f32).min(1.0)
+    }
+
+    pub fn observe(&mut self, p: macroquad::math::Vec3, h: f32) {
+        self.since += h.abs();
+        if self.since < self.interval {
+            return;
+        }
+        self.since -= self.interval;
+        self.samples.push_back(p.x);
+        if self.samples.len() > WINDOW {
+            self.samples.pop_front();
+        }
+        self.pending += 1;
+        if self.pending >= RECOMPUTE && self.samples.len() >= MIN_SAMPLES {
+            self.pending = 0;
+            let samples = self.samples.iter().map(|x| *x as f64).collect::<Vec<_>>();
+            self.k = Some(statistic(&samples) as f32);
+        }
+    }
+}
+
+/// Median over several frequencies of the correlation between n and the oscillation-corrected
+/// mean square displacement of the translation variables.
+pub fn statistic(phi: &[f64]) -> f64 {
+    let n = phi.len();
+    let cut = n / 10;
+    let mean = phi.iter().sum::<f64>() / n as f64;
+    let golden = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut ks = (0..FREQUENCIES)
+        .map(|i| {
+            let c = std::f64::consts::PI * (0.2 + 0.6 * ((i as f64 + 1.0) * golden).fract());
+            let (mut p, mut q) = (vec![0.0; n], vec![0.0; n]);
+            let (mut sp, mut sq) = (0.0, 0.0);
+            for (j, value) in phi.iter().enumerate() {
+                let angle = (j + 1) as f64 * c;
+                sp += value * angle.cos();
+                sq += value * angle.sin();
+                (p[j], q[j]) = (sp, sq);
+            }
+            let d = (1..=cut)
+                .map(|lag| {
+                    let m = (0..n - cut)
+                        .map(|j| (p[j + lag] - p[j]).powi(2) + (q[j + lag] - q[j]).powi(2))
+                        .sum::<f64>()
+                        / (n - cut) as f64;
+                    let lag = lag as f64;
+                    m - mean * mean * (1.0 - (lag * c).cos()) / (1.0 - c.cos())
+                })
+                .collect::<Vec<_>>();
+            correlation(d.iter().enumerate().map(|(lag, d)| ((lag + 1) as f64, *d)))
+        })
+        .filter(|k| k.is_finite())
+        .collect::<Vec<_>>();
+    if ks.is_empty() {
+        return 0.0;
+    }
+    ks.sort_by(f64::total_cmp);
+    ks[ks.len() / 2]
+}
+
+fn correlation(pairs: impl Iterator<Item = (f64, f64)> + Clone) -> f64 {
+    let count = pairs.clone().count() as f64;
+    let (mx, my) = pairs
+        .clone()
+        .fold((0.0, 0.0), |(x, y), (a, b)| (x + a / count, y + b / count));
+    let (sxy, sxx, syy) = pairs.fold((0.0, 0.0, 0.0), |(xy, xx, yy), (a, b)| {
+        let (a, b) = (a - mx, b - my);
+        (xy + a * b, xx + a * a, yy + b * b)
+    });
+    sxy / (sxx * syy).sqrt()
+}